@@ -206,6 +206,16 @@ task qirlib -depends init {
     }
 }
 
+task qirlib-serde -depends init {
+    Invoke-LoggedCommand -wd $pyqir.qirlib.dir -errorMessage "Please fix the above clippy errors" {
+        $extraArgs = (Test-CI) ? @("--", "-D", "warnings") : @()
+        cargo clippy --all-targets --features serde @("$($env:CARGO_EXTRA_ARGS)" -split " ") @extraArgs
+    }
+    Invoke-LoggedCommand -wd $pyqir.qirlib.dir {
+        cargo test --release --features serde @("$($env:CARGO_EXTRA_ARGS)" -split " ")
+    }
+}
+
 task metawheel {
     $wheelDir = Split-Path -Parent $wheelhouse
     if (!(Test-Path $wheelDir)) {
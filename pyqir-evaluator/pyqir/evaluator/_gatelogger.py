@@ -2,6 +2,7 @@
 # Licensed under the MIT License.
 
 from pyqir.evaluator._gateset import GateSet
+from typing import List, Optional
 
 
 class GateLogger(GateSet):
@@ -24,6 +25,28 @@ class GateLogger(GateSet):
     def cz(self, control: str, target: str):
         self.instructions.append(f"cz qubit[{control}], qubit[{target}]")
 
+    def ccx(self, control1: str, control2: str, target: str):
+        self.instructions.append(
+            f"ccx qubit[{control1}], qubit[{control2}], qubit[{target}]")
+
+    def ccz(self, control1: str, control2: str, target: str):
+        self.instructions.append(
+            f"ccz qubit[{control1}], qubit[{control2}], qubit[{target}]")
+
+    def swap(self, qubit1: str, qubit2: str):
+        self.instructions.append(f"swap qubit[{qubit1}], qubit[{qubit2}]")
+
+    def ctl(self, gate: str, controls: List[str], qubit: str, theta: Optional[float]):
+        self._controlled(f"{gate}_ctl", controls, qubit, theta)
+
+    def ctladj(self, gate: str, controls: List[str], qubit: str, theta: Optional[float]):
+        self._controlled(f"{gate}_ctladj", controls, qubit, theta)
+
+    def _controlled(self, name: str, controls: List[str], qubit: str, theta: Optional[float]):
+        operands = ", ".join(f"qubit[{control}]" for control in controls)
+        angle = "" if theta is None else f" theta[{theta}]"
+        self.instructions.append(f"{name}{angle} {operands} => qubit[{qubit}]")
+
     def h(self, target: str):
         self.instructions.append(f"h qubit[{target}]")
 
@@ -33,6 +56,11 @@ class GateLogger(GateSet):
     def mz(self, qubit: str, target: str):
         self.instructions.append(f"m qubit[{qubit}] => out[{target}]")
 
+    def measure(self, bases: List[str], qubits: List[str], target: str):
+        operands = ", ".join(
+            f"{basis} qubit[{qubit}]" for basis, qubit in zip(bases, qubits))
+        self.instructions.append(f"measure {operands} => out[{target}]")
+
     def reset(self, target: str):
         self.instructions.append(f"reset {target}")
 
@@ -1,6 +1,9 @@
 # Copyright (c) Microsoft Corporation.
 # Licensed under the MIT License.
 
+from typing import List, Optional
+
+
 class GateSet:
     """
     Defines the quantum circuit operations which may be registered for
@@ -13,6 +16,29 @@ class GateSet:
     def cz(self, control: str, target: str):
         pass
 
+    def ccx(self, control1: str, control2: str, target: str):
+        pass
+
+    def ccz(self, control1: str, control2: str, target: str):
+        pass
+
+    def swap(self, qubit1: str, qubit2: str):
+        pass
+
+    def ctl(self, gate: str, controls: List[str], qubit: str, theta: Optional[float]):
+        """
+        Applies the named gate to qubit controlled on every qubit in controls.
+        theta is the rotation angle for rx, ry and rz, and None otherwise.
+        """
+        pass
+
+    def ctladj(self, gate: str, controls: List[str], qubit: str, theta: Optional[float]):
+        """
+        Applies the adjoint of the named gate to qubit controlled on every
+        qubit in controls.
+        """
+        pass
+
     def h(self, target: str):
         pass
 
@@ -22,6 +48,13 @@ class GateSet:
     def mz(self, qubit: str, target: str):
         pass
 
+    def measure(self, bases: List[str], qubits: List[str], target: str):
+        """
+        Jointly measures the qubits in the product of the Pauli bases, which
+        are i, x, y or z, with one basis for each qubit.
+        """
+        pass
+
     def reset(self, target: str):
         pass
 
@@ -19,7 +19,8 @@ class NonadaptiveEvaluator:
              file_path: str,
              gateset: GateSet,
              entry_point: Optional[str] = None,
-             result_stream: Optional[List[bool]] = None):
+             result_stream: Optional[List[bool]] = None,
+             args: Optional[List[float]] = None):
         """
         JIT compiles and evaluates the QIR program, delegating quantum
         operations to the supplied gate set.
@@ -36,5 +37,6 @@ class NonadaptiveEvaluator:
         :param gateset: python GateSet based object defining the operations
         :param entry_point: entry point name; required if QIR contains multiple entry points
         :param result_stream: list of boolean result values representing the QIS measure results
+        :param args: values of the entry point's double parameters, in order
         """
-        self._jit.eval(file_path, gateset, entry_point, result_stream)
+        self._jit.eval(file_path, gateset, entry_point, result_stream, args)
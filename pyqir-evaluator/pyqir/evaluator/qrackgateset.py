@@ -2,6 +2,7 @@
 # Licensed under the MIT License.
 
 from pyqrack import QrackSimulator, Pauli
+from typing import List, Optional
 
 class QrackGateSet:
     """
@@ -25,12 +26,40 @@ class QrackGateSet:
     def cz(self, control: str, target: str):
         self._sim.mcz([self._checkAlloc(control)], self._checkAlloc(target))
 
+    def ccx(self, control1: str, control2: str, target: str):
+        self._sim.mcx([self._checkAlloc(control1), self._checkAlloc(control2)], self._checkAlloc(target))
+
+    def ccz(self, control1: str, control2: str, target: str):
+        self._sim.mcz([self._checkAlloc(control1), self._checkAlloc(control2)], self._checkAlloc(target))
+
+    def swap(self, qubit1: str, qubit2: str):
+        self._sim.swap(self._checkAlloc(qubit1), self._checkAlloc(qubit2))
+
     def h(self, target: str):
         self._sim.h(self._checkAlloc(target))
 
     def m(self, qubit: str, target: str):
         self._measurement_dict[target] = self._sim.m(self._checkAlloc(target))
 
+    def ctl(self, gate: str, controls: List[str], qubit: str, theta: Optional[float]):
+        self._controlled(gate, False, controls, qubit, theta)
+
+    def ctladj(self, gate: str, controls: List[str], qubit: str, theta: Optional[float]):
+        self._controlled(gate, True, controls, qubit, theta)
+
+    def _controlled(self, gate: str, adjoint: bool, controls: List[str], qubit: str, theta: Optional[float]):
+        c = [self._checkAlloc(control) for control in controls]
+        q = self._checkAlloc(qubit)
+        if theta is not None:
+            paulis = {"rx": Pauli.PauliX, "ry": Pauli.PauliY, "rz": Pauli.PauliZ}
+            self._sim.mcr(paulis[gate], -theta if adjoint else theta, c, q)
+        elif gate == "s":
+            (self._sim.mcadjs if adjoint else self._sim.mcs)(c, q)
+        elif gate == "t":
+            (self._sim.mcadjt if adjoint else self._sim.mct)(c, q)
+        else:
+            getattr(self._sim, f"mc{gate}")(c, q)
+
     def mz(self, qubit: str, target: str):
         self._measurement_dict[target] = self._sim.m(self._checkAlloc(target))
 
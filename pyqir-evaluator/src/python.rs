@@ -7,7 +7,10 @@ use pyo3::{
     types::{PyDict, PyList},
     PyAny,
 };
-use qirlib::evaluation::{interop::Instruction, jit::run_module_file};
+use qirlib::evaluation::{
+    interop::{ControlledGate, Gate, Instruction, Measure, Pauli},
+    jit::run_module_file_with_args,
+};
 
 #[pymodule]
 #[pyo3(name = "_native")]
@@ -32,6 +35,7 @@ impl PyNonadaptiveJit {
         pyobj: &PyAny,
         entry_point: Option<&str>,
         result_stream: Option<&PyList>,
+        args: Option<Vec<f64>>,
     ) -> PyResult<()> {
         fn controlled(pyobj: &PyAny, gate: &str, control: String, target: String) -> PyResult<()> {
             let has_gate = pyobj.hasattr(gate)?;
@@ -43,6 +47,54 @@ impl PyNonadaptiveJit {
             Ok(())
         }
 
+        fn doubly_controlled(
+            pyobj: &PyAny,
+            gate: &str,
+            control1: String,
+            control2: String,
+            target: String,
+        ) -> PyResult<()> {
+            let has_gate = pyobj.hasattr(gate)?;
+            if has_gate {
+                let func = pyobj.getattr(gate)?;
+                let args = (control1, control2, target);
+                func.call1(args)?;
+            }
+            Ok(())
+        }
+
+        fn swap(pyobj: &PyAny, qubit1: String, qubit2: String) -> PyResult<()> {
+            let has_gate = pyobj.hasattr("swap")?;
+            if has_gate {
+                let func = pyobj.getattr("swap")?;
+                let args = (qubit1, qubit2);
+                func.call1(args)?;
+            }
+            Ok(())
+        }
+
+        fn controlled_gate(pyobj: &PyAny, ins: ControlledGate) -> PyResult<()> {
+            let method = if ins.adjoint { "ctladj" } else { "ctl" };
+            let has_gate = pyobj.hasattr(method)?;
+            if has_gate {
+                let (gate, theta) = match ins.gate {
+                    Gate::H => ("h", None),
+                    Gate::S => ("s", None),
+                    Gate::T => ("t", None),
+                    Gate::X => ("x", None),
+                    Gate::Y => ("y", None),
+                    Gate::Z => ("z", None),
+                    Gate::Rx(theta) => ("rx", Some(theta)),
+                    Gate::Ry(theta) => ("ry", Some(theta)),
+                    Gate::Rz(theta) => ("rz", Some(theta)),
+                };
+                let func = pyobj.getattr(method)?;
+                let args = (gate, ins.controls, ins.qubit, theta);
+                func.call1(args)?;
+            }
+            Ok(())
+        }
+
         fn measured(pyobj: &PyAny, gate: &str, qubit: String, target: String) -> PyResult<()> {
             let has_gate = pyobj.hasattr(gate)?;
             if has_gate {
@@ -53,6 +105,26 @@ impl PyNonadaptiveJit {
             Ok(())
         }
 
+        fn measure(pyobj: &PyAny, ins: Measure, target: String) -> PyResult<()> {
+            let has_gate = pyobj.hasattr("measure")?;
+            if has_gate {
+                let bases: Vec<_> = ins
+                    .bases
+                    .iter()
+                    .map(|basis| match basis {
+                        Pauli::I => "i",
+                        Pauli::X => "x",
+                        Pauli::Y => "y",
+                        Pauli::Z => "z",
+                    })
+                    .collect();
+                let func = pyobj.getattr("measure")?;
+                let args = (bases, ins.qubits, target);
+                func.call1(args)?;
+            }
+            Ok(())
+        }
+
         fn single(pyobj: &PyAny, gate: &str, qubit: String) -> PyResult<()> {
             let has_gate = pyobj.hasattr(gate)?;
             if has_gate {
@@ -97,8 +169,9 @@ impl PyNonadaptiveJit {
             .map(|rs| rs.iter().map(PyAny::extract::<bool>).collect())
             .transpose()?;
 
-        let gen_model =
-            run_module_file(file, entry_point, result_vec).map_err(PyOSError::new_err)?;
+        let args = args.unwrap_or_default();
+        let gen_model = run_module_file_with_args(file, entry_point, &args, result_vec)
+            .map_err(PyOSError::new_err)?;
 
         Python::with_gil(|py| -> PyResult<()> {
             let mut current_register = 0;
@@ -106,6 +179,14 @@ impl PyNonadaptiveJit {
                 match instruction {
                     Instruction::Cx(ins) => controlled(pyobj, "cx", ins.control, ins.target)?,
                     Instruction::Cz(ins) => controlled(pyobj, "cz", ins.control, ins.target)?,
+                    Instruction::Ccx(ins) => {
+                        doubly_controlled(pyobj, "ccx", ins.control1, ins.control2, ins.target)?
+                    }
+                    Instruction::Ccz(ins) => {
+                        doubly_controlled(pyobj, "ccz", ins.control1, ins.control2, ins.target)?
+                    }
+                    Instruction::Swap(ins) => swap(pyobj, ins.qubit1, ins.qubit2)?,
+                    Instruction::ControlledGate(ins) => controlled_gate(pyobj, ins)?,
                     Instruction::H(ins) => single(pyobj, "h", ins.qubit)?,
                     Instruction::M(ins) => {
                         if ins.target.is_empty() {
@@ -115,6 +196,10 @@ impl PyNonadaptiveJit {
                             measured(pyobj, "mz", ins.qubit, ins.target)?;
                         }
                     }
+                    Instruction::Measure(ins) => {
+                        measure(pyobj, ins, current_register.to_string())?;
+                        current_register += 1;
+                    }
                     Instruction::Reset(ins) => reset(pyobj, ins.qubit)?,
                     Instruction::Rx(ins) => rotated(pyobj, "rx", ins.theta, ins.qubit)?,
                     Instruction::Ry(ins) => rotated(pyobj, "ry", ins.theta, ins.qubit)?,
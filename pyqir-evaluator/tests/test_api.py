@@ -2,6 +2,7 @@
 # Licensed under the MIT License.
 
 from pyqir.evaluator import GateLogger, NonadaptiveEvaluator
+import tempfile
 
 
 def test_bell_qir():
@@ -17,3 +18,70 @@ def test_bell_qir():
     assert str(logger.instructions[1]) == "cx qubit[0], qubit[1]"
     assert str(logger.instructions[2]) == "m qubit[0] => out[0]"
     assert str(logger.instructions[3]) == "m qubit[1] => out[1]"
+
+
+def test_entry_point_args():
+    content = """
+        ; ModuleID = 'entry_point_double_params'
+        source_filename = "entry_point_double_params"
+
+        %Qubit = type opaque
+
+        declare void @__quantum__qis__rx__body(double, %Qubit*)
+
+        define void @main(double %theta) #0 {
+        entry:
+            call void @__quantum__qis__rx__body(double %theta, %Qubit* null)
+            ret void
+        }
+
+        attributes #0 = { "EntryPoint" }
+    """
+
+    evaluator = NonadaptiveEvaluator()
+    logger = GateLogger()
+    with tempfile.NamedTemporaryFile("wt", suffix=".ll") as fd:
+        fd.write(content)
+        fd.flush()
+        evaluator.eval(fd.name, logger, args=[0.5])
+
+    assert logger.instructions == ["rx theta[0.5] qubit[0]"]
+
+
+def test_controlled_gates():
+    content = """
+        %Array = type opaque
+        %Qubit = type opaque
+
+        declare %Array* @__quantum__rt__array_create_1d(i32, i64)
+        declare i8* @__quantum__rt__array_get_element_ptr_1d(%Array*, i64)
+        declare void @__quantum__rt__array_update_reference_count(%Array*, i32)
+        declare void @__quantum__qis__x__ctl(%Array*, %Qubit*)
+        declare void @__quantum__qis__ry__ctladj(%Array*, double, %Qubit*)
+
+        define void @main() #0 {
+        entry:
+            %controls = call %Array* @__quantum__rt__array_create_1d(i32 8, i64 1)
+            %0 = call i8* @__quantum__rt__array_get_element_ptr_1d(%Array* %controls, i64 0)
+            %1 = bitcast i8* %0 to %Qubit**
+            store %Qubit* null, %Qubit** %1, align 8
+            call void @__quantum__qis__x__ctl(%Array* %controls, %Qubit* inttoptr (i64 1 to %Qubit*))
+            call void @__quantum__qis__ry__ctladj(%Array* %controls, double 0.5, %Qubit* inttoptr (i64 1 to %Qubit*))
+            call void @__quantum__rt__array_update_reference_count(%Array* %controls, i32 -1)
+            ret void
+        }
+
+        attributes #0 = { "EntryPoint" }
+    """
+
+    evaluator = NonadaptiveEvaluator()
+    logger = GateLogger()
+    with tempfile.NamedTemporaryFile("wt", suffix=".ll") as fd:
+        fd.write(content)
+        fd.flush()
+        evaluator.eval(fd.name, logger)
+
+    assert logger.instructions == [
+        "x_ctl qubit[0] => qubit[1]",
+        "ry_ctladj theta[0.5] qubit[0] => qubit[1]",
+    ]
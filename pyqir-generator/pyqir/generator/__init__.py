@@ -14,10 +14,20 @@ intended to be used as a framework for algorithm and application development.
 from pyqir.generator._native import (
     BasicQisBuilder as BasicQisBuilder,
     Builder as Builder,
+    ForRange as ForRange,
     Function as Function,
+    GenerationError as GenerationError,
     Qubit as Qubit,
+    ResultCondition as ResultCondition,
     ResultRef as ResultRef,
     SimpleModule as SimpleModule,
+    UninitializedResultError as UninitializedResultError,
+    UnknownFunctionError as UnknownFunctionError,
+    UnknownQubitError as UnknownQubitError,
+    UnknownResultError as UnknownResultError,
+    UnknownVariableError as UnknownVariableError,
+    Variable as Variable,
+    VerificationError as VerificationError,
     ir_to_bitcode as ir_to_bitcode,
     bitcode_to_ir as bitcode_to_ir,
 )
@@ -3,7 +3,15 @@
 
 from pyqir.generator import types
 from pyqir.generator._values import Value
-from typing import Callable, Optional, Sequence, Tuple
+from types import TracebackType
+from typing import Callable, Mapping, Optional, Sequence, Tuple, Type, Union
+
+Operand = Union[bool, int, float, "Variable"]
+"""
+An operand for an arithmetic or comparison instruction. Python values are
+converted to constants: ``bool`` becomes a 1-bit integer, ``int`` becomes a
+64-bit integer, and ``float`` becomes a double.
+"""
 
 
 def ir_to_bitcode(ir: str, module_name: Optional[str], source_file_name: Optional[str]) -> bytes:
@@ -29,13 +37,110 @@ def bitcode_to_ir(bitcode: bytes, module_name: Optional[str], source_file_name:
     ...
 
 
+class GenerationError(Exception):
+    """An error that occurred while generating QIR from a module."""
+    ...
+
+
+class UnknownQubitError(GenerationError):
+    """An instruction referred to a qubit that does not exist in the module."""
+    ...
+
+
+class UnknownResultError(GenerationError):
+    """An instruction referred to a result that does not exist in the module."""
+    ...
+
+
+class UninitializedResultError(GenerationError):
+    """A statically allocated result was read before it was initialized."""
+    ...
+
+
+class UnknownFunctionError(GenerationError):
+    """A call referred to a function that is not declared in the module."""
+    ...
+
+
+class UnknownVariableError(GenerationError):
+    """An instruction referred to a variable that is not in scope."""
+    ...
+
+
+class VerificationError(GenerationError):
+    """The generated QIR failed LLVM verification."""
+    ...
+
+
 class Qubit:
     """A qubit identifier."""
     ...
 
 
 class ResultRef:
-    """A mutable reference cell that holds a measurement result."""
+    """
+    A mutable reference cell that holds a measurement result.
+
+    Results can be combined into a condition for ``BasicQisBuilder.if_result``
+    with the ``&`` (and), ``|`` (or), ``^`` (exclusive or) and ``~`` (not)
+    operators.
+    """
+
+    def __and__(
+        self, other: Union[ResultRef, Variable, ResultCondition]
+    ) -> ResultCondition: ...
+
+    def __or__(
+        self, other: Union[ResultRef, Variable, ResultCondition]
+    ) -> ResultCondition: ...
+
+    def __xor__(
+        self, other: Union[ResultRef, Variable, ResultCondition]
+    ) -> ResultCondition: ...
+
+    def __invert__(self) -> ResultCondition: ...
+
+
+class ResultCondition:
+    """
+    A boolean expression over measurement results, where each result is true
+    if it is one. Conditions are built from results with the ``&``, ``|``,
+    ``^`` and ``~`` operators, and can be combined further in the same way.
+    Exclusive or over several results is their parity.
+    """
+
+    def __init__(self, result: Union[ResultRef, Variable, ResultCondition]) -> None:
+        """
+        Initializes a condition that is true if the result is one. This is
+        needed to use the operators on a result variable, such as a result
+        parameter of a subroutine.
+
+        :param result: The result.
+        """
+        ...
+
+    def __and__(
+        self, other: Union[ResultRef, Variable, ResultCondition]
+    ) -> ResultCondition: ...
+
+    def __or__(
+        self, other: Union[ResultRef, Variable, ResultCondition]
+    ) -> ResultCondition: ...
+
+    def __xor__(
+        self, other: Union[ResultRef, Variable, ResultCondition]
+    ) -> ResultCondition: ...
+
+    def __invert__(self) -> ResultCondition: ...
+
+
+class Variable:
+    """
+    An immutable classical value computed while the program runs, such as the
+    index of a loop or the result of an arithmetic instruction. A variable can
+    only be used inside the block that defines it and the blocks nested inside
+    it.
+    """
     ...
 
 
@@ -44,15 +149,248 @@ class Function:
     ...
 
 
+class ForRange:
+    """
+    A context manager that builds a counted loop. Instructions built inside the
+    ``with`` block become the body of the loop.
+    """
+
+    def __enter__(self) -> Variable:
+        """
+        Starts building the loop body.
+
+        :return: The loop index, an integer variable that counts up from zero.
+        """
+        ...
+
+    def __exit__(
+        self,
+        exc_type: Optional[Type[BaseException]],
+        exc_value: Optional[BaseException],
+        traceback: Optional[TracebackType],
+    ) -> bool:
+        """Finishes building the loop body and adds the loop to the builder."""
+        ...
+
+
 class Builder:
     """An instruction builder."""
 
-    def call(self, function: Function, args: Sequence[Value]) -> None:
+    def call(
+        self, function: Function, args: Sequence[Value]
+    ) -> Optional[Variable]:
         """
         Builds a call instruction.
 
         :param function: The function to call.
         :param args: The arguments to the function.
+        :return: A new variable holding the return value, or ``None`` if the
+                 function returns void.
+        """
+        ...
+
+    def for_range(self, count: int) -> ForRange:
+        """
+        Builds a loop that runs its body ``count`` times.
+
+        Use the returned value in a ``with`` statement. The instructions built
+        inside the ``with`` block become the body of the loop, and the value
+        bound by ``as`` is the loop index::
+
+            with builder.for_range(3) as i:
+                builder.call(f, [i])
+
+        :param count: The number of iterations.
+        :return: A context manager for building the loop body.
+        """
+        ...
+
+    def add(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds an addition.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, of the same type as ``lhs``.
+        :return: A new variable holding the result.
+        """
+        ...
+
+    def sub(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a subtraction.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, of the same type as ``lhs``.
+        :return: A new variable holding the result.
+        """
+        ...
+
+    def mul(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a multiplication.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, of the same type as ``lhs``.
+        :return: A new variable holding the result.
+        """
+        ...
+
+    def and_(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a bitwise and. Both operands must be integers.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, of the same type as ``lhs``.
+        :return: A new variable holding the result.
+        """
+        ...
+
+    def or_(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a bitwise or. Both operands must be integers.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, of the same type as ``lhs``.
+        :return: A new variable holding the result.
+        """
+        ...
+
+    def xor(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a bitwise exclusive or. Both operands must be integers.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, of the same type as ``lhs``.
+        :return: A new variable holding the result.
+        """
+        ...
+
+    def eq(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a comparison that is true if ``lhs`` is equal to ``rhs``.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, of the same type as ``lhs``.
+        :return: A new boolean variable holding the result.
+        """
+        ...
+
+    def ne(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a comparison that is true if ``lhs`` is not equal to ``rhs``.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, of the same type as ``lhs``.
+        :return: A new boolean variable holding the result.
+        """
+        ...
+
+    def lt(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a comparison that is true if ``lhs`` is less than ``rhs``.
+        Integers are compared as signed.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, of the same type as ``lhs``.
+        :return: A new boolean variable holding the result.
+        """
+        ...
+
+    def le(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a comparison that is true if ``lhs`` is less than or equal to ``rhs``.
+        Integers are compared as signed.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, of the same type as ``lhs``.
+        :return: A new boolean variable holding the result.
+        """
+        ...
+
+    def gt(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a comparison that is true if ``lhs`` is greater than ``rhs``.
+        Integers are compared as signed.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, of the same type as ``lhs``.
+        :return: A new boolean variable holding the result.
+        """
+        ...
+
+    def ge(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a comparison that is true if ``lhs`` is greater than or equal to ``rhs``.
+        Integers are compared as signed.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, of the same type as ``lhs``.
+        :return: A new boolean variable holding the result.
+        """
+        ...
+
+    def ult(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a comparison that is true if ``lhs`` is less than ``rhs``,
+        where both are integers compared as unsigned.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, an integer of the same type as ``lhs``.
+        :return: A new boolean variable holding the result.
+        """
+        ...
+
+    def ule(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a comparison that is true if ``lhs`` is less than or equal to ``rhs``,
+        where both are integers compared as unsigned.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, an integer of the same type as ``lhs``.
+        :return: A new boolean variable holding the result.
+        """
+        ...
+
+    def ugt(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a comparison that is true if ``lhs`` is greater than ``rhs``,
+        where both are integers compared as unsigned.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, an integer of the same type as ``lhs``.
+        :return: A new boolean variable holding the result.
+        """
+        ...
+
+    def uge(self, lhs: Operand, rhs: Operand) -> Variable:
+        """
+        Builds a comparison that is true if ``lhs`` is greater than or equal to ``rhs``,
+        where both are integers compared as unsigned.
+
+        :param lhs: The left operand.
+        :param rhs: The right operand, an integer of the same type as ``lhs``.
+        :return: A new boolean variable holding the result.
+        """
+        ...
+
+    def if_(
+        self,
+        cond: Variable,
+        true: Callable[[], None] = ...,
+        false: Callable[[], None] = ...,
+    ) -> None:
+        """
+        Builds a conditional branch on a boolean variable.
+
+        Evaluates the instructions built by ``true`` if the condition is true,
+        or the instructions built by ``false`` otherwise. The callables should
+        use this builder to build instructions.
+
+        :param cond: The boolean variable to branch on.
+        :param true: A callable that builds instructions for the branch where
+                     the condition is true.
+        :param false: A callable that builds instructions for the branch where
+                      the condition is false.
         """
         ...
 
@@ -65,7 +403,8 @@ class SimpleModule:
     - There is one global quantum register and one global classical register.
       Both are allocated automatically with a fixed size before the program
       starts.
-    - There is only a single function that runs as the entry point.
+    - The entry point named ``main`` uses the global registers. Additional
+      entry points added with ``add_entry_point`` have their own registers.
     """
 
     def __init__(
@@ -103,11 +442,19 @@ class SimpleModule:
         ...
 
     def ir(self) -> str:
-        """Emits the LLVM IR for the module as plain text."""
+        """
+        Emits the LLVM IR for the module as plain text.
+
+        :raises GenerationError: If the module is invalid.
+        """
         ...
 
     def bitcode(self) -> bytes:
-        """Emits the LLVM bitcode for the module as a sequence of bytes."""
+        """
+        Emits the LLVM bitcode for the module as a sequence of bytes.
+
+        :raises GenerationError: If the module is invalid.
+        """
         ...
 
     def add_external_function(self, name: str, ty: types.Function) -> Function:
@@ -120,6 +467,64 @@ class SimpleModule:
         """
         ...
 
+    def add_subroutine(
+        self,
+        name: str,
+        param_types: Sequence[types.Value],
+        body: Callable[..., None],
+    ) -> Function:
+        """
+        Adds a subroutine to the module. The subroutine can be called with
+        ``Builder.call``, from the entry point or from another subroutine.
+
+        The body callable is called once with a variable for each parameter,
+        and should use the module's builder to build the instructions of the
+        subroutine. Qubit and result parameters can be used wherever a qubit or
+        result is expected. The body can only use its own parameters, not the
+        qubits and results of the module.
+
+        :param name: The name of the subroutine.
+        :param param_types: The types of the parameters.
+        :param body: A callable that builds the body of the subroutine.
+        :return: The function value.
+        """
+        ...
+
+    def add_parameter(self, name: str) -> Variable:
+        """
+        Adds a double parameter to the ``main`` entry point. The value of the
+        parameter is supplied when the program is run, so a parametric circuit
+        can be run with different angles without being generated again.
+
+        :param name: The name of the parameter.
+        :return: A variable for the parameter, which can be used as a rotation
+                 angle or as an operand.
+        """
+        ...
+
+    def add_entry_point(
+        self,
+        name: str,
+        num_qubits: int,
+        num_results: int,
+        body: Callable[[Tuple[Qubit, ...], Tuple[ResultRef, ...]], None],
+    ) -> None:
+        """
+        Adds another entry point to the module, with its own quantum and
+        classical registers.
+
+        The body callable is called once with the qubits and results of the
+        entry point's registers, and should use the module's builder to build
+        the instructions of the entry point. The body can only use its own
+        registers, not the global registers of the module.
+
+        :param name: The name of the entry point.
+        :param num_qubits: The size of the entry point's quantum register.
+        :param num_results: The size of the entry point's classical register.
+        :param body: A callable that builds the body of the entry point.
+        """
+        ...
+
     def use_static_qubit_alloc(self, value: bool):
         """
         Configures code generation to use static or dynamic qubit allocation
@@ -140,6 +545,98 @@ class SimpleModule:
         """
         ...
 
+    def use_array_qubit_alloc(self, value: bool):
+        """
+        Configures dynamic qubit allocation to allocate and release the
+        qubits of each register together as an array, rather than one qubit
+        at a time. Has no effect with static qubit allocation. Default is
+        `False`.
+
+        :param value: The value indicating to use array allocation (`True`)
+                      or single qubit allocation (`False`)
+        """
+        ...
+
+    def use_profile(self, profile: Optional[str]):
+        """
+        Configures code generation to target a QIR profile. The module gets
+        the QIR version and resource management module flags, and entry
+        points get the `qir_profiles` attribute. Generating the module fails
+        if it contains instructions or dynamic allocation that the profile
+        does not allow. Default is `None`, which targets no particular
+        profile.
+
+        :param profile: `"base"`, `"adaptive"` or `None`.
+        """
+        ...
+
+    def record_output(self, value: bool, label: Optional[str] = None):
+        """
+        Configures code generation to record the results at the end of each
+        entry point, using the output recording functions of the runtime.
+        Under the base profile, only the record of each result is written,
+        without the array and tuple records that group them. Default is
+        `False`.
+
+        :param value: Whether to record output.
+        :param label: An optional label for the results of the module.
+        """
+        ...
+
+    def use_interop_wrappers(self, value: bool):
+        """
+        Configures code generation to add an `InteropFriendly` wrapper for
+        each entry point, named after the entry point with an `__Interop`
+        suffix. The wrapper takes the parameters of the entry point followed
+        by a pointer to a byte buffer, calls the entry point, and writes the
+        final value of each result into the buffer as one byte. Wrappers
+        require static result allocation. Default is `False`.
+
+        :param value: Whether to add interop wrappers.
+        """
+        ...
+
+    def use_debug_info(self, value: bool):
+        """
+        Configures the builder to record the source location of the Python
+        code that builds each instruction, which is emitted as debug info.
+        Only instructions built afterwards have a location. Default is
+        `False`, so that the same program always emits the same IR.
+
+        :param value: Whether to record source locations.
+        """
+        ...
+
+    def set_entry_point_name(self, name: str):
+        """
+        Sets the name of the entry point function that runs the instructions
+        of the module. Default is `"main"`.
+
+        :param name: The name of the entry point.
+        """
+        ...
+
+    def add_entry_point_attribute(self, key: str, value: Optional[str] = None):
+        """
+        Adds a string attribute to every entry point, such as
+        `output_labeling_schema`.
+
+        :param key: The key of the attribute.
+        :param value: The value of the attribute. An attribute without a
+                      value is written with only its key.
+        """
+        ...
+
+    def add_metadata(self, name: str, values: Sequence[str]):
+        """
+        Adds a node of strings to the named metadata of the module, such as
+        `!name = !{!0}` with `!0 = !{!"value"}`.
+
+        :param name: The name of the metadata.
+        :param values: The strings in the node.
+        """
+        ...
+
 
 class BasicQisBuilder:
     """
@@ -154,7 +651,11 @@ class BasicQisBuilder:
         """
         ...
 
-    def cx(self, control: Qubit, target: Qubit) -> None:
+    def cx(
+        self,
+        control: Union[Qubit, Variable],
+        target: Union[Qubit, Variable],
+    ) -> None:
         """
         Builds a controlled Pauli :math:`X` gate.
 
@@ -163,7 +664,11 @@ class BasicQisBuilder:
         """
         ...
 
-    def cz(self, control: Qubit, target: Qubit) -> None:
+    def cz(
+        self,
+        control: Union[Qubit, Variable],
+        target: Union[Qubit, Variable],
+    ) -> None:
         """
         Builds a controlled Pauli :math:`Z` gate.
 
@@ -172,7 +677,88 @@ class BasicQisBuilder:
         """
         ...
 
-    def h(self, qubit: Qubit) -> None:
+    def ccx(
+        self,
+        control1: Union[Qubit, Variable],
+        control2: Union[Qubit, Variable],
+        target: Union[Qubit, Variable],
+    ) -> None:
+        """
+        Builds a Toffoli (doubly controlled Pauli :math:`X`) gate.
+
+        :param control1: The first control qubit.
+        :param control2: The second control qubit.
+        :param target: The target qubit.
+        """
+        ...
+
+    def ccz(
+        self,
+        control1: Union[Qubit, Variable],
+        control2: Union[Qubit, Variable],
+        target: Union[Qubit, Variable],
+    ) -> None:
+        """
+        Builds a doubly controlled Pauli :math:`Z` gate.
+
+        :param control1: The first control qubit.
+        :param control2: The second control qubit.
+        :param target: The target qubit.
+        """
+        ...
+
+    def swap(
+        self,
+        qubit1: Union[Qubit, Variable],
+        qubit2: Union[Qubit, Variable],
+    ) -> None:
+        """
+        Builds a SWAP gate.
+
+        :param qubit1: The first qubit.
+        :param qubit2: The second qubit.
+        """
+        ...
+
+    def ctl(
+        self,
+        gate: str,
+        controls: Sequence[Union[Qubit, Variable]],
+        target: Union[Qubit, Variable],
+        theta: Optional[Union[float, Variable]] = None,
+    ) -> None:
+        """
+        Builds a gate controlled on any number of qubits. The controls are
+        passed to the gate as a runtime array, so it is not allowed by the
+        base profile.
+
+        :param gate: The name of the gate: h, s, t, x, y, z, rx, ry or rz.
+        :param controls: The control qubits.
+        :param target: The target qubit.
+        :param theta: The rotation angle, required for rx, ry and rz.
+        """
+        ...
+
+    def ctladj(
+        self,
+        gate: str,
+        controls: Sequence[Union[Qubit, Variable]],
+        target: Union[Qubit, Variable],
+        theta: Optional[Union[float, Variable]] = None,
+    ) -> None:
+        """
+        Builds the adjoint of a gate controlled on any number of qubits. The
+        controls are passed to the gate as a runtime array, so it is not
+        allowed by the base profile.
+
+        :param gate: The name of the gate: h, s, t, x, y, z, rx, ry or rz.
+        :param controls: The control qubits.
+        :param target: The target qubit.
+        :param theta: The rotation angle, required for rx, ry and rz.
+        """
+        ...
+
+    def h(self, qubit: Union[Qubit, Variable]) -> None:
         """
         Builds a Hadamard gate.
 
@@ -180,7 +766,11 @@ class BasicQisBuilder:
         """
         ...
 
-    def m(self, qubit: Qubit, result: ResultRef) -> None:
+    def m(
+        self,
+        qubit: Union[Qubit, Variable],
+        result: Union[ResultRef, Variable],
+    ) -> None:
         """
         Builds a measurement operation.
 
@@ -190,7 +780,29 @@ class BasicQisBuilder:
         """
         ...
 
-    def reset(self, qubit: Qubit) -> None:
+    def measure(
+        self,
+        bases: Sequence[str],
+        qubits: Sequence[Union[Qubit, Variable]],
+        result: Union[ResultRef, Variable],
+    ) -> None:
+        """
+        Builds a joint measurement of several qubits in a product of Pauli
+        bases, such as a stabilizer measurement::
+
+            qis.measure("zz", [q0, q1], r)
+
+        The measurement returns a new result, so it requires dynamic result
+        allocation, and is not allowed by the base profile.
+
+        :param bases: The Pauli basis for each qubit: i, x, y or z.
+        :param qubits: The qubits to measure.
+        :param result: A result reference where the measurement result will be
+                       written to.
+        """
+        ...
+
+    def reset(self, qubit: Union[Qubit, Variable]) -> None:
         """
         Builds a reset operation.
 
@@ -198,34 +810,46 @@ class BasicQisBuilder:
         """
         ...
 
-    def rx(self, theta: float, qubit: Qubit) -> None:
+    def rx(
+        self,
+        theta: Union[float, Variable],
+        qubit: Union[Qubit, Variable],
+    ) -> None:
         """
         Builds a rotation gate about the :math:`x` axis.
 
-        :param theta: The angle to rotate by.
+        :param theta: The angle to rotate by, a float or a double variable.
         :param qubit: The qubit to rotate.
         """
         ...
 
-    def ry(self, theta: float, qubit: Qubit) -> None:
+    def ry(
+        self,
+        theta: Union[float, Variable],
+        qubit: Union[Qubit, Variable],
+    ) -> None:
         """
         Builds a rotation gate about the :math:`y` axis.
 
-        :param theta: The angle to rotate by.
+        :param theta: The angle to rotate by, a float or a double variable.
         :param qubit: The qubit to rotate.
         """
         ...
 
-    def rz(self, theta: float, qubit: Qubit) -> None:
+    def rz(
+        self,
+        theta: Union[float, Variable],
+        qubit: Union[Qubit, Variable],
+    ) -> None:
         """
         Builds a rotation gate about the :math:`z` axis.
 
-        :param theta: The angle to rotate by.
+        :param theta: The angle to rotate by, a float or a double variable.
         :param qubit: The qubit to rotate.
         """
         ...
 
-    def s(self, qubit: Qubit) -> None:
+    def s(self, qubit: Union[Qubit, Variable]) -> None:
         """
         Builds an :math:`S` gate.
 
@@ -233,7 +857,7 @@ class BasicQisBuilder:
         """
         ...
 
-    def s_adj(self, qubit: Qubit) -> None:
+    def s_adj(self, qubit: Union[Qubit, Variable]) -> None:
         """
         Builds an adjoint :math:`S` gate.
 
@@ -241,7 +865,7 @@ class BasicQisBuilder:
         """
         ...
 
-    def t(self, qubit: Qubit) -> None:
+    def t(self, qubit: Union[Qubit, Variable]) -> None:
         """
         Builds a :math:`T` gate.
 
@@ -249,7 +873,7 @@ class BasicQisBuilder:
         """
         ...
 
-    def t_adj(self, qubit: Qubit) -> None:
+    def t_adj(self, qubit: Union[Qubit, Variable]) -> None:
         """
         Builds an adjoint :math:`T` gate.
 
@@ -257,7 +881,7 @@ class BasicQisBuilder:
         """
         ...
 
-    def x(self, qubit: Qubit) -> None:
+    def x(self, qubit: Union[Qubit, Variable]) -> None:
         """
         Builds a Pauli :math:`X` gate.
 
@@ -265,7 +889,7 @@ class BasicQisBuilder:
         """
         ...
 
-    def y(self, qubit: Qubit) -> None:
+    def y(self, qubit: Union[Qubit, Variable]) -> None:
         """
         Builds a Pauli :math:`Y` gate.
 
@@ -273,7 +897,7 @@ class BasicQisBuilder:
         """
         ...
 
-    def z(self, qubit: Qubit) -> None:
+    def z(self, qubit: Union[Qubit, Variable]) -> None:
         """
         Builds a Pauli :math:`Z` gate.
 
@@ -283,7 +907,7 @@ class BasicQisBuilder:
 
     def if_result(
         self,
-        result: ResultRef,
+        result: Union[ResultRef, Variable, ResultCondition],
         one: Callable[[], None] = ...,
         zero: Callable[[], None] = ...,
     ) -> None:
@@ -295,10 +919,55 @@ class BasicQisBuilder:
         ``zero`` if the result is zero. The one and zero callables should
         use this builder to build instructions.
 
-        :param result: The result to branch on.
+        The branch can also be on a condition over several results, such as
+        ``r0 & ~r1``, in which case ``one`` is built for the branch where the
+        condition is true and ``zero`` for the branch where it is false.
+
+        :param result: The result or result condition to branch on.
         :param one: A callable that builds instructions for the branch where
                     the result is one.
         :param zero: A callable that builds instructions for the branch where
                      the result is zero.
         """
         ...
+
+    def switch(
+        self,
+        results: Sequence[Union[ResultRef, Variable]],
+        cases: Mapping[int, Callable[[], None]],
+        default: Callable[[], None] = ...,
+    ) -> None:
+        """
+        Builds a multi-way branch on the value of several results.
+
+        Reads the results as the bits of an unsigned integer, with the first
+        result as the least significant bit, then evaluates the instructions
+        built by the case for that value, or by ``default`` if there is no
+        case for it. The callables should use this builder to build
+        instructions::
+
+            qis.switch(mod.results, {5: lambda: qis.x(q)})
+
+        :param results: The results to read, at most 64.
+        :param cases: A callable for each value that builds the instructions
+                      for that value.
+        :param default: A callable that builds instructions for every other
+                        value.
+        """
+        ...
+
+    def for_range(self, count: int) -> ForRange:
+        """
+        Builds a loop that runs its body ``count`` times.
+
+        Use the returned value in a ``with`` statement. The instructions built
+        inside the ``with`` block become the body of the loop, and the value
+        bound by ``as`` is the loop index::
+
+            with qis.for_range(3) as i:
+                qis.h(qubit)
+
+        :param count: The number of iterations.
+        :return: A context manager for building the loop body.
+        """
+        ...
@@ -1,10 +1,10 @@
 # Copyright (c) Microsoft Corporation.
 # Licensed under the MIT License.
 
-from pyqir.generator._native import Qubit, ResultRef
+from pyqir.generator._native import Qubit, ResultRef, Variable
 from typing import Union
 
-Value = Union[bool, int, float, Qubit, ResultRef]
+Value = Union[bool, int, float, str, Qubit, ResultRef, Variable]
 """
 A QIR or LLVM value, or a Python value that can be automatically converted into
 one.
@@ -39,7 +39,14 @@ class Result(Enum):
     _RESULT = auto()
 
 
-Value = Union[Integer, Double, Qubit, Result]
+class String(Enum):
+    """
+    A null-terminated string constant, represented by the LLVM type `i8*`.
+    """
+    _STRING = auto()
+
+
+Value = Union[Integer, Double, Qubit, Result, String]
 """The set of types that can represent a value."""
 
 Return = Union[Void, Value]
@@ -74,3 +81,6 @@ QUBIT: Qubit = Qubit._QUBIT
 
 RESULT: Result = Result._RESULT
 """The QIR result type, represented by the LLVM type `%Result*`."""
+
+STRING: String = String._STRING
+"""A null-terminated string constant, represented by the LLVM type `i8*`."""
@@ -3,16 +3,22 @@
 
 use pyo3::{
     basic::CompareOp,
-    exceptions::{PyOSError, PyOverflowError, PyTypeError, PyValueError},
+    create_exception,
+    exceptions::{PyException, PyOSError, PyOverflowError, PyTypeError, PyValueError},
     prelude::*,
-    types::{PyBytes, PySequence, PyString, PyUnicode},
-    PyObjectProtocol,
+    types::{
+        PyBool, PyBytes, PyDict, PyFloat, PyLong, PySequence, PyString, PyTuple, PyType, PyUnicode,
+    },
+    PyContextProtocol, PyNumberProtocol, PyObjectProtocol,
 };
 use qirlib::generation::{
-    emit,
+    emit, error,
     interop::{
-        Call, ClassicalRegister, Controlled, FunctionType, If, Instruction, IntegerValue, Measured,
-        QuantumRegister, ReturnType, Rotated, SemanticModel, Single, Value, ValueType,
+        BinaryKind, BinaryOp, Call, ClassicalRegister, Comparison, ComparisonKind, Condition,
+        Controlled, ControlledGate, DoublyControlled, EntryPoint, For, FunctionType, Gate, If,
+        Instruction, IntegerValue, Measure, Measured, NamedMetadata, Parameter, Pauli, Profile,
+        QuantumRegister, Reference, ReturnType, Rotated, SemanticModel, Single, SourceSpan,
+        Spanned, Subroutine, Swapped, Switch, SwitchCase, Value, ValueType,
     },
 };
 use std::{
@@ -47,13 +53,67 @@ fn bitcode_to_ir<'a>(
     Ok(PyUnicode::new(py, ir.as_str()))
 }
 
+create_exception!(generator, GenerationError, PyException);
+create_exception!(generator, UnknownQubitError, GenerationError);
+create_exception!(generator, UnknownResultError, GenerationError);
+create_exception!(generator, UninitializedResultError, GenerationError);
+create_exception!(generator, UnknownFunctionError, GenerationError);
+create_exception!(generator, UnknownVariableError, GenerationError);
+create_exception!(generator, VerificationError, GenerationError);
+
+fn generation_err(err: &error::GenerationError) -> PyErr {
+    let message = err.to_string();
+    match err {
+        error::GenerationError::UnknownQubit(_) => UnknownQubitError::new_err(message),
+        error::GenerationError::UnknownResult(_) => UnknownResultError::new_err(message),
+        error::GenerationError::UninitializedResult(_) => {
+            UninitializedResultError::new_err(message)
+        }
+        error::GenerationError::UnknownFunction(_) => UnknownFunctionError::new_err(message),
+        error::GenerationError::UnknownVariable(_) => UnknownVariableError::new_err(message),
+        error::GenerationError::InvalidOperands(_)
+        | error::GenerationError::InvalidCondition(_)
+        | error::GenerationError::SwitchTooWide(_)
+        | error::GenerationError::VoidReturn(_)
+        | error::GenerationError::FunctionExists(_)
+        | error::GenerationError::AmbiguousReference(_)
+        | error::GenerationError::StaticMeasure(_)
+        | error::GenerationError::MeasureParameter(_)
+        | error::GenerationError::InteropDynamicResults
+        | error::GenerationError::NulInString(_)
+        | error::GenerationError::NotInProfile(_)
+        | error::GenerationError::ModuleFlagMismatch(_) => GenerationError::new_err(message),
+        error::GenerationError::Verification(_) => VerificationError::new_err(message),
+    }
+}
+
 #[pymodule]
 #[pyo3(name = "_native")]
-fn native_module(_py: Python, m: &PyModule) -> PyResult<()> {
+fn native_module(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add("GenerationError", py.get_type::<GenerationError>())?;
+    m.add("UnknownQubitError", py.get_type::<UnknownQubitError>())?;
+    m.add("UnknownResultError", py.get_type::<UnknownResultError>())?;
+    m.add(
+        "UninitializedResultError",
+        py.get_type::<UninitializedResultError>(),
+    )?;
+    m.add(
+        "UnknownFunctionError",
+        py.get_type::<UnknownFunctionError>(),
+    )?;
+    m.add(
+        "UnknownVariableError",
+        py.get_type::<UnknownVariableError>(),
+    )?;
+    m.add("VerificationError", py.get_type::<VerificationError>())?;
+
     m.add_class::<Qubit>()?;
     m.add_class::<ResultRef>()?;
+    m.add_class::<ResultCondition>()?;
+    m.add_class::<Variable>()?;
     m.add_class::<Function>()?;
     m.add_class::<Builder>()?;
+    m.add_class::<ForRange>()?;
     m.add_class::<SimpleModule>()?;
     m.add_class::<BasicQisBuilder>()?;
 
@@ -66,6 +126,7 @@ fn native_module(_py: Python, m: &PyModule) -> PyResult<()> {
 const TYPES_MODULE_NAME: &str = "pyqir.generator.types";
 const RESULT_NAME: &str = "result";
 const QUBIT_NAME: &str = "qubit";
+const VARIABLE_NAME: &str = "var";
 
 struct PyVoidType;
 
@@ -104,6 +165,14 @@ impl<'source> FromPyObject<'source> for PyResultType {
     }
 }
 
+struct PyStringType;
+
+impl<'source> FromPyObject<'source> for PyStringType {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        extract_sentinel(TYPES_MODULE_NAME, "String", ob).map(|()| PyStringType)
+    }
+}
+
 fn extract_sentinel(module_name: &str, type_name: &str, ob: &PyAny) -> PyResult<()> {
     let module: &str = ob.get_type().getattr("__module__")?.extract()?;
 
@@ -121,6 +190,7 @@ enum PyValueType {
     Double(PyDoubleType),
     Qubit(PyQubitType),
     Result(PyResultType),
+    String(PyStringType),
 }
 
 impl From<PyValueType> for ValueType {
@@ -130,6 +200,7 @@ impl From<PyValueType> for ValueType {
             PyValueType::Double(PyDoubleType) => ValueType::Double,
             PyValueType::Qubit(PyQubitType) => ValueType::Qubit,
             PyValueType::Result(PyResultType) => ValueType::Result,
+            PyValueType::String(PyStringType) => ValueType::String,
         }
     }
 }
@@ -171,8 +242,8 @@ struct Qubit {
 }
 
 impl Qubit {
-    fn id(&self) -> String {
-        format!("{}{}", QUBIT_NAME, self.index)
+    fn id(&self) -> Reference {
+        Reference::indexed(QUBIT_NAME.to_string(), self.index)
     }
 }
 
@@ -203,8 +274,8 @@ struct ResultRef {
 }
 
 impl ResultRef {
-    fn id(&self) -> String {
-        format!("{}{}", RESULT_NAME, self.index)
+    fn id(&self) -> Reference {
+        Reference::indexed(RESULT_NAME.to_string(), self.index)
     }
 }
 
@@ -228,6 +299,127 @@ impl PyObjectProtocol for ResultRef {
     }
 }
 
+#[pyproto]
+impl PyNumberProtocol for ResultRef {
+    fn __and__(lhs: &PyAny, rhs: &PyAny) -> PyResult<ResultCondition> {
+        ResultCondition::and(lhs, rhs)
+    }
+
+    fn __or__(lhs: &PyAny, rhs: &PyAny) -> PyResult<ResultCondition> {
+        ResultCondition::or(lhs, rhs)
+    }
+
+    fn __xor__(lhs: &PyAny, rhs: &PyAny) -> PyResult<ResultCondition> {
+        ResultCondition::xor(lhs, rhs)
+    }
+
+    fn __invert__(&self) -> ResultCondition {
+        ResultCondition {
+            condition: Condition::Not(Box::new(Condition::Result(self.id()))),
+        }
+    }
+}
+
+/// A boolean expression over results, built from results with the `&`, `|`, `^` and `~`
+/// operators.
+#[derive(Clone)]
+#[pyclass]
+struct ResultCondition {
+    condition: Condition,
+}
+
+#[pymethods]
+impl ResultCondition {
+    #[new]
+    fn new(result: &PyAny) -> PyResult<ResultCondition> {
+        Ok(ResultCondition {
+            condition: extract_condition(result)?,
+        })
+    }
+}
+
+impl ResultCondition {
+    fn and(lhs: &PyAny, rhs: &PyAny) -> PyResult<ResultCondition> {
+        let unwrap = |condition| match condition {
+            Condition::And(conditions) => Ok(conditions),
+            condition => Err(condition),
+        };
+        ResultCondition::join(lhs, rhs, unwrap, Condition::And)
+    }
+
+    fn or(lhs: &PyAny, rhs: &PyAny) -> PyResult<ResultCondition> {
+        let unwrap = |condition| match condition {
+            Condition::Or(conditions) => Ok(conditions),
+            condition => Err(condition),
+        };
+        ResultCondition::join(lhs, rhs, unwrap, Condition::Or)
+    }
+
+    fn xor(lhs: &PyAny, rhs: &PyAny) -> PyResult<ResultCondition> {
+        let unwrap = |condition| match condition {
+            Condition::Xor(conditions) => Ok(conditions),
+            condition => Err(condition),
+        };
+        ResultCondition::join(lhs, rhs, unwrap, Condition::Xor)
+    }
+
+    /// Joins two conditions with a connective. Operands that already use the same connective are
+    /// flattened, so that `r0 & r1 & r2` is a single `And` of three results.
+    fn join(
+        lhs: &PyAny,
+        rhs: &PyAny,
+        unwrap: fn(Condition) -> Result<Vec<Condition>, Condition>,
+        wrap: fn(Vec<Condition>) -> Condition,
+    ) -> PyResult<ResultCondition> {
+        let mut conditions = vec![];
+        for operand in [lhs, rhs] {
+            match unwrap(extract_condition(operand)?) {
+                Ok(nested) => conditions.extend(nested),
+                Err(condition) => conditions.push(condition),
+            }
+        }
+
+        Ok(ResultCondition {
+            condition: wrap(conditions),
+        })
+    }
+}
+
+#[pyproto]
+impl PyNumberProtocol for ResultCondition {
+    fn __and__(lhs: &PyAny, rhs: &PyAny) -> PyResult<ResultCondition> {
+        ResultCondition::and(lhs, rhs)
+    }
+
+    fn __or__(lhs: &PyAny, rhs: &PyAny) -> PyResult<ResultCondition> {
+        ResultCondition::or(lhs, rhs)
+    }
+
+    fn __xor__(lhs: &PyAny, rhs: &PyAny) -> PyResult<ResultCondition> {
+        ResultCondition::xor(lhs, rhs)
+    }
+
+    fn __invert__(&self) -> ResultCondition {
+        ResultCondition {
+            condition: Condition::Not(Box::new(self.condition.clone())),
+        }
+    }
+}
+
+#[derive(Clone)]
+#[pyclass]
+struct Variable {
+    name: String,
+    ty: ValueType,
+}
+
+#[pyproto]
+impl PyObjectProtocol for Variable {
+    fn __repr__(&self) -> String {
+        format!("<Variable {}>", self.name)
+    }
+}
+
 #[derive(Clone)]
 #[pyclass]
 struct Function {
@@ -238,6 +430,13 @@ struct Function {
 struct Builder {
     frames: Vec<Vec<Instruction>>,
     external_functions: HashMap<String, FunctionType>,
+    subroutines: Vec<Subroutine>,
+    entry_points: Vec<EntryPoint>,
+    next_variable: u64,
+
+    /// Whether instructions are annotated with the source location of the Python code that builds
+    /// them, which is emitted as debug info.
+    debug_info: bool,
 }
 
 #[pymethods]
@@ -247,12 +446,114 @@ impl Builder {
         Builder {
             frames: vec![vec![]],
             external_functions: HashMap::new(),
+            subroutines: vec![],
+            entry_points: vec![],
+            next_variable: 0,
+            debug_info: false,
+        }
+    }
+
+    fn for_range(slf: &PyCell<Self>, count: u64) -> ForRange {
+        ForRange::new(slf.into(), count)
+    }
+
+    fn add(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.binary_op(BinaryKind::Add, lhs, rhs)
+    }
+
+    fn sub(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.binary_op(BinaryKind::Sub, lhs, rhs)
+    }
+
+    fn mul(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.binary_op(BinaryKind::Mul, lhs, rhs)
+    }
+
+    fn and_(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.binary_op(BinaryKind::And, lhs, rhs)
+    }
+
+    fn or_(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.binary_op(BinaryKind::Or, lhs, rhs)
+    }
+
+    fn xor(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.binary_op(BinaryKind::Xor, lhs, rhs)
+    }
+
+    fn eq(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.comparison(ComparisonKind::Eq, lhs, rhs)
+    }
+
+    fn ne(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.comparison(ComparisonKind::Ne, lhs, rhs)
+    }
+
+    fn lt(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.comparison(ComparisonKind::Lt, lhs, rhs)
+    }
+
+    fn le(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.comparison(ComparisonKind::Le, lhs, rhs)
+    }
+
+    fn gt(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.comparison(ComparisonKind::Gt, lhs, rhs)
+    }
+
+    fn ge(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.comparison(ComparisonKind::Ge, lhs, rhs)
+    }
+
+    fn ult(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.comparison(ComparisonKind::ULt, lhs, rhs)
+    }
+
+    fn ule(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.comparison(ComparisonKind::ULe, lhs, rhs)
+    }
+
+    fn ugt(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.comparison(ComparisonKind::UGt, lhs, rhs)
+    }
+
+    fn uge(&mut self, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        self.comparison(ComparisonKind::UGe, lhs, rhs)
+    }
+
+    fn if_(
+        slf: &PyCell<Self>,
+        cond: Variable,
+        r#true: Option<&PyAny>,
+        r#false: Option<&PyAny>,
+    ) -> PyResult<()> {
+        if cond.ty != (ValueType::Integer { width: 1 }) {
+            let message = format!("Expected a boolean condition, got {:?}.", cond.ty);
+            return Err(PyErr::new::<PyTypeError, _>(message));
         }
+
+        let build_frame = |callback: Option<&PyAny>| -> PyResult<_> {
+            slf.borrow_mut().push_frame();
+            if let Some(callback) = callback {
+                callback.call0()?;
+            }
+
+            Ok(slf.borrow_mut().pop_frame().unwrap())
+        };
+
+        let if_inst = If {
+            condition: Condition::Variable(cond.name),
+            then_insts: build_frame(r#true)?,
+            else_insts: build_frame(r#false)?,
+        };
+
+        slf.borrow_mut().push_inst(Instruction::If(if_inst));
+        Ok(())
     }
 
-    fn call(&mut self, function: Function, args: &PySequence) -> PyResult<()> {
+    fn call(&mut self, function: Function, args: &PySequence) -> PyResult<Option<Variable>> {
         let name = function.name;
-        let ty = self.external_functions.get(&name).unwrap();
+        let ty = self.function_type(&name).unwrap();
         let num_params = ty.param_types.len();
         let num_args = args.len()?;
 
@@ -267,13 +568,39 @@ impl Builder {
             .map(|(arg, &ty)| extract_value(arg?, ty))
             .collect::<PyResult<_>>()?;
 
-        self.push_inst(Instruction::Call(Call { name, args }));
-        Ok(())
+        let result_type = match ty.return_type {
+            ReturnType::Void => None,
+            ReturnType::Value(ty) => Some(ty),
+        };
+        let result = result_type.map(|ty| self.fresh_variable(ty));
+
+        self.push_inst(Instruction::Call(Call {
+            name,
+            args,
+            result: result.as_ref().map(|r| r.name.clone()),
+        }));
+        Ok(result)
     }
 }
 
 impl Builder {
+    /// Pushes the instruction into the current frame. With debug info, the instruction is
+    /// annotated with the source location of the Python code that is building it.
     fn push_inst(&mut self, inst: Instruction) {
+        let span = if self.debug_info {
+            Python::with_gil(caller_span)
+        } else {
+            None
+        };
+
+        let inst = match span {
+            Some(span) => Instruction::Spanned(Spanned {
+                span,
+                inst: Box::new(inst),
+            }),
+            None => inst,
+        };
+
         self.frames.last_mut().unwrap().push(inst);
     }
 
@@ -284,6 +611,130 @@ impl Builder {
     fn pop_frame(&mut self) -> Option<Vec<Instruction>> {
         self.frames.pop()
     }
+
+    fn function_type(&self, name: &str) -> Option<FunctionType> {
+        self.external_functions.get(name).cloned().or_else(|| {
+            self.subroutines
+                .iter()
+                .find(|subroutine| subroutine.name == name)
+                .map(Subroutine::function_type)
+        })
+    }
+
+    fn fresh_variable(&mut self, ty: ValueType) -> Variable {
+        let name = format!("{}{}", VARIABLE_NAME, self.next_variable);
+        self.next_variable += 1;
+        Variable { name, ty }
+    }
+
+    fn binary_op(&mut self, kind: BinaryKind, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        let (lhs, lhs_type) = extract_operand(lhs)?;
+        let (rhs, rhs_type) = extract_operand(rhs)?;
+        let valid = lhs_type == rhs_type
+            && match (kind, lhs_type) {
+                (_, ValueType::Integer { .. })
+                | (BinaryKind::Add | BinaryKind::Sub | BinaryKind::Mul, ValueType::Double) => true,
+                _ => false,
+            };
+
+        if !valid {
+            let message = format!("Invalid operand types {:?} and {:?}.", lhs_type, rhs_type);
+            return Err(PyErr::new::<PyTypeError, _>(message));
+        }
+
+        let result = self.fresh_variable(lhs_type);
+        self.push_inst(Instruction::BinaryOp(BinaryOp {
+            kind,
+            lhs,
+            rhs,
+            result: result.name.clone(),
+        }));
+        Ok(result)
+    }
+
+    fn comparison(&mut self, kind: ComparisonKind, lhs: &PyAny, rhs: &PyAny) -> PyResult<Variable> {
+        let (lhs, lhs_type) = extract_operand(lhs)?;
+        let (rhs, rhs_type) = extract_operand(rhs)?;
+        let comparable = match lhs_type {
+            ValueType::Integer { .. } => true,
+            ValueType::Double => !kind.is_unsigned(),
+            ValueType::Qubit | ValueType::Result | ValueType::String => false,
+        };
+
+        if lhs_type != rhs_type || !comparable {
+            let message = format!("Invalid operand types {:?} and {:?}.", lhs_type, rhs_type);
+            return Err(PyErr::new::<PyTypeError, _>(message));
+        }
+
+        let result = self.fresh_variable(ValueType::Integer { width: 1 });
+        self.push_inst(Instruction::Comparison(Comparison {
+            kind,
+            lhs,
+            rhs,
+            result: result.name.clone(),
+        }));
+        Ok(result)
+    }
+}
+
+#[pyclass]
+struct ForRange {
+    builder: Py<Builder>,
+    count: u64,
+    index: Option<Variable>,
+}
+
+impl ForRange {
+    fn new(builder: Py<Builder>, count: u64) -> Self {
+        ForRange {
+            builder,
+            count,
+            index: None,
+        }
+    }
+}
+
+#[pyproto]
+impl<'p> PyContextProtocol<'p> for ForRange {
+    fn __enter__(&mut self) -> PyResult<Variable> {
+        if self.index.is_some() {
+            return Err(PyErr::new::<PyValueError, _>(
+                "Loop has already been entered.",
+            ));
+        }
+
+        Python::with_gil(|py| {
+            let mut builder = self.builder.as_ref(py).borrow_mut();
+            let index = builder.fresh_variable(ValueType::Integer { width: 64 });
+            builder.push_frame();
+            self.index = Some(index.clone());
+            Ok(index)
+        })
+    }
+
+    fn __exit__(
+        &mut self,
+        ty: Option<&'p PyType>,
+        _value: Option<&'p PyAny>,
+        _traceback: Option<&'p PyAny>,
+    ) -> bool {
+        Python::with_gil(|py| {
+            let mut builder = self.builder.as_ref(py).borrow_mut();
+            let body = builder.pop_frame().unwrap();
+            let index = self.index.take().unwrap();
+
+            // If the body raised an exception, discard the loop and let the exception propagate.
+            if ty.is_none() {
+                builder.push_inst(Instruction::For(For {
+                    index: index.name,
+                    count: self.count,
+                    body,
+                }));
+            }
+        });
+
+        false
+    }
 }
 
 #[pyclass]
@@ -305,11 +756,22 @@ impl SimpleModule {
         let model = SemanticModel {
             name,
             external_functions: HashMap::new(),
+            subroutines: vec![],
+            entry_points: vec![],
+            params: vec![],
             registers,
             qubits,
             instructions: Vec::new(),
             use_static_qubit_alloc: true,
             use_static_result_alloc: true,
+            use_array_qubit_alloc: false,
+            record_output: false,
+            profile: None,
+            entry_point_name: "main".to_string(),
+            entry_point_attributes: vec![],
+            metadata: vec![],
+            max_block_size: None,
+            interop_wrappers: false,
         };
 
         let builder = Py::new(py, Builder::new())?;
@@ -338,14 +800,14 @@ impl SimpleModule {
 
     fn ir(&self, py: Python) -> PyResult<String> {
         let model = self.model_from_builder(py);
-        emit::ir(&model).map_err(PyOSError::new_err)
+        emit::ir(&model).map_err(|e| generation_err(&e))
     }
 
     fn bitcode<'a>(&self, py: Python<'a>) -> PyResult<&'a PyBytes> {
         let model = self.model_from_builder(py);
         match emit::bitcode(&model) {
             Ok(bitcode) => Ok(PyBytes::new(py, &bitcode[..])),
-            Err(err) => Err(PyOSError::new_err(err)),
+            Err(err) => Err(generation_err(&err)),
         }
     }
 
@@ -355,6 +817,100 @@ impl SimpleModule {
         Function { name }
     }
 
+    fn add_subroutine(
+        &mut self,
+        py: Python,
+        name: String,
+        param_types: Vec<PyValueType>,
+        body: &PyAny,
+    ) -> PyResult<Function> {
+        let builder = self.builder.as_ref(py);
+        let params: Vec<_> = {
+            let mut builder = builder.borrow_mut();
+            let params: Vec<_> = param_types
+                .into_iter()
+                .map(|ty| builder.fresh_variable(ty.into()))
+                .collect();
+
+            // Add the subroutine before building its body so that it can call itself.
+            builder.subroutines.push(Subroutine {
+                name: name.clone(),
+                params: params
+                    .iter()
+                    .map(|param| Parameter::new(param.name.clone(), param.ty))
+                    .collect(),
+                instructions: vec![],
+            });
+            builder.push_frame();
+            params
+        };
+
+        let args: Vec<_> = params.into_iter().map(|param| param.into_py(py)).collect();
+        let result = body.call1(PyTuple::new(py, args));
+
+        let mut builder = builder.borrow_mut();
+        let instructions = builder.pop_frame().unwrap();
+        if let Err(err) = result {
+            builder
+                .subroutines
+                .retain(|subroutine| subroutine.name != name);
+            return Err(err);
+        }
+
+        let subroutine = builder
+            .subroutines
+            .iter_mut()
+            .find(|subroutine| subroutine.name == name)
+            .unwrap();
+        subroutine.instructions = instructions;
+        Ok(Function { name })
+    }
+
+    fn add_parameter(&mut self, name: String) -> Variable {
+        self.model
+            .params
+            .push(Parameter::new(name.clone(), ValueType::Double));
+        Variable {
+            name,
+            ty: ValueType::Double,
+        }
+    }
+
+    fn add_entry_point(
+        &mut self,
+        py: Python,
+        name: String,
+        num_qubits: u64,
+        num_results: u64,
+        body: &PyAny,
+    ) -> PyResult<()> {
+        let builder = self.builder.as_ref(py);
+        builder.borrow_mut().push_frame();
+
+        let qubits: Vec<_> = (0..num_qubits)
+            .map(|index| Qubit { index }.into_py(py))
+            .collect();
+        let results: Vec<_> = (0..num_results)
+            .map(|index| ResultRef { index }.into_py(py))
+            .collect();
+        let result = body.call1((PyTuple::new(py, qubits), PyTuple::new(py, results)));
+
+        let mut builder = builder.borrow_mut();
+        let instructions = builder.pop_frame().unwrap();
+        result?;
+
+        builder.entry_points.push(EntryPoint {
+            name,
+            params: vec![],
+            registers: vec![ClassicalRegister::new(RESULT_NAME.to_string(), num_results)],
+            qubits: (0..num_qubits)
+                .map(|index| QuantumRegister::new(QUBIT_NAME.to_string(), index))
+                .collect(),
+            instructions,
+        });
+        Ok(())
+    }
+
     fn use_static_qubit_alloc(&mut self, value: bool) {
         self.model.use_static_qubit_alloc = value;
     }
@@ -362,6 +918,50 @@ impl SimpleModule {
     fn use_static_result_alloc(&mut self, value: bool) {
         self.model.use_static_result_alloc = value;
     }
+
+    fn use_array_qubit_alloc(&mut self, value: bool) {
+        self.model.use_array_qubit_alloc = value;
+    }
+
+    fn use_profile(&mut self, profile: Option<&str>) -> PyResult<()> {
+        self.model.profile = match profile {
+            None => None,
+            Some("base") => Some(Profile::Base),
+            Some("adaptive") => Some(Profile::Adaptive),
+            Some(profile) => {
+                let message = format!("Unknown profile {}.", profile);
+                return Err(PyErr::new::<PyValueError, _>(message));
+            }
+        };
+        Ok(())
+    }
+
+    fn record_output(&mut self, value: bool, label: Option<String>) {
+        self.model.record_output = value;
+        self.model.registers[0].label = label;
+    }
+
+    fn use_interop_wrappers(&mut self, value: bool) {
+        self.model.interop_wrappers = value;
+    }
+
+    fn use_debug_info(&self, py: Python, value: bool) {
+        self.builder.as_ref(py).borrow_mut().debug_info = value;
+    }
+
+    fn set_entry_point_name(&mut self, name: String) {
+        self.model.entry_point_name = name;
+    }
+
+    fn add_entry_point_attribute(&mut self, key: String, value: Option<String>) {
+        self.model
+            .entry_point_attributes
+            .push((key, value.unwrap_or_default()));
+    }
+
+    fn add_metadata(&mut self, name: String, values: Vec<String>) {
+        self.model.metadata.push(NamedMetadata { name, values });
+    }
 }
 
 impl SimpleModule {
@@ -372,6 +972,8 @@ impl SimpleModule {
             [ref instructions] => SemanticModel {
                 instructions: instructions.clone(),
                 external_functions: builder.external_functions.clone(),
+                subroutines: builder.subroutines.clone(),
+                entry_points: builder.entry_points.clone(),
                 ..self.model.clone()
             },
             _ => panic!("Builder does not contain exactly one stack frame."),
@@ -391,88 +993,205 @@ impl BasicQisBuilder {
         BasicQisBuilder { builder }
     }
 
-    fn cx(&self, py: Python, control: &Qubit, target: &Qubit) {
-        let controlled = Controlled::new(control.id(), target.id());
+    fn cx(&self, py: Python, control: &PyAny, target: &PyAny) -> PyResult<()> {
+        let controlled = Controlled::new(extract_qubit(control)?, extract_qubit(target)?);
         self.push_inst(py, Instruction::Cx(controlled));
+        Ok(())
     }
 
-    fn cz(&self, py: Python, control: &Qubit, target: &Qubit) {
-        let controlled = Controlled::new(control.id(), target.id());
+    fn cz(&self, py: Python, control: &PyAny, target: &PyAny) -> PyResult<()> {
+        let controlled = Controlled::new(extract_qubit(control)?, extract_qubit(target)?);
         self.push_inst(py, Instruction::Cz(controlled));
+        Ok(())
+    }
+
+    fn ccx(&self, py: Python, control1: &PyAny, control2: &PyAny, target: &PyAny) -> PyResult<()> {
+        let controlled = DoublyControlled::new(
+            extract_qubit(control1)?,
+            extract_qubit(control2)?,
+            extract_qubit(target)?,
+        );
+        self.push_inst(py, Instruction::Ccx(controlled));
+        Ok(())
+    }
+
+    fn ccz(&self, py: Python, control1: &PyAny, control2: &PyAny, target: &PyAny) -> PyResult<()> {
+        let controlled = DoublyControlled::new(
+            extract_qubit(control1)?,
+            extract_qubit(control2)?,
+            extract_qubit(target)?,
+        );
+        self.push_inst(py, Instruction::Ccz(controlled));
+        Ok(())
+    }
+
+    fn swap(&self, py: Python, qubit1: &PyAny, qubit2: &PyAny) -> PyResult<()> {
+        let swapped = Swapped::new(extract_qubit(qubit1)?, extract_qubit(qubit2)?);
+        self.push_inst(py, Instruction::Swap(swapped));
+        Ok(())
+    }
+
+    fn ctl(
+        &self,
+        py: Python,
+        gate: &str,
+        controls: &PySequence,
+        target: &PyAny,
+        theta: Option<&PyAny>,
+    ) -> PyResult<()> {
+        self.controlled_gate(py, gate, false, controls, target, theta)
     }
 
-    fn h(&self, py: Python, qubit: &Qubit) {
-        let single = Single::new(qubit.id());
+    fn ctladj(
+        &self,
+        py: Python,
+        gate: &str,
+        controls: &PySequence,
+        target: &PyAny,
+        theta: Option<&PyAny>,
+    ) -> PyResult<()> {
+        self.controlled_gate(py, gate, true, controls, target, theta)
+    }
+
+    fn h(&self, py: Python, qubit: &PyAny) -> PyResult<()> {
+        let single = Single::new(extract_qubit(qubit)?);
         self.push_inst(py, Instruction::H(single));
+        Ok(())
     }
 
-    fn m(&self, py: Python, qubit: &Qubit, result: &ResultRef) {
-        let measured = Measured::new(qubit.id(), result.id());
+    fn m(&self, py: Python, qubit: &PyAny, result: &PyAny) -> PyResult<()> {
+        let measured = Measured::new(extract_qubit(qubit)?, extract_result(result)?);
         self.push_inst(py, Instruction::M(measured));
+        Ok(())
+    }
+
+    fn measure(
+        &self,
+        py: Python,
+        bases: &PySequence,
+        qubits: &PySequence,
+        result: &PyAny,
+    ) -> PyResult<()> {
+        let bases: Vec<_> = bases
+            .iter()?
+            .map(|basis| match basis?.extract::<&str>()? {
+                "i" => Ok(Pauli::I),
+                "x" => Ok(Pauli::X),
+                "y" => Ok(Pauli::Y),
+                "z" => Ok(Pauli::Z),
+                basis => {
+                    let message = format!("Unsupported Pauli basis {}.", basis);
+                    Err(PyErr::new::<PyValueError, _>(message))
+                }
+            })
+            .collect::<PyResult<_>>()?;
+
+        let qubits: Vec<_> = qubits
+            .iter()?
+            .map(|qubit| extract_qubit(qubit?))
+            .collect::<PyResult<_>>()?;
+
+        if bases.len() != qubits.len() {
+            let message = format!(
+                "Expected one basis for each qubit, got {} bases for {} qubits.",
+                bases.len(),
+                qubits.len()
+            );
+            return Err(PyErr::new::<PyValueError, _>(message));
+        }
+
+        let measure = Measure {
+            bases,
+            qubits,
+            target: extract_result(result)?,
+        };
+        self.push_inst(py, Instruction::Measure(measure));
+        Ok(())
     }
 
-    fn reset(&self, py: Python, qubit: &Qubit) {
-        let single = Single::new(qubit.id());
+    fn reset(&self, py: Python, qubit: &PyAny) -> PyResult<()> {
+        let single = Single::new(extract_qubit(qubit)?);
         self.push_inst(py, Instruction::Reset(single));
+        Ok(())
     }
 
-    fn rx(&self, py: Python, theta: f64, qubit: &Qubit) {
-        let rotated = Rotated::new(theta, qubit.id());
+    fn rx(&self, py: Python, theta: &PyAny, qubit: &PyAny) -> PyResult<()> {
+        let rotated = Rotated::new(
+            extract_value(theta, ValueType::Double)?,
+            extract_qubit(qubit)?,
+        );
         self.push_inst(py, Instruction::Rx(rotated));
+        Ok(())
     }
 
-    fn ry(&self, py: Python, theta: f64, qubit: &Qubit) {
-        let rotated = Rotated::new(theta, qubit.id());
+    fn ry(&self, py: Python, theta: &PyAny, qubit: &PyAny) -> PyResult<()> {
+        let rotated = Rotated::new(
+            extract_value(theta, ValueType::Double)?,
+            extract_qubit(qubit)?,
+        );
         self.push_inst(py, Instruction::Ry(rotated));
+        Ok(())
     }
 
-    fn rz(&self, py: Python, theta: f64, qubit: &Qubit) {
-        let rotated = Rotated::new(theta, qubit.id());
+    fn rz(&self, py: Python, theta: &PyAny, qubit: &PyAny) -> PyResult<()> {
+        let rotated = Rotated::new(
+            extract_value(theta, ValueType::Double)?,
+            extract_qubit(qubit)?,
+        );
         self.push_inst(py, Instruction::Rz(rotated));
+        Ok(())
     }
 
-    fn s(&self, py: Python, qubit: &Qubit) {
-        let single = Single::new(qubit.id());
+    fn s(&self, py: Python, qubit: &PyAny) -> PyResult<()> {
+        let single = Single::new(extract_qubit(qubit)?);
         self.push_inst(py, Instruction::S(single));
+        Ok(())
     }
 
-    fn s_adj(&self, py: Python, qubit: &Qubit) {
-        let single = Single::new(qubit.id());
+    fn s_adj(&self, py: Python, qubit: &PyAny) -> PyResult<()> {
+        let single = Single::new(extract_qubit(qubit)?);
         self.push_inst(py, Instruction::SAdj(single));
+        Ok(())
     }
 
-    fn t(&self, py: Python, qubit: &Qubit) {
-        let single = Single::new(qubit.id());
+    fn t(&self, py: Python, qubit: &PyAny) -> PyResult<()> {
+        let single = Single::new(extract_qubit(qubit)?);
         self.push_inst(py, Instruction::T(single));
+        Ok(())
     }
 
-    fn t_adj(&self, py: Python, qubit: &Qubit) {
-        let single = Single::new(qubit.id());
+    fn t_adj(&self, py: Python, qubit: &PyAny) -> PyResult<()> {
+        let single = Single::new(extract_qubit(qubit)?);
         self.push_inst(py, Instruction::TAdj(single));
+        Ok(())
     }
 
-    fn x(&self, py: Python, qubit: &Qubit) {
-        let single = Single::new(qubit.id());
+    fn x(&self, py: Python, qubit: &PyAny) -> PyResult<()> {
+        let single = Single::new(extract_qubit(qubit)?);
         self.push_inst(py, Instruction::X(single));
+        Ok(())
     }
 
-    fn y(&self, py: Python, qubit: &Qubit) {
-        let single = Single::new(qubit.id());
+    fn y(&self, py: Python, qubit: &PyAny) -> PyResult<()> {
+        let single = Single::new(extract_qubit(qubit)?);
         self.push_inst(py, Instruction::Y(single));
+        Ok(())
     }
 
-    fn z(&self, py: Python, qubit: &Qubit) {
-        let single = Single::new(qubit.id());
+    fn z(&self, py: Python, qubit: &PyAny) -> PyResult<()> {
+        let single = Single::new(extract_qubit(qubit)?);
         self.push_inst(py, Instruction::Z(single));
+        Ok(())
     }
 
     fn if_result(
         &self,
         py: Python,
-        result: &ResultRef,
+        result: &PyAny,
         one: Option<&PyAny>,
         zero: Option<&PyAny>,
     ) -> PyResult<()> {
+        let condition = extract_condition(result)?;
         let build_frame = |callback: Option<&PyAny>| -> PyResult<_> {
             self.push_frame(py);
             if let Some(callback) = callback {
@@ -483,7 +1202,7 @@ impl BasicQisBuilder {
         };
 
         let if_inst = If {
-            condition: result.id(),
+            condition,
             then_insts: build_frame(one)?,
             else_insts: build_frame(zero)?,
         };
@@ -491,6 +1210,51 @@ impl BasicQisBuilder {
         self.push_inst(py, Instruction::If(if_inst));
         Ok(())
     }
+
+    fn switch(
+        &self,
+        py: Python,
+        results: &PySequence,
+        cases: &PyDict,
+        default: Option<&PyAny>,
+    ) -> PyResult<()> {
+        let results = results
+            .iter()?
+            .map(|result| extract_result(result?))
+            .collect::<PyResult<_>>()?;
+
+        let build_frame = |callback: Option<&PyAny>| -> PyResult<_> {
+            self.push_frame(py);
+            if let Some(callback) = callback {
+                callback.call0()?;
+            }
+
+            Ok(self.pop_frame(py).unwrap())
+        };
+
+        let cases = cases
+            .iter()
+            .map(|(value, callback)| {
+                Ok(SwitchCase {
+                    value: value.extract()?,
+                    insts: build_frame(Some(callback))?,
+                })
+            })
+            .collect::<PyResult<_>>()?;
+
+        let switch = Switch {
+            results,
+            cases,
+            default_insts: build_frame(default)?,
+        };
+
+        self.push_inst(py, Instruction::Switch(switch));
+        Ok(())
+    }
+
+    fn for_range(&self, py: Python, count: u64) -> ForRange {
+        ForRange::new(self.builder.clone_ref(py), count)
+    }
 }
 
 impl BasicQisBuilder {
@@ -508,9 +1272,145 @@ impl BasicQisBuilder {
         let mut builder = self.builder.as_ref(py).borrow_mut();
         builder.pop_frame()
     }
+
+    fn controlled_gate(
+        &self,
+        py: Python,
+        gate: &str,
+        adjoint: bool,
+        controls: &PySequence,
+        target: &PyAny,
+        theta: Option<&PyAny>,
+    ) -> PyResult<()> {
+        let angle = || match theta {
+            Some(theta) => extract_value(theta, ValueType::Double),
+            None => Err(PyErr::new::<PyTypeError, _>(format!(
+                "Gate {} requires a rotation angle.",
+                gate
+            ))),
+        };
+
+        let gate = match gate {
+            "h" => Gate::H,
+            "s" => Gate::S,
+            "t" => Gate::T,
+            "x" => Gate::X,
+            "y" => Gate::Y,
+            "z" => Gate::Z,
+            "rx" => Gate::Rx(angle()?),
+            "ry" => Gate::Ry(angle()?),
+            "rz" => Gate::Rz(angle()?),
+            _ => {
+                let message = format!("Unsupported controlled gate {}.", gate);
+                return Err(PyErr::new::<PyValueError, _>(message));
+            }
+        };
+
+        let controls = controls
+            .iter()?
+            .map(|control| extract_qubit(control?))
+            .collect::<PyResult<_>>()?;
+
+        let inst = ControlledGate {
+            gate,
+            adjoint,
+            controls,
+            target: extract_qubit(target)?,
+        };
+        self.push_inst(py, Instruction::ControlledGate(inst));
+        Ok(())
+    }
+}
+
+/// Returns the source location of the Python code that is currently calling into the builder, or
+/// `None` if there is no Python frame to take it from. Frames only have line numbers, so the
+/// column is unknown.
+fn caller_span(py: Python) -> Option<SourceSpan> {
+    let frame = py.import("sys").ok()?.call_method0("_getframe").ok()?;
+    let file = frame.getattr("f_code").ok()?.getattr("co_filename").ok()?;
+    Some(SourceSpan {
+        file: file.extract().ok()?,
+        line: frame.getattr("f_lineno").ok()?.extract().ok()?,
+        column: None,
+    })
+}
+
+/// Extracts a reference to a qubit from a `Qubit` or a qubit variable.
+fn extract_qubit(ob: &PyAny) -> PyResult<Reference> {
+    if let Ok(qubit) = ob.extract::<Qubit>() {
+        return Ok(qubit.id());
+    }
+
+    match ob.extract::<Variable>() {
+        Ok(Variable {
+            name,
+            ty: ValueType::Qubit,
+        }) => Ok(Reference::Named(name)),
+        _ => Err(PyErr::new::<PyTypeError, _>("Expected a qubit.")),
+    }
+}
+
+/// Extracts a reference to a result from a `ResultRef` or a result variable.
+fn extract_result(ob: &PyAny) -> PyResult<Reference> {
+    if let Ok(result) = ob.extract::<ResultRef>() {
+        return Ok(result.id());
+    }
+
+    match ob.extract::<Variable>() {
+        Ok(Variable {
+            name,
+            ty: ValueType::Result,
+        }) => Ok(Reference::Named(name)),
+        _ => Err(PyErr::new::<PyTypeError, _>("Expected a result.")),
+    }
+}
+
+/// Extracts a condition from a `ResultCondition`, or from a `ResultRef` or result variable that is
+/// true if the result is one.
+fn extract_condition(ob: &PyAny) -> PyResult<Condition> {
+    if let Ok(condition) = ob.extract::<ResultCondition>() {
+        return Ok(condition.condition);
+    }
+
+    match extract_result(ob) {
+        Ok(result) => Ok(Condition::Result(result)),
+        Err(_) => Err(PyErr::new::<PyTypeError, _>(
+            "Expected a result or a result condition.",
+        )),
+    }
+}
+
+/// Extracts an operand for an arithmetic or comparison instruction, inferring its type: `bool` is a
+/// 1-bit integer, `int` is a 64-bit integer, and `float` is a double.
+fn extract_operand(ob: &PyAny) -> PyResult<(Value, ValueType)> {
+    if let Ok(variable) = ob.extract::<Variable>() {
+        return Ok((Value::Variable(variable.name), variable.ty));
+    }
+
+    let ty = if ob.is_instance::<PyBool>()? {
+        ValueType::Integer { width: 1 }
+    } else if ob.is_instance::<PyLong>()? {
+        ValueType::Integer { width: 64 }
+    } else if ob.is_instance::<PyFloat>()? {
+        ValueType::Double
+    } else {
+        let message = "Expected a bool, int, float or variable.";
+        return Err(PyErr::new::<PyTypeError, _>(message));
+    };
+
+    Ok((extract_value(ob, ty)?, ty))
 }
 
 fn extract_value(ob: &PyAny, ty: ValueType) -> PyResult<Value> {
+    if let Ok(variable) = ob.extract::<Variable>() {
+        return if variable.ty == ty {
+            Ok(Value::Variable(variable.name))
+        } else {
+            let message = format!("Expected {:?} value, got {:?} variable.", ty, variable.ty);
+            Err(PyErr::new::<PyTypeError, _>(message))
+        };
+    }
+
     match ty {
         ValueType::Integer { width } => IntegerValue::new(width, ob.extract()?)
             .map(Value::Integer)
@@ -521,5 +1421,6 @@ fn extract_value(ob: &PyAny, ty: ValueType) -> PyResult<Value> {
         ValueType::Double => Ok(Value::Double(ob.extract()?)),
         ValueType::Qubit => Ok(Value::Qubit(ob.extract::<Qubit>()?.id())),
         ValueType::Result => Ok(Value::Result(ob.extract::<ResultRef>()?.id())),
+        ValueType::String => Ok(Value::String(ob.extract()?)),
     }
 }
@@ -7,7 +7,13 @@ IR string without errors. These tests are not meant to make detailed assertions
 about the generated IR.
 """
 
-from pyqir.generator import BasicQisBuilder, SimpleModule
+from pyqir.generator import (
+    BasicQisBuilder,
+    SimpleModule,
+    UnknownQubitError,
+    UnknownResultError,
+)
+import pytest
 
 
 def test_bell() -> None:
@@ -98,3 +104,23 @@ def test_all_gates() -> None:
 
     ir = module.ir()
     assert ir.startswith("; ModuleID = 'All Gates'")
+
+
+def test_unknown_qubit_raises() -> None:
+    module = SimpleModule("Unknown qubit", num_qubits=1, num_results=0)
+    other = SimpleModule("Other", num_qubits=2, num_results=0)
+    qis = BasicQisBuilder(module.builder)
+    qis.h(other.qubits[1])
+
+    with pytest.raises(UnknownQubitError):
+        module.ir()
+
+
+def test_unknown_result_raises() -> None:
+    module = SimpleModule("Unknown result", num_qubits=1, num_results=1)
+    other = SimpleModule("Other", num_qubits=1, num_results=2)
+    qis = BasicQisBuilder(module.builder)
+    qis.m(module.qubits[0], other.results[1])
+
+    with pytest.raises(UnknownResultError):
+        module.bitcode()
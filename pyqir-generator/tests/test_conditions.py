@@ -0,0 +1,56 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+from pyqir.generator import (
+    BasicQisBuilder,
+    ResultCondition,
+    SimpleModule,
+    Variable,
+    types,
+)
+import unittest
+
+
+class ConditionsTest(unittest.TestCase):
+    def test_and_not(self) -> None:
+        mod = SimpleModule("test", 1, 2)
+        qis = BasicQisBuilder(mod.builder)
+        r0, r1 = mod.results
+        qis.m(mod.qubits[0], r0)
+        qis.m(mod.qubits[0], r1)
+        qis.if_result(r0 & ~r1, lambda: qis.x(mod.qubits[0]))
+
+        ir = mod.ir()
+        self.assertEqual(ir.count("call i1 @__quantum__qis__read_result__body("), 2)
+        self.assertEqual(ir.count(" = xor i1 "), 1)
+        self.assertEqual(ir.count(" = and i1 "), 1)
+
+    def test_chained_operators_are_flattened(self) -> None:
+        mod = SimpleModule("test", 1, 4)
+        qis = BasicQisBuilder(mod.builder)
+        for result in mod.results:
+            qis.m(mod.qubits[0], result)
+
+        r0, r1, r2, r3 = mod.results
+        qis.if_result(r0 ^ r1 ^ r2 ^ r3, lambda: qis.x(mod.qubits[0]))
+        qis.if_result((r0 | r1) | (r2 | r3), lambda: qis.z(mod.qubits[0]))
+
+        ir = mod.ir()
+        self.assertEqual(ir.count(" = xor i1 "), 3)
+        self.assertEqual(ir.count(" = or i1 "), 3)
+
+    def test_result_variable_in_condition(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        qis = BasicQisBuilder(mod.builder)
+
+        def body(q: Variable, r: Variable) -> None:
+            qis.if_result(~ResultCondition(r), lambda: qis.x(q))
+
+        f = mod.add_subroutine("f", [types.QUBIT, types.RESULT], body)
+        mod.builder.call(f, [mod.qubits[0], mod.results[0]])
+        self.assertEqual(mod.ir().count(" = xor i1 "), 1)
+
+    def test_operand_must_be_result(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        with self.assertRaises(TypeError):
+            mod.results[0] & mod.qubits[0]  # type: ignore
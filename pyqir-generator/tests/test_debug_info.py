@@ -0,0 +1,60 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+from pyqir.generator import BasicQisBuilder, SimpleModule, types
+import inspect
+from typing import Callable
+import unittest
+
+
+class DebugInfoTest(unittest.TestCase):
+    def test_gate_has_source_location(self) -> None:
+        mod = SimpleModule("test", 1, 0)
+        mod.use_debug_info(True)
+        qis = BasicQisBuilder(mod.builder)
+        line = inspect.currentframe().f_lineno + 1  # type: ignore
+        qis.h(mod.qubits[0])
+
+        ir = mod.ir()
+        self.assertIn("call void @__quantum__qis__h__body(%Qubit* null), !dbg", ir)
+        self.assertIn(f"!DILocation(line: {line}, scope:", ir)
+        self.assertIn(f'!DIFile(filename: "{__file__}"', ir)
+        self.assertIn('!"Debug Info Version", i32 3}', ir)
+
+    def test_builder_instructions_have_source_locations(self) -> None:
+        mod = SimpleModule("test", 1, 0)
+        mod.use_debug_info(True)
+        qis = BasicQisBuilder(mod.builder)
+        f = mod.add_external_function(
+            "test_function", types.Function([types.INT], types.VOID)
+        )
+        line = inspect.currentframe().f_lineno + 1  # type: ignore
+        with mod.builder.for_range(2) as i:
+            x = mod.builder.add(i, 1)
+            cond = mod.builder.eq(x, 2)
+            mod.builder.if_(cond, true=lambda: qis.x(mod.qubits[0]))
+            mod.builder.call(f, [x])
+
+        ir = mod.ir()
+        self.assertIn("= add i64 %var0, 1, !dbg", ir)
+        self.assertIn("= icmp eq i64 %var1, 2, !dbg", ir)
+        self.assertIn("call void @test_function(i64 %var1), !dbg", ir)
+        for offset in range(1, 5):
+            self.assertIn(f"!DILocation(line: {line + offset}, scope:", ir)
+
+    def test_no_source_location_without_gates(self) -> None:
+        mod = SimpleModule("test", 1, 0)
+        mod.use_debug_info(True)
+        self.assertNotIn("!dbg", mod.ir())
+
+    def test_ir_is_deterministic_by_default(self) -> None:
+        def build(gates: Callable[[BasicQisBuilder, SimpleModule], None]) -> str:
+            mod = SimpleModule("test", 1, 1)
+            gates(BasicQisBuilder(mod.builder), mod)
+            return mod.ir()
+
+        # The gates are built on different lines.
+        first = build(lambda qis, mod: qis.h(mod.qubits[0]))
+        second = build(lambda qis, mod: qis.h(mod.qubits[0]))
+        self.assertNotIn("!dbg", first)
+        self.assertEqual(first, second)
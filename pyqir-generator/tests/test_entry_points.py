@@ -0,0 +1,105 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+from typing import Tuple
+from pyqir.generator import (
+    BasicQisBuilder,
+    Qubit,
+    ResultRef,
+    SimpleModule,
+    UnknownResultError,
+)
+import unittest
+
+
+class EntryPointsTest(unittest.TestCase):
+    def test_entry_point_has_own_registers(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        qis = BasicQisBuilder(mod.builder)
+        qis.m(mod.qubits[0], mod.results[0])
+
+        def bell(qubits: Tuple[Qubit, ...], results: Tuple[ResultRef, ...]) -> None:
+            qis.h(qubits[0])
+            qis.cx(qubits[0], qubits[1])
+            qis.m(qubits[0], results[0])
+            qis.m(qubits[1], results[1])
+
+        mod.add_entry_point("bell", 2, 2, bell)
+
+        ir = mod.ir()
+        self.assertIn("define void @main() #0", ir)
+        self.assertIn("define void @bell() #1", ir)
+        self.assertIn(
+            'attributes #1 = { "EntryPoint" "requiredQubits"="2" "requiredResults"="2" }',
+            ir,
+        )
+
+    def test_entry_point_cannot_use_module_results(self) -> None:
+        mod = SimpleModule("test", 1, 2)
+        qis = BasicQisBuilder(mod.builder)
+        mod.add_entry_point(
+            "f", 1, 1, lambda qubits, _: qis.m(qubits[0], mod.results[1])
+        )
+
+        with self.assertRaises(UnknownResultError):
+            mod.ir()
+
+    def test_failed_body_is_discarded(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+
+        def body(qubits: Tuple[Qubit, ...], results: Tuple[ResultRef, ...]) -> None:
+            raise RuntimeError("oops")
+
+        with self.assertRaises(RuntimeError):
+            mod.add_entry_point("f", 0, 0, body)
+
+        self.assertNotIn("@f", mod.ir())
+
+    def test_parameters(self) -> None:
+        mod = SimpleModule("test", 1, 0)
+        qis = BasicQisBuilder(mod.builder)
+        theta = mod.add_parameter("theta")
+        phi = mod.add_parameter("phi")
+        qis.rx(theta, mod.qubits[0])
+        qis.rz(mod.builder.add(theta, phi), mod.qubits[0])
+
+        ir = mod.ir()
+        self.assertIn("define void @main(double %theta, double %phi) #0", ir)
+        self.assertIn(
+            "call void @__quantum__qis__rx__body(double %theta, %Qubit* null)", ir
+        )
+
+    def test_entry_point_name_and_attributes(self) -> None:
+        mod = SimpleModule("test", 1, 0)
+        qis = BasicQisBuilder(mod.builder)
+        qis.h(mod.qubits[0])
+        mod.set_entry_point_name("program")
+        mod.add_entry_point_attribute("output_labeling_schema", "labeled")
+        mod.add_entry_point_attribute("vendor_tag")
+
+        ir = mod.ir()
+        self.assertIn("define void @program() #0", ir)
+        self.assertNotIn("@main", ir)
+        self.assertIn('"output_labeling_schema"="labeled"', ir)
+        self.assertIn('"vendor_tag"', ir)
+
+    def test_named_metadata(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        mod.add_metadata("vendor.info", ["a", "b"])
+
+        ir = mod.ir()
+        self.assertIn("!vendor.info = !{!0}", ir)
+        self.assertIn('!0 = !{!"a", !"b"}', ir)
+
+    def test_interop_wrappers(self) -> None:
+        mod = SimpleModule("test", 1, 2)
+        qis = BasicQisBuilder(mod.builder)
+        qis.m(mod.qubits[0], mod.results[1])
+        mod.use_interop_wrappers(True)
+
+        ir = mod.ir()
+        self.assertIn("define void @main() #0", ir)
+        self.assertIn("define void @main__Interop(i8* %results) #1", ir)
+        self.assertIn('"InteropFriendly"', ir)
+        self.assertIn("call void @main()", ir)
+        self.assertIn("getelementptr inbounds i8, i8* %results, i64 1", ir)
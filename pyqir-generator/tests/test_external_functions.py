@@ -1,7 +1,7 @@
 # Copyright (c) Microsoft Corporation.
 # Licensed under the MIT License.
 
-from pyqir.generator import BasicQisBuilder, SimpleModule, Value, types
+from pyqir.generator import BasicQisBuilder, GenerationError, SimpleModule, Value, types
 from typing import Any, Callable, List, Tuple
 import unittest
 
@@ -146,6 +146,29 @@ class ExternalFunctionsTest(unittest.TestCase):
             mod.ir(),
         )
 
+    def test_call_string(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        f = mod.add_external_function(
+            "__quantum__rt__message", types.Function([types.STRING], types.VOID)
+        )
+        mod.builder.call(f, ["hello"])
+
+        ir = mod.ir()
+        self.assertIn('@0 = private unnamed_addr constant [6 x i8] c"hello\\00"', ir)
+        self.assertIn(
+            "call void @__quantum__rt__message(i8* getelementptr inbounds ([6 x i8], [6 x i8]* @0, i32 0, i32 0))",
+            ir,
+        )
+
+    def test_string_with_null_character(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        f = mod.add_external_function(
+            "__quantum__rt__message", types.Function([types.STRING], types.VOID)
+        )
+        mod.builder.call(f, ["a\0b"])
+        with self.assertRaisesRegex(GenerationError, "null character"):
+            mod.ir()
+
     def test_wrong_type(self) -> None:
         cases: List[Tuple[List[types.Value], Callable[[SimpleModule], List[Any]]]] = [
             ([types.BOOL], lambda _: ["true"]),
@@ -155,6 +178,8 @@ class ExternalFunctionsTest(unittest.TestCase):
             ([types.DOUBLE], lambda _: ["1.23"]),
             ([types.QUBIT], lambda mod: [mod.results[0]]),
             ([types.RESULT], lambda mod: [mod.qubits[0]]),
+            ([types.STRING], lambda _: [123]),
+            ([types.STRING], lambda mod: [mod.qubits[0]]),
         ]
 
         for param_types, get_args in cases:
@@ -203,3 +228,67 @@ class ExternalFunctionsTest(unittest.TestCase):
                 message = f"Expected {len(param_types)} arguments, got {len(args)}."
                 with self.assertRaisesRegex(ValueError, message):
                     mod.builder.call(f, args)
+
+    def test_call_void_returns_none(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        f = mod.add_external_function(
+            "test_function", types.Function([], types.VOID)
+        )
+        self.assertIsNone(mod.builder.call(f, []))
+
+    def test_return_value_as_argument(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        get = mod.add_external_function(
+            "get_int", types.Function([], types.INT)
+        )
+        put = mod.add_external_function(
+            "put_int", types.Function([types.INT], types.VOID)
+        )
+        x = mod.builder.call(get, [])
+        mod.builder.call(put, [x])
+
+        ir = mod.ir()
+        self.assertIn("%var0 = call i64 @get_int()", ir)
+        self.assertIn("call void @put_int(i64 %var0)", ir)
+
+    def test_return_value_as_rotation_angle(self) -> None:
+        mod = SimpleModule("test", 1, 0)
+        qis = BasicQisBuilder(mod.builder)
+        f = mod.add_external_function(
+            "get_angle", types.Function([], types.DOUBLE)
+        )
+        theta = mod.builder.call(f, [])
+        assert theta is not None
+        qis.rz(theta, mod.qubits[0])
+
+        self.assertIn(
+            "call void @__quantum__qis__rz__body(double %var0, %Qubit* null)",
+            mod.ir(),
+        )
+
+    def test_return_value_as_condition(self) -> None:
+        mod = SimpleModule("test", 1, 0)
+        qis = BasicQisBuilder(mod.builder)
+        f = mod.add_external_function(
+            "is_ready", types.Function([], types.BOOL)
+        )
+        ready = mod.builder.call(f, [])
+        assert ready is not None
+        mod.builder.if_(ready, true=lambda: qis.x(mod.qubits[0]))
+
+        ir = mod.ir()
+        self.assertIn("%var0 = call i1 @is_ready()", ir)
+        self.assertIn("br i1 %var0", ir)
+
+    def test_return_value_wrong_type(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        get = mod.add_external_function(
+            "get_int", types.Function([], types.INT)
+        )
+        put = mod.add_external_function(
+            "put_double", types.Function([types.DOUBLE], types.VOID)
+        )
+        x = mod.builder.call(get, [])
+
+        with self.assertRaises(TypeError):
+            mod.builder.call(put, [x])
@@ -0,0 +1,65 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+from pyqir.generator import (
+    BasicQisBuilder,
+    SimpleModule,
+    UnknownVariableError,
+    types,
+)
+import unittest
+
+
+class LoopsTest(unittest.TestCase):
+    def test_for_range_emits_loop(self) -> None:
+        mod = SimpleModule("test", 1, 0)
+        qis = BasicQisBuilder(mod.builder)
+        with qis.for_range(1000):
+            qis.h(mod.qubits[0])
+
+        ir = mod.ir()
+        self.assertEqual(ir.count("call void @__quantum__qis__h__body"), 1)
+        self.assertIn("icmp ult i64 %var0, 1000", ir)
+
+    def test_loop_index_as_argument(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        f = mod.add_external_function(
+            "test_function", types.Function([types.INT], types.VOID)
+        )
+        with mod.builder.for_range(3) as i:
+            mod.builder.call(f, [i])
+
+        self.assertIn("call void @test_function(i64 %var0)", mod.ir())
+
+    def test_nested_loops_have_distinct_indices(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        f = mod.add_external_function(
+            "test_function",
+            types.Function([types.INT, types.INT], types.VOID),
+        )
+        with mod.builder.for_range(2) as i:
+            with mod.builder.for_range(3) as j:
+                mod.builder.call(f, [i, j])
+
+        self.assertIn("call void @test_function(i64 %var0, i64 %var1)", mod.ir())
+
+    def test_loop_index_has_integer_type(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        f = mod.add_external_function(
+            "test_function", types.Function([types.DOUBLE], types.VOID)
+        )
+        with mod.builder.for_range(3) as i:
+            with self.assertRaises(TypeError):
+                mod.builder.call(f, [i])
+
+    def test_loop_index_out_of_scope(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        f = mod.add_external_function(
+            "test_function", types.Function([types.INT], types.VOID)
+        )
+        with mod.builder.for_range(3) as i:
+            pass
+
+        mod.builder.call(f, [i])
+        with self.assertRaises(UnknownVariableError):
+            mod.ir()
@@ -0,0 +1,42 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+from pyqir.generator import BasicQisBuilder, GenerationError, SimpleModule
+import unittest
+
+
+class MeasureTest(unittest.TestCase):
+    def test_joint_measurement(self) -> None:
+        mod = SimpleModule("test", 2, 1)
+        mod.use_static_result_alloc(False)
+        qis = BasicQisBuilder(mod.builder)
+        qis.measure("xz", mod.qubits, mod.results[0])
+        qis.if_result(mod.results[0], one=lambda: qis.x(mod.qubits[0]))
+
+        ir = mod.ir()
+        self.assertIn(
+            "call %Result* @__quantum__qis__measure__body(%Array* %bases, %Array* %qubits)",
+            ir,
+        )
+        self.assertIn("store i2 1, i2* ", ir)
+        self.assertIn("store i2 -2, i2* ", ir)
+        self.assertIn("call void @__quantum__qis__x__body(", ir)
+
+    def test_basis_count_must_match(self) -> None:
+        mod = SimpleModule("test", 2, 1)
+        qis = BasicQisBuilder(mod.builder)
+        with self.assertRaises(ValueError):
+            qis.measure(["z"], mod.qubits, mod.results[0])
+
+    def test_unknown_basis_is_rejected(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        qis = BasicQisBuilder(mod.builder)
+        with self.assertRaises(ValueError):
+            qis.measure(["w"], mod.qubits, mod.results[0])
+
+    def test_static_results_are_rejected(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        qis = BasicQisBuilder(mod.builder)
+        qis.measure("z", mod.qubits, mod.results[0])
+        with self.assertRaises(GenerationError):
+            mod.ir()
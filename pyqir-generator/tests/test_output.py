@@ -0,0 +1,33 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+from pyqir.generator import BasicQisBuilder, SimpleModule
+import unittest
+
+
+class OutputTest(unittest.TestCase):
+    def test_results_are_recorded(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        qis = BasicQisBuilder(mod.builder)
+        qis.m(mod.qubits[0], mod.results[0])
+        mod.record_output(True)
+
+        ir = mod.ir()
+        self.assertIn(
+            "call void @__quantum__rt__array_record_output(i64 1, i8* null)", ir
+        )
+        self.assertIn(
+            "call void @__quantum__rt__result_record_output(%Result* null, i8* null)",
+            ir,
+        )
+
+    def test_label(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        mod.record_output(True, "results")
+
+        ir = mod.ir()
+        self.assertIn('c"results\\00"', ir)
+
+    def test_output_is_not_recorded_by_default(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        self.assertNotIn("record_output", mod.ir())
@@ -0,0 +1,49 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+from pyqir.generator import BasicQisBuilder, GenerationError, SimpleModule
+import unittest
+
+
+class ProfilesTest(unittest.TestCase):
+    def test_base_profile(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        qis = BasicQisBuilder(mod.builder)
+        qis.h(mod.qubits[0])
+        qis.m(mod.qubits[0], mod.results[0])
+        mod.use_profile("base")
+
+        ir = mod.ir()
+        self.assertIn('!{i32 1, !"qir_major_version", i32 1}', ir)
+        self.assertIn('"qir_profiles"="base_profile"', ir)
+
+    def test_base_profile_rejects_branching(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        qis = BasicQisBuilder(mod.builder)
+        qis.m(mod.qubits[0], mod.results[0])
+        qis.if_result(mod.results[0], lambda: qis.x(mod.qubits[0]))
+        mod.use_profile("base")
+
+        with self.assertRaises(GenerationError):
+            mod.ir()
+
+        mod.use_profile("adaptive")
+        self.assertIn('"qir_profiles"="adaptive_profile"', mod.ir())
+
+    def test_base_profile_rejects_dynamic_allocation(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        qis = BasicQisBuilder(mod.builder)
+        qis.m(mod.qubits[0], mod.results[0])
+        mod.use_static_qubit_alloc(False)
+        mod.use_profile("base")
+
+        with self.assertRaises(GenerationError):
+            mod.ir()
+
+        mod.use_profile("adaptive")
+        self.assertIn('!{i32 1, !"dynamic_qubit_management", i1 true}', mod.ir())
+
+    def test_unknown_profile(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        with self.assertRaises(ValueError):
+            mod.use_profile("full")
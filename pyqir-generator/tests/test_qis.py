@@ -39,6 +39,53 @@ class QisTest(unittest.TestCase):
                 call = f"call void @__quantum__qis__{name}__body(%Qubit* null, %Qubit* inttoptr (i64 1 to %Qubit*))"
                 self.assertIn(call, mod.ir())
 
+    def test_doubly_controlled(self) -> None:
+        cases = [
+            ("ccx", lambda qis: qis.ccx),
+            ("ccz", lambda qis: qis.ccz),
+        ]
+
+        for name, gate in cases:
+            with self.subTest(name):
+                mod = SimpleModule("test_doubly_controlled", 3, 0)
+                qis = BasicQisBuilder(mod.builder)
+                gate(qis)(mod.qubits[0], mod.qubits[1], mod.qubits[2])
+                call = f"call void @__quantum__qis__{name}__body(%Qubit* null, %Qubit* inttoptr (i64 1 to %Qubit*), %Qubit* inttoptr (i64 2 to %Qubit*))"
+                self.assertIn(call, mod.ir())
+
+    def test_swap(self) -> None:
+        mod = SimpleModule("test_swap", 2, 0)
+        qis = BasicQisBuilder(mod.builder)
+        qis.swap(mod.qubits[0], mod.qubits[1])
+        call = f"call void @__quantum__qis__swap__body(%Qubit* null, %Qubit* inttoptr (i64 1 to %Qubit*))"
+        self.assertIn(call, mod.ir())
+
+    def test_ctl(self) -> None:
+        mod = SimpleModule("test_ctl", 3, 0)
+        qis = BasicQisBuilder(mod.builder)
+        qis.ctl("x", [mod.qubits[0], mod.qubits[1]], mod.qubits[2])
+        ir = mod.ir()
+        self.assertIn(
+            "%controls = call %Array* @__quantum__rt__array_create_1d(i32 8, i64 2)", ir
+        )
+        self.assertIn(
+            "call void @__quantum__qis__x__ctl(%Array* %controls, %Qubit* inttoptr (i64 2 to %Qubit*))",
+            ir,
+        )
+
+    def test_ctladj_rotation(self) -> None:
+        mod = SimpleModule("test_ctladj_rotation", 2, 0)
+        qis = BasicQisBuilder(mod.builder)
+        qis.ctladj("rz", [mod.qubits[0]], mod.qubits[1], 0.5)
+        call = "call void @__quantum__qis__rz__ctladj(%Array* %controls, double 5.000000e-01, %Qubit* inttoptr (i64 1 to %Qubit*))"
+        self.assertIn(call, mod.ir())
+
+    def test_ctl_rotation_needs_angle(self) -> None:
+        mod = SimpleModule("test_ctl_rotation_needs_angle", 2, 0)
+        qis = BasicQisBuilder(mod.builder)
+        with self.assertRaises(TypeError):
+            qis.ctl("rx", [mod.qubits[0]], mod.qubits[1])
+
     def test_adjoint(self) -> None:
         cases = [
             ("s", lambda qis: qis.s_adj),
@@ -0,0 +1,25 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+from pyqir.generator import BasicQisBuilder, SimpleModule
+import unittest
+
+
+class QubitArraysTest(unittest.TestCase):
+    def test_array_allocation(self) -> None:
+        mod = SimpleModule("test", 3, 0)
+        mod.use_static_qubit_alloc(False)
+        mod.use_array_qubit_alloc(True)
+        qis = BasicQisBuilder(mod.builder)
+        qis.cx(mod.qubits[0], mod.qubits[2])
+
+        ir = mod.ir()
+        self.assertIn(
+            "call %Array* @__quantum__rt__qubit_allocate_array(i64 3)", ir)
+        self.assertIn("call void @__quantum__rt__qubit_release_array", ir)
+        self.assertNotIn("@__quantum__rt__qubit_allocate()", ir)
+
+    def test_static_allocation_ignores_arrays(self) -> None:
+        mod = SimpleModule("test", 3, 0)
+        mod.use_array_qubit_alloc(True)
+        self.assertNotIn("qubit_allocate_array", mod.ir())
@@ -0,0 +1,97 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+from pyqir.generator import (
+    BasicQisBuilder,
+    GenerationError,
+    SimpleModule,
+    UnknownQubitError,
+    Variable,
+    types,
+)
+import unittest
+
+
+class SubroutinesTest(unittest.TestCase):
+    def test_subroutine_with_qubit_and_double(self) -> None:
+        mod = SimpleModule("test", 1, 0)
+        qis = BasicQisBuilder(mod.builder)
+
+        def body(q: Variable, theta: Variable) -> None:
+            qis.h(q)
+            qis.rz(theta, q)
+
+        f = mod.add_subroutine("rotate", [types.QUBIT, types.DOUBLE], body)
+        mod.builder.call(f, [mod.qubits[0], 0.5])
+
+        ir = mod.ir()
+        self.assertIn(
+            "define internal void @rotate(%Qubit* %var0, double %var1)", ir
+        )
+        self.assertIn(
+            "call void @__quantum__qis__rz__body(double %var1, %Qubit* %var0)",
+            ir,
+        )
+        self.assertIn(
+            "call void @rotate(%Qubit* null, double 5.000000e-01)", ir
+        )
+
+    def test_subroutine_with_result(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        qis = BasicQisBuilder(mod.builder)
+
+        def body(q: Variable, r: Variable) -> None:
+            qis.m(q, r)
+            qis.if_result(r, one=lambda: qis.x(q))
+
+        f = mod.add_subroutine("reset", [types.QUBIT, types.RESULT], body)
+        mod.builder.call(f, [mod.qubits[0], mod.results[0]])
+
+        ir = mod.ir()
+        self.assertIn(
+            "call void @__quantum__qis__mz__body(%Qubit* %var0, %Result* %var1)",
+            ir,
+        )
+        self.assertIn("call void @reset(%Qubit* null, %Result* null)", ir)
+
+    def test_dynamic_result_parameter_cannot_be_measured(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        mod.use_static_result_alloc(False)
+        qis = BasicQisBuilder(mod.builder)
+        f = mod.add_subroutine("measure", [types.QUBIT, types.RESULT], qis.m)
+        mod.builder.call(f, [mod.qubits[0], mod.results[0]])
+        qis.if_result(mod.results[0], one=lambda: qis.x(mod.qubits[0]))
+
+        with self.assertRaises(GenerationError):
+            mod.ir()
+
+    def test_subroutine_calls_subroutine(self) -> None:
+        mod = SimpleModule("test", 1, 0)
+        qis = BasicQisBuilder(mod.builder)
+        inner = mod.add_subroutine("inner", [types.QUBIT], qis.x)
+        outer = mod.add_subroutine(
+            "outer", [types.QUBIT], lambda q: mod.builder.call(inner, [q])
+        )
+        mod.builder.call(outer, [mod.qubits[0]])
+
+        self.assertIn("call void @inner(%Qubit* %var1)", mod.ir())
+
+    def test_subroutine_cannot_use_module_qubits(self) -> None:
+        mod = SimpleModule("test", 1, 0)
+        qis = BasicQisBuilder(mod.builder)
+        f = mod.add_subroutine("f", [], lambda: qis.x(mod.qubits[0]))
+        mod.builder.call(f, [])
+
+        with self.assertRaises(UnknownQubitError):
+            mod.ir()
+
+    def test_failed_body_is_discarded(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+
+        def body() -> None:
+            raise RuntimeError("oops")
+
+        with self.assertRaises(RuntimeError):
+            mod.add_subroutine("f", [], body)
+
+        self.assertNotIn("@f", mod.ir())
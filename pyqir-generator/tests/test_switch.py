@@ -0,0 +1,39 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+from pyqir.generator import BasicQisBuilder, SimpleModule
+import unittest
+
+
+class SwitchTest(unittest.TestCase):
+    def test_switch_on_results(self) -> None:
+        mod = SimpleModule("test", 1, 3)
+        qis = BasicQisBuilder(mod.builder)
+        for result in mod.results:
+            qis.m(mod.qubits[0], result)
+
+        qis.switch(
+            mod.results,
+            {5: lambda: qis.x(mod.qubits[0]), 2: lambda: qis.z(mod.qubits[0])},
+            default=lambda: qis.h(mod.qubits[0]),
+        )
+
+        ir = mod.ir()
+        self.assertEqual(ir.count("call i1 @__quantum__qis__read_result__body("), 3)
+        self.assertIn("switch i64 ", ir)
+        self.assertIn("i64 5, label %case", ir)
+        self.assertIn("i64 2, label %case", ir)
+        self.assertIn("call void @__quantum__qis__h__body(", ir)
+
+    def test_switch_without_default(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        qis = BasicQisBuilder(mod.builder)
+        qis.m(mod.qubits[0], mod.results[0])
+        qis.switch(mod.results, {1: lambda: qis.x(mod.qubits[0])})
+        self.assertIn("switch i64 ", mod.ir())
+
+    def test_negative_case_is_rejected(self) -> None:
+        mod = SimpleModule("test", 1, 1)
+        qis = BasicQisBuilder(mod.builder)
+        with self.assertRaises(OverflowError):
+            qis.switch(mod.results, {-1: lambda: qis.x(mod.qubits[0])})
@@ -0,0 +1,110 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+from pyqir.generator import (
+    BasicQisBuilder,
+    SimpleModule,
+    UnknownVariableError,
+    types,
+)
+import unittest
+
+
+class VariablesTest(unittest.TestCase):
+    def test_integer_arithmetic(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        f = mod.add_external_function(
+            "test_function", types.Function([types.INT], types.VOID)
+        )
+        with mod.builder.for_range(4) as i:
+            x = mod.builder.mul(i, 2)
+            y = mod.builder.add(x, 1)
+            mod.builder.call(f, [y])
+
+        ir = mod.ir()
+        self.assertIn("%var1 = mul i64 %var0, 2", ir)
+        self.assertIn("%var2 = add i64 %var1, 1", ir)
+        self.assertIn("call void @test_function(i64 %var2)", ir)
+
+    def test_double_arithmetic(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        f = mod.add_external_function(
+            "test_function", types.Function([types.DOUBLE], types.VOID)
+        )
+        x = mod.builder.sub(1.5, 0.25)
+        mod.builder.call(f, [x])
+        self.assertIn("call void @test_function(double 1.250000e+00)", mod.ir())
+
+    def test_branch_on_comparison(self) -> None:
+        mod = SimpleModule("test", 1, 0)
+        qis = BasicQisBuilder(mod.builder)
+        with mod.builder.for_range(10) as i:
+            cond = mod.builder.lt(i, 5)
+            mod.builder.if_(
+                cond,
+                true=lambda: qis.x(mod.qubits[0]),
+                false=lambda: qis.h(mod.qubits[0]),
+            )
+
+        ir = mod.ir()
+        self.assertIn("icmp slt i64 %var0, 5", ir)
+        self.assertIn("call void @__quantum__qis__x__body", ir)
+        self.assertIn("call void @__quantum__qis__h__body", ir)
+
+    def test_signed_and_unsigned_comparisons(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        with mod.builder.for_range(10) as i:
+            mod.builder.lt(i, 5)
+            mod.builder.ult(i, 5)
+            mod.builder.ge(i, 5)
+            mod.builder.uge(i, 5)
+
+        ir = mod.ir()
+        self.assertIn("icmp slt i64 %var0, 5", ir)
+        self.assertIn("icmp ult i64 %var0, 5", ir)
+        self.assertIn("icmp sge i64 %var0, 5", ir)
+        self.assertIn("icmp uge i64 %var0, 5", ir)
+
+    def test_unsigned_comparison_of_doubles_raises(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        with self.assertRaises(TypeError):
+            mod.builder.ult(1.0, 2.0)
+
+    def test_bitwise_on_double_raises(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        with self.assertRaises(TypeError):
+            mod.builder.xor(1.0, 2.0)
+
+    def test_mismatched_operands_raise(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        with self.assertRaises(TypeError):
+            mod.builder.add(1, 2.0)
+
+    def test_non_numeric_comparison_raises(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        f = mod.add_external_function(
+            "get_qubit", types.Function([], types.QUBIT)
+        )
+        q1 = mod.builder.call(f, [])
+        q2 = mod.builder.call(f, [])
+        with self.assertRaises(TypeError):
+            mod.builder.eq(q1, q2)
+
+    def test_non_boolean_condition_raises(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        x = mod.builder.add(1, 2)
+        with self.assertRaises(TypeError):
+            mod.builder.if_(x)
+
+    def test_variable_out_of_scope(self) -> None:
+        mod = SimpleModule("test", 0, 0)
+        f = mod.add_external_function(
+            "test_function", types.Function([types.INT], types.VOID)
+        )
+        escaped = []
+        with mod.builder.for_range(2) as i:
+            escaped.append(mod.builder.add(i, 1))
+
+        mod.builder.call(f, escaped)
+        with self.assertRaises(UnknownVariableError):
+            mod.ir()
@@ -607,6 +607,15 @@ class QirInstr:
             self._type = QirType(self.instr.type)
         return cast(QirType, self._type)
 
+    @property
+    def debug_location(self) -> Optional[Tuple[str, int, Optional[int]]]:
+        """
+        Gets the source location attached to this instruction as debug metadata, as a tuple of the
+        file name, line number and column number, or None if the instruction has no location. The
+        column is None if it is not known.
+        """
+        return self.instr.debug_location
+
 
 class QirOpInstr(QirInstr):
     """
@@ -15,7 +15,7 @@ use super::parse::{
     BasicBlockExt, CallExt, ConstantExt, FunctionExt, IntructionExt, ModuleExt, NameExt, PhiExt,
     TypeExt,
 };
-use llvm_ir::{self, types::Typed};
+use llvm_ir::{self, types::Typed, HasDebugLoc};
 use pyo3::{exceptions::PyRuntimeError, prelude::*};
 use std::{convert::TryFrom, path::PathBuf};
 
@@ -321,6 +321,14 @@ impl PyQirInstruction {
         }
     }
 
+    #[getter]
+    fn get_debug_location(&self) -> Option<(String, u32, Option<u32>)> {
+        self.instr
+            .get_debug_loc()
+            .as_ref()
+            .map(|loc| (loc.filename.clone(), loc.line, loc.col))
+    }
+
     #[getter]
     fn get_is_add(&self) -> bool {
         matches!(self.instr, llvm_ir::Instruction::Add(_))
@@ -99,6 +99,20 @@ def test_parser_zext_support():
     assert instr.target_operands[0].type.width == 1
 
 
+def test_debug_location():
+    mod = QirModule("tests/debug_info.bc")
+    func = mod.get_func_by_name("main")
+    instrs = func.blocks[0].instructions
+    assert instrs[0].debug_location == ("debug_info", 0, None)
+    assert instrs[1].debug_location == ("test.py", 3, 5)
+
+
+def test_missing_debug_location():
+    mod = QirModule("tests/select.bc")
+    func = mod.get_funcs_by_attr("EntryPoint")[0]
+    assert func.blocks[0].instructions[0].debug_location is None
+
+
 def test_loading_invalid_bitcode():
     path = "tests/teleportchain.ll.reference"
     with pytest.raises(RuntimeError) as exc_info:
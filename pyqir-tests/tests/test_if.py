@@ -219,6 +219,60 @@ def test_results_default_to_zero_if_not_measured(matrix) -> None:
     _eval(module, logger)
     assert logger.instructions == ["h qubit[0]"]
 
+@pytest.mark.parametrize("matrix", static_generator_variations)
+@pytest.mark.parametrize("results", [[False, False], [False, True], [True, False], [True, True]])
+def test_and_not_condition(matrix, results) -> None:
+    module = SimpleModule("test_and_not", num_qubits=1, num_results=2)
+    module.use_static_qubit_alloc(matrix[0])
+    module.use_static_result_alloc(matrix[1])
+    qis = BasicQisBuilder(module.builder)
+    r0, r1 = module.results
+    qis.m(module.qubits[0], r0)
+    qis.m(module.qubits[0], r1)
+    qis.if_result(r0 & ~r1, lambda: qis.x(module.qubits[0]))
+
+    logger = GateLogger()
+    _eval(module, logger, results)
+    expected = ["m qubit[0] => out[0]", "m qubit[0] => out[1]"]
+    if results[0] and not results[1]:
+        expected.append("x qubit[0]")
+    assert logger.instructions == expected
+
+@pytest.mark.parametrize("matrix", static_generator_variations)
+@pytest.mark.parametrize("results", [[False, False, True], [True, True, False], [True, True, True]])
+def test_parity_condition(matrix, results) -> None:
+    module = SimpleModule("test_parity", num_qubits=1, num_results=3)
+    module.use_static_qubit_alloc(matrix[0])
+    module.use_static_result_alloc(matrix[1])
+    qis = BasicQisBuilder(module.builder)
+    for result in module.results:
+        qis.m(module.qubits[0], result)
+
+    r0, r1, r2 = module.results
+    qis.if_result(r0 ^ r1 ^ r2,
+                  one=lambda: qis.x(module.qubits[0]),
+                  zero=lambda: qis.z(module.qubits[0]))
+
+    logger = GateLogger()
+    _eval(module, logger, results)
+    parity = sum(results) % 2 == 1
+    assert logger.instructions[3:] == ["x qubit[0]" if parity else "z qubit[0]"]
+
+@pytest.mark.parametrize("matrix", static_generator_variations)
+def test_or_condition(matrix) -> None:
+    module = SimpleModule("test_or", num_qubits=1, num_results=2)
+    module.use_static_qubit_alloc(matrix[0])
+    module.use_static_result_alloc(matrix[1])
+    qis = BasicQisBuilder(module.builder)
+    r0, r1 = module.results
+    qis.m(module.qubits[0], r0)
+    qis.m(module.qubits[0], r1)
+    qis.if_result(r0 | r1, lambda: qis.x(module.qubits[0]))
+
+    logger = GateLogger()
+    _eval(module, logger, [False, True])
+    assert logger.instructions == ["m qubit[0] => out[0]", "m qubit[0] => out[1]", "x qubit[0]"]
+
 
 def _eval(module: SimpleModule,
           gates: GateSet,
@@ -0,0 +1,36 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+from pyqir.generator import BasicQisBuilder, SimpleModule
+from pyqir.evaluator import GateLogger, GateSet, NonadaptiveEvaluator
+import tempfile
+from typing import List, Optional
+import pytest
+
+
+# Pauli measurements return new results, so only qubit allocation varies.
+@pytest.mark.parametrize("use_static_qubit_alloc", [False, True])
+@pytest.mark.parametrize("outcome", [False, True])
+def test_stabilizer_measurement(use_static_qubit_alloc, outcome) -> None:
+    module = SimpleModule("test_measure", num_qubits=3, num_results=1)
+    module.use_static_qubit_alloc(use_static_qubit_alloc)
+    module.use_static_result_alloc(False)
+    qis = BasicQisBuilder(module.builder)
+    qis.measure("zyx", module.qubits, module.results[0])
+    qis.if_result(module.results[0], one=lambda: qis.x(module.qubits[0]))
+
+    logger = GateLogger()
+    _eval(module, logger, [outcome])
+    expected = ["measure z qubit[0], y qubit[1], x qubit[2] => out[0]"]
+    if outcome:
+        expected.append("x qubit[0]")
+    assert logger.instructions == expected
+
+
+def _eval(module: SimpleModule,
+          gates: GateSet,
+          result_stream: Optional[List[bool]] = None) -> None:
+    with tempfile.NamedTemporaryFile(suffix=".ll") as f:
+        f.write(module.ir().encode("utf-8"))
+        f.flush()
+        NonadaptiveEvaluator().eval(f.name, gates, None, result_stream)
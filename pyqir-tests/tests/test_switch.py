@@ -0,0 +1,52 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+from pyqir.generator import BasicQisBuilder, SimpleModule
+from pyqir.evaluator import GateLogger, GateSet, NonadaptiveEvaluator
+import tempfile
+from typing import List, Optional
+import pytest
+
+# Combinations of static qubit and result code generation
+# first element => use_static_qubit_alloc
+# second element => use_static_result_alloc
+static_generator_variations = [
+    [False, False],
+    [False, True],
+    [True, False],
+    [True, True]
+]
+
+@pytest.mark.parametrize("matrix", static_generator_variations)
+@pytest.mark.parametrize("value", range(8))
+def test_case_for_value_executes(matrix, value) -> None:
+    module = SimpleModule("test_switch", num_qubits=1, num_results=3)
+    module.use_static_qubit_alloc(matrix[0])
+    module.use_static_result_alloc(matrix[1])
+    qis = BasicQisBuilder(module.builder)
+    for result in module.results:
+        qis.m(module.qubits[0], result)
+
+    qis.switch(
+        module.results,
+        {
+            1: lambda: qis.x(module.qubits[0]),
+            6: lambda: qis.y(module.qubits[0]),
+        },
+        default=lambda: qis.z(module.qubits[0]),
+    )
+    qis.h(module.qubits[0])
+
+    logger = GateLogger()
+    _eval(module, logger, [bool(value >> bit & 1) for bit in range(3)])
+    expected = {1: "x qubit[0]", 6: "y qubit[0]"}.get(value, "z qubit[0]")
+    assert logger.instructions[3:] == [expected, "h qubit[0]"]
+
+
+def _eval(module: SimpleModule,
+          gates: GateSet,
+          result_stream: Optional[List[bool]] = None) -> None:
+    with tempfile.NamedTemporaryFile(suffix=".ll") as f:
+        f.write(module.ir().encode("utf-8"))
+        f.flush()
+        NonadaptiveEvaluator().eval(f.name, gates, None, result_stream)
@@ -0,0 +1,98 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Emits and verifies circuits of up to a million gates, and prints the time per gate for each
+//! size. Fails if the time per gate for the largest circuit is more than twice the time per gate
+//! for the smallest, since it should not grow with the size of the circuit.
+
+#[cfg(not(feature = "no-llvm-linking"))]
+fn main() {
+    use inkwell::context::Context;
+    use qirlib::generation::{
+        emit,
+        interop::{
+            ClassicalRegister, Controlled, Instruction, Measured, QuantumRegister, SemanticModel,
+            Single,
+        },
+    };
+    use std::time::{Duration, Instant};
+
+    const NUM_QUBITS: u64 = 64;
+    const GATE_COUNTS: [u64; 4] = [125_000, 250_000, 500_000, 1_000_000];
+
+    let get_model = |num_gates: u64, max_block_size| {
+        let qubit = |index: u64| format!("q{}", index % NUM_QUBITS);
+        let mut instructions: Vec<_> = (0..num_gates)
+            .map(|index| {
+                if index % 2 == 0 {
+                    Instruction::H(Single::new(qubit(index)))
+                } else {
+                    Instruction::Cx(Controlled::new(qubit(index), qubit(index + 1)))
+                }
+            })
+            .collect();
+        instructions.push(Instruction::M(Measured::new("q0", "r0")));
+
+        SemanticModel {
+            registers: vec![ClassicalRegister::new("r".to_string(), 1)],
+            qubits: (0..NUM_QUBITS)
+                .map(|index| QuantumRegister::new("q".to_string(), index))
+                .collect(),
+            instructions,
+            use_static_result_alloc: false,
+            max_block_size,
+            ..SemanticModel::new("bench".to_string())
+        }
+    };
+
+    let per_gate = |elapsed: Duration, num_gates: u64| elapsed.as_nanos() / u128::from(num_gates);
+    let check_growth = |step: &str, max_block_size: Option<usize>, times: &[u128]| {
+        let (first, last) = (times[0], times[times.len() - 1]);
+        assert!(
+            last <= 2 * first,
+            "{} with block size {:?} takes {} ns per gate for {} gates, but {} ns per gate for {} gates.",
+            step,
+            max_block_size,
+            last,
+            GATE_COUNTS[GATE_COUNTS.len() - 1],
+            first,
+            GATE_COUNTS[0],
+        );
+    };
+
+    for max_block_size in [None, Some(10_000)] {
+        let mut emit_times = vec![];
+        let mut verify_times = vec![];
+        for num_gates in GATE_COUNTS {
+            let model = get_model(num_gates, max_block_size);
+            let context = Context::create();
+
+            // Emission includes the verification that `populate_context` does, which is also
+            // timed on its own so that it can be told apart from building the instructions.
+            let start = Instant::now();
+            let generator = emit::populate_context(&context, &model).expect("Emission failed.");
+            let emit_time = start.elapsed();
+            let start = Instant::now();
+            generator.module.verify().expect("Verification failed.");
+            let verify_time = start.elapsed();
+
+            println!(
+                "{} gates, block size {:?}: emitted in {:?} ({} ns per gate), verified in {:?} ({} ns per gate)",
+                num_gates,
+                max_block_size,
+                emit_time,
+                per_gate(emit_time, num_gates),
+                verify_time,
+                per_gate(verify_time, num_gates),
+            );
+            emit_times.push(per_gate(emit_time, num_gates));
+            verify_times.push(per_gate(verify_time, num_gates));
+        }
+
+        check_growth("Emission", max_block_size, &emit_times);
+        check_growth("Verification", max_block_size, &verify_times);
+    }
+}
+
+#[cfg(feature = "no-llvm-linking")]
+fn main() {}
@@ -0,0 +1,32 @@
+; ModuleID = 'controlled_gates'
+source_filename = "controlled_gates"
+
+%Array = type opaque
+%Qubit = type opaque
+
+declare %Array* @__quantum__rt__array_create_1d(i32, i64)
+
+declare i8* @__quantum__rt__array_get_element_ptr_1d(%Array*, i64)
+
+declare void @__quantum__rt__array_update_reference_count(%Array*, i32)
+
+declare void @__quantum__qis__x__ctl(%Array*, %Qubit*)
+
+declare void @__quantum__qis__rz__ctladj(%Array*, double, %Qubit*)
+
+define void @main() #0 {
+entry:
+  %controls = call %Array* @__quantum__rt__array_create_1d(i32 8, i64 2)
+  %0 = call i8* @__quantum__rt__array_get_element_ptr_1d(%Array* %controls, i64 0)
+  %1 = bitcast i8* %0 to %Qubit**
+  store %Qubit* null, %Qubit** %1, align 8
+  %2 = call i8* @__quantum__rt__array_get_element_ptr_1d(%Array* %controls, i64 1)
+  %3 = bitcast i8* %2 to %Qubit**
+  store %Qubit* inttoptr (i64 1 to %Qubit*), %Qubit** %3, align 8
+  call void @__quantum__qis__x__ctl(%Array* %controls, %Qubit* inttoptr (i64 2 to %Qubit*))
+  call void @__quantum__qis__rz__ctladj(%Array* %controls, double 5.000000e-01, %Qubit* inttoptr (i64 2 to %Qubit*))
+  call void @__quantum__rt__array_update_reference_count(%Array* %controls, i32 -1)
+  ret void
+}
+
+attributes #0 = { "EntryPoint" }
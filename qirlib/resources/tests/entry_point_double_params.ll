@@ -0,0 +1,14 @@
+; ModuleID = 'entry_point_double_params'
+source_filename = "entry_point_double_params"
+
+%Qubit = type opaque
+
+declare void @__quantum__qis__rx__body(double, %Qubit*)
+
+define void @main(double %theta) #0 {
+entry:
+  call void @__quantum__qis__rx__body(double %theta, %Qubit* null)
+  ret void
+}
+
+attributes #0 = { "EntryPoint" }
@@ -0,0 +1,20 @@
+; ModuleID = 'multi_qubit_gates'
+source_filename = "multi_qubit_gates"
+
+%Qubit = type opaque
+
+declare void @__quantum__qis__ccx__body(%Qubit*, %Qubit*, %Qubit*)
+
+declare void @__quantum__qis__ccz__body(%Qubit*, %Qubit*, %Qubit*)
+
+declare void @__quantum__qis__swap__body(%Qubit*, %Qubit*)
+
+define void @main() #0 {
+entry:
+  call void @__quantum__qis__ccx__body(%Qubit* null, %Qubit* inttoptr (i64 1 to %Qubit*), %Qubit* inttoptr (i64 2 to %Qubit*))
+  call void @__quantum__qis__ccz__body(%Qubit* inttoptr (i64 2 to %Qubit*), %Qubit* null, %Qubit* inttoptr (i64 1 to %Qubit*))
+  call void @__quantum__qis__swap__body(%Qubit* null, %Qubit* inttoptr (i64 2 to %Qubit*))
+  ret void
+}
+
+attributes #0 = { "EntryPoint" }
@@ -0,0 +1,160 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    debug_info::{
+        AsDIScope, DICompileUnit, DIFile, DIFlags, DIFlagsConstants, DILexicalBlock,
+        DWARFEmissionKind, DWARFSourceLanguage, DebugInfoBuilder,
+    },
+    module::{FlagBehavior, Module},
+    values::FunctionValue,
+};
+use std::{cell::RefCell, collections::HashMap};
+
+/// The version of the debug metadata format that LLVM expects. Without a module flag declaring
+/// it, LLVM drops the debug metadata when the module is loaded.
+const DEBUG_INFO_VERSION: u64 = 3;
+
+/// Builds the debug metadata that maps generated instructions back to the source that produced
+/// them.
+pub struct DebugInfo<'ctx> {
+    builder: DebugInfoBuilder<'ctx>,
+    compile_unit: DICompileUnit<'ctx>,
+
+    /// The file of each source file name.
+    files: RefCell<HashMap<String, DIFile<'ctx>>>,
+
+    /// The scope of the locations in each source file in the function that was attached last,
+    /// which is the function being emitted.
+    scopes: RefCell<HashMap<String, DILexicalBlock<'ctx>>>,
+}
+
+impl<'ctx> DebugInfo<'ctx> {
+    pub(crate) fn new(context: &'ctx Context, module: &Module<'ctx>) -> Self {
+        let name = module.get_name().to_string_lossy();
+        let (builder, compile_unit) = module.create_debug_info_builder(
+            true,
+            DWARFSourceLanguage::Python,
+            &name,
+            ".",
+            "pyqir",
+            false,
+            "",
+            0,
+            "",
+            DWARFEmissionKind::LineTablesOnly,
+            0,
+            false,
+            false,
+            "",
+            "",
+        );
+
+        if module.get_flag("Debug Info Version").is_none() {
+            module.add_basic_value_flag(
+                "Debug Info Version",
+                FlagBehavior::Warning,
+                context.i32_type().const_int(DEBUG_INFO_VERSION, false),
+            );
+        }
+
+        DebugInfo {
+            builder,
+            compile_unit,
+            files: RefCell::new(HashMap::new()),
+            scopes: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Attaches a subprogram to the function, which is the scope of the locations in its body.
+    /// Instructions emitted without a source location are given line 0 of the function, because
+    /// LLVM requires every call in a function with debug metadata to have a location.
+    pub(crate) fn attach(
+        &self,
+        context: &'ctx Context,
+        builder: &Builder<'ctx>,
+        function: FunctionValue<'ctx>,
+    ) {
+        let file = self.compile_unit.get_file();
+        let ty = self
+            .builder
+            .create_subroutine_type(file, None, &[], DIFlags::PUBLIC);
+        let name = function.get_name().to_string_lossy();
+        let subprogram = self.builder.create_function(
+            self.compile_unit.as_debug_info_scope(),
+            &name,
+            None,
+            file,
+            0,
+            ty,
+            false,
+            true,
+            0,
+            DIFlags::PUBLIC,
+            false,
+        );
+        function.set_subprogram(subprogram);
+        self.scopes.borrow_mut().clear();
+
+        let location = self.builder.create_debug_location(
+            context,
+            0,
+            0,
+            subprogram.as_debug_info_scope(),
+            None,
+        );
+        builder.set_current_debug_location(context, location);
+    }
+
+    /// Sets the location of the instructions emitted next into the function to the given source
+    /// location. The function must be the one that was attached last.
+    pub(crate) fn set_location(
+        &self,
+        context: &'ctx Context,
+        builder: &Builder<'ctx>,
+        function: FunctionValue<'ctx>,
+        file: &str,
+        line: u32,
+        column: u32,
+    ) {
+        if let Some(subprogram) = function.get_subprogram() {
+            // Locations in the same file share one scope, which starts at the first of them.
+            let mut scopes = self.scopes.borrow_mut();
+            let block = match scopes.get(file) {
+                Some(&block) => block,
+                None => {
+                    let block = self.builder.create_lexical_block(
+                        subprogram.as_debug_info_scope(),
+                        self.file(file),
+                        line,
+                        column,
+                    );
+                    scopes.insert(file.to_owned(), block);
+                    block
+                }
+            };
+            let location = self.builder.create_debug_location(
+                context,
+                line,
+                column,
+                block.as_debug_info_scope(),
+                None,
+            );
+            builder.set_current_debug_location(context, location);
+        }
+    }
+
+    fn file(&self, name: &str) -> DIFile<'ctx> {
+        *self
+            .files
+            .borrow_mut()
+            .entry(name.to_owned())
+            .or_insert_with(|| self.builder.create_file(name, ""))
+    }
+
+    pub(crate) fn finalize(&self) {
+        self.builder.finalize();
+    }
+}
@@ -5,24 +5,37 @@ use inkwell::{
     memory_buffer::MemoryBuffer,
     module::Module,
     types::{FloatType, IntType, StructType},
-    values::{BasicMetadataValueEnum, BasicValueEnum, FunctionValue, InstructionValue},
+    values::{
+        BasicMetadataValueEnum, BasicValueEnum, FunctionValue, InstructionValue, IntValue,
+        PointerValue,
+    },
 };
-use std::path::Path;
+use std::{cell::RefCell, collections::HashMap, path::Path};
 
 use self::{
     basicvalues::{f64_to_f64, i64_to_i32, i8_null_ptr, u64_to_i32, u64_to_i64},
     calls::{emit_call_with_return, emit_void_call},
+    debug::DebugInfo,
     qis::{
-        cnot_body, cz_body, h_body, m_body, mz_body, reset_body, rx_body, ry_body, rz_body, s_adj,
-        s_body, t_adj, t_body, x_body, y_body, z_body,
+        ccx_body, ccz_body, cnot_body, cz_body, h_body, m_body, measure_body, mz_body, reset_body,
+        rx_body, ry_body, rz_body, s_adj, s_body, swap_body, t_adj, t_body, x_body, y_body, z_body,
+    },
+    qubits::{
+        emit_allocate_qubit, emit_allocate_qubit_array, emit_array_qubit, emit_pauli_array,
+        emit_qubit_array, emit_release_array, emit_release_qubit, emit_release_qubit_array,
+    },
+    rt::{
+        array_create_1d, array_get_element_ptr_1d, array_record_output,
+        array_update_reference_count, qubit_allocate, qubit_allocate_array, qubit_release,
+        qubit_release_array, result_equal, result_get_one, result_get_zero, result_record_output,
+        tuple_record_output,
     },
-    qubits::{emit_allocate_qubit, emit_release_qubit},
-    rt::{qubit_allocate, qubit_release, result_equal, result_get_one, result_get_zero},
-    types::{int32, int64, int8, qubit, result},
+    types::{int32, int64, int8, pauli, qubit, result},
 };
 
 pub mod basicvalues;
 pub mod calls;
+pub mod debug;
 pub mod qis;
 pub mod qubits;
 pub mod rt;
@@ -34,6 +47,14 @@ pub struct CodeGenerator<'ctx> {
     pub builder: inkwell::builder::Builder<'ctx>,
     pub use_static_qubit_alloc: bool,
     pub use_static_result_alloc: bool,
+    pub use_array_qubit_alloc: bool,
+
+    /// The most top-level instructions in a basic block of a function body, if bodies are split.
+    pub max_block_size: Option<usize>,
+    pub debug_info: Option<DebugInfo<'ctx>>,
+
+    /// The global string constants that have been emitted, by content.
+    strings: RefCell<HashMap<String, PointerValue<'ctx>>>,
 }
 
 impl<'ctx> CodeGenerator<'ctx> {
@@ -53,10 +74,51 @@ impl<'ctx> CodeGenerator<'ctx> {
             builder,
             use_static_qubit_alloc,
             use_static_result_alloc,
+            use_array_qubit_alloc: false,
+            max_block_size: None,
+            debug_info: None,
+            strings: RefCell::default(),
         })
     }
 }
 
+impl<'ctx> CodeGenerator<'ctx> {
+    /// Starts building debug metadata for the module, so that instructions can be given source
+    /// locations.
+    pub fn enable_debug_info(&mut self) {
+        self.debug_info = Some(DebugInfo::new(self.context, &self.module));
+    }
+
+    /// Gives the function a debug scope if debug metadata is enabled. Call this before emitting
+    /// the body of the function.
+    pub fn attach_debug_info(&self, function: FunctionValue<'ctx>) {
+        if let Some(debug_info) = &self.debug_info {
+            debug_info.attach(self.context, &self.builder, function);
+        }
+    }
+
+    /// Sets the source location of the instructions emitted next into the function, if debug
+    /// metadata is enabled.
+    pub fn set_debug_location(
+        &self,
+        function: FunctionValue<'ctx>,
+        file: &str,
+        line: u32,
+        column: u32,
+    ) {
+        if let Some(debug_info) = &self.debug_info {
+            debug_info.set_location(self.context, &self.builder, function, file, line, column);
+        }
+    }
+
+    /// Resolves the debug metadata. Call this after every function has been emitted.
+    pub fn finalize_debug_info(&self) {
+        if let Some(debug_info) = &self.debug_info {
+            debug_info.finalize();
+        }
+    }
+}
+
 impl<'ctx> CodeGenerator<'ctx> {
     pub fn emit_bitcode(&self, path: impl AsRef<Path>) {
         self.module.write_bitcode_to_path(path.as_ref());
@@ -121,6 +183,20 @@ impl<'ctx> CodeGenerator<'ctx> {
     pub fn usize_to_i64(&self, value: usize) -> BasicMetadataValueEnum<'ctx> {
         u64_to_i64(self.context, value as u64)
     }
+
+    /// A pointer to a null-terminated global constant containing the string. Strings with the same
+    /// content share one global.
+    pub fn global_string(&self, value: &str) -> PointerValue<'ctx> {
+        *self
+            .strings
+            .borrow_mut()
+            .entry(value.to_string())
+            .or_insert_with(|| {
+                self.builder
+                    .build_global_string_ptr(value, "")
+                    .as_pointer_value()
+            })
+    }
 }
 
 impl<'ctx> CodeGenerator<'ctx> {
@@ -132,6 +208,18 @@ impl<'ctx> CodeGenerator<'ctx> {
         cz_body(self.context, &self.module)
     }
 
+    pub fn qis_ccx_body(&self) -> FunctionValue<'ctx> {
+        ccx_body(self.context, &self.module)
+    }
+
+    pub fn qis_ccz_body(&self) -> FunctionValue<'ctx> {
+        ccz_body(self.context, &self.module)
+    }
+
+    pub fn qis_swap_body(&self) -> FunctionValue<'ctx> {
+        swap_body(self.context, &self.module)
+    }
+
     pub fn qis_h_body(&self) -> FunctionValue<'ctx> {
         h_body(self.context, &self.module)
     }
@@ -187,6 +275,26 @@ impl<'ctx> CodeGenerator<'ctx> {
     pub fn qis_mz_body(&self) -> FunctionValue<'ctx> {
         mz_body(self.context, &self.module)
     }
+
+    pub fn qis_measure_body(&self) -> FunctionValue<'ctx> {
+        measure_body(self.context, &self.module)
+    }
+
+    pub fn qis_ctl(&self, name: &str) -> FunctionValue<'ctx> {
+        qis::get_intrinsic_function_ctl(self.context, &self.module, name)
+    }
+
+    pub fn qis_ctladj(&self, name: &str) -> FunctionValue<'ctx> {
+        qis::get_intrinsic_function_ctladj(self.context, &self.module, name)
+    }
+
+    pub fn qis_rotated_ctl(&self, name: &str) -> FunctionValue<'ctx> {
+        qis::get_rotated_intrinsic_function_ctl(self.context, &self.module, name)
+    }
+
+    pub fn qis_rotated_ctladj(&self, name: &str) -> FunctionValue<'ctx> {
+        qis::get_rotated_intrinsic_function_ctladj(self.context, &self.module, name)
+    }
 }
 
 impl<'ctx> CodeGenerator<'ctx> {
@@ -197,6 +305,62 @@ impl<'ctx> CodeGenerator<'ctx> {
     pub fn emit_release_qubit(&self, qubit: &BasicValueEnum<'ctx>) -> InstructionValue<'ctx> {
         emit_release_qubit(self.context, &self.builder, &self.module, qubit)
     }
+
+    pub fn emit_allocate_qubit_array(&self, size: u64, result_name: &str) -> PointerValue<'ctx> {
+        emit_allocate_qubit_array(self.context, &self.builder, &self.module, size, result_name)
+    }
+
+    pub fn emit_array_qubit(
+        &self,
+        array: PointerValue<'ctx>,
+        index: u64,
+        result_name: &str,
+    ) -> BasicValueEnum<'ctx> {
+        emit_array_qubit(
+            self.context,
+            &self.builder,
+            &self.module,
+            array,
+            index,
+            result_name,
+        )
+    }
+
+    pub fn emit_release_qubit_array(&self, array: PointerValue<'ctx>) -> InstructionValue<'ctx> {
+        emit_release_qubit_array(self.context, &self.builder, &self.module, array)
+    }
+
+    pub fn emit_qubit_array(
+        &self,
+        qubits: &[BasicValueEnum<'ctx>],
+        result_name: &str,
+    ) -> PointerValue<'ctx> {
+        emit_qubit_array(
+            self.context,
+            &self.builder,
+            &self.module,
+            qubits,
+            result_name,
+        )
+    }
+
+    pub fn emit_pauli_array(
+        &self,
+        paulis: &[IntValue<'ctx>],
+        result_name: &str,
+    ) -> PointerValue<'ctx> {
+        emit_pauli_array(
+            self.context,
+            &self.builder,
+            &self.module,
+            paulis,
+            result_name,
+        )
+    }
+
+    pub fn emit_release_array(&self, array: PointerValue<'ctx>) -> InstructionValue<'ctx> {
+        emit_release_array(self.context, &self.builder, &self.module, array)
+    }
 }
 
 impl<'ctx> CodeGenerator<'ctx> {
@@ -223,6 +387,38 @@ impl<'ctx> CodeGenerator<'ctx> {
     pub fn rt_qubit_release(&self) -> FunctionValue<'ctx> {
         qubit_release(self.context, &self.module)
     }
+
+    pub fn rt_qubit_allocate_array(&self) -> FunctionValue<'ctx> {
+        qubit_allocate_array(self.context, &self.module)
+    }
+
+    pub fn rt_qubit_release_array(&self) -> FunctionValue<'ctx> {
+        qubit_release_array(self.context, &self.module)
+    }
+
+    pub fn rt_array_create_1d(&self) -> FunctionValue<'ctx> {
+        array_create_1d(self.context, &self.module)
+    }
+
+    pub fn rt_array_get_element_ptr_1d(&self) -> FunctionValue<'ctx> {
+        array_get_element_ptr_1d(self.context, &self.module)
+    }
+
+    pub fn rt_array_update_reference_count(&self) -> FunctionValue<'ctx> {
+        array_update_reference_count(self.context, &self.module)
+    }
+
+    pub fn rt_result_record_output(&self) -> FunctionValue<'ctx> {
+        result_record_output(self.context, &self.module)
+    }
+
+    pub fn rt_array_record_output(&self) -> FunctionValue<'ctx> {
+        array_record_output(self.context, &self.module)
+    }
+
+    pub fn rt_tuple_record_output(&self) -> FunctionValue<'ctx> {
+        tuple_record_output(self.context, &self.module)
+    }
 }
 
 impl<'ctx> CodeGenerator<'ctx> {
@@ -246,6 +442,10 @@ impl<'ctx> CodeGenerator<'ctx> {
         self.context.bool_type()
     }
 
+    pub fn pauli_type(&self) -> IntType<'ctx> {
+        pauli(self.context)
+    }
+
     pub fn qubit_type(&self) -> StructType<'ctx> {
         qubit(self.context, &self.module)
     }
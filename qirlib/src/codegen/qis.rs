@@ -8,7 +8,7 @@ use log;
 use inkwell::module::{Linkage, Module};
 use inkwell::values::FunctionValue;
 
-use super::types::{self, double, qubit, result};
+use super::types::{self, array, double, qubit, result};
 
 pub(crate) fn cnot_body<'ctx>(
     context: &'ctx inkwell::context::Context,
@@ -24,6 +24,27 @@ pub(crate) fn cz_body<'ctx>(
     get_controlled_intrinsic_function_body(context, module, "cz")
 }
 
+pub(crate) fn ccx_body<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+) -> FunctionValue<'ctx> {
+    get_doubly_controlled_intrinsic_function_body(context, module, "ccx")
+}
+
+pub(crate) fn ccz_body<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+) -> FunctionValue<'ctx> {
+    get_doubly_controlled_intrinsic_function_body(context, module, "ccz")
+}
+
+pub(crate) fn swap_body<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+) -> FunctionValue<'ctx> {
+    get_controlled_intrinsic_function_body(context, module, "swap")
+}
+
 pub(crate) fn h_body<'ctx>(
     context: &'ctx inkwell::context::Context,
     module: &Module<'ctx>,
@@ -137,6 +158,25 @@ pub(crate) fn get_controlled_intrinsic_function_body<'ctx>(
     )
 }
 
+/// `declare void @__quantum__qis__{}__body(%Qubit*, %Qubit*, %Qubit*)`
+pub(crate) fn get_doubly_controlled_intrinsic_function_body<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+    name: &str,
+) -> FunctionValue<'ctx> {
+    let qubit_ptr_type = qubit(context, module).ptr_type(AddressSpace::Generic);
+    get_intrinsic_function_body_impl(
+        context,
+        module,
+        name,
+        &[
+            qubit_ptr_type.into(),
+            qubit_ptr_type.into(),
+            qubit_ptr_type.into(),
+        ],
+    )
+}
+
 /// `declare void @__quantum__qis__{}__body(double, %Qubit*)`
 pub(crate) fn get_rotated_intrinsic_function_body<'ctx>(
     context: &'ctx inkwell::context::Context,
@@ -162,6 +202,61 @@ pub(crate) fn get_intrinsic_function_body<'ctx>(
     get_intrinsic_function_body_impl(context, module, name, &[qubit_ptr_type.into()])
 }
 
+/// `declare void @__quantum__qis__{}__ctl(%Array*, %Qubit*)`
+pub(crate) fn get_intrinsic_function_ctl<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+    name: &str,
+) -> FunctionValue<'ctx> {
+    get_controlled_variant(context, module, name, "ctl", false)
+}
+
+/// `declare void @__quantum__qis__{}__ctladj(%Array*, %Qubit*)`
+pub(crate) fn get_intrinsic_function_ctladj<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+    name: &str,
+) -> FunctionValue<'ctx> {
+    get_controlled_variant(context, module, name, "ctladj", false)
+}
+
+/// `declare void @__quantum__qis__{}__ctl(%Array*, double, %Qubit*)`
+pub(crate) fn get_rotated_intrinsic_function_ctl<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+    name: &str,
+) -> FunctionValue<'ctx> {
+    get_controlled_variant(context, module, name, "ctl", true)
+}
+
+/// `declare void @__quantum__qis__{}__ctladj(%Array*, double, %Qubit*)`
+pub(crate) fn get_rotated_intrinsic_function_ctladj<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+    name: &str,
+) -> FunctionValue<'ctx> {
+    get_controlled_variant(context, module, name, "ctladj", true)
+}
+
+fn get_controlled_variant<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+    name: &str,
+    functor: &str,
+    rotated: bool,
+) -> FunctionValue<'ctx> {
+    let array_ptr_type = array(context, module).ptr_type(AddressSpace::Generic);
+    let qubit_ptr_type = qubit(context, module).ptr_type(AddressSpace::Generic);
+    let mut param_types: Vec<BasicMetadataTypeEnum> = vec![array_ptr_type.into()];
+    if rotated {
+        param_types.push(double(context).into());
+    }
+    param_types.push(qubit_ptr_type.into());
+
+    let function_name = format!("__quantum__qis__{}__{}", name.to_lowercase(), functor);
+    get_or_declare_void_function(context, module, &function_name, &param_types)
+}
+
 fn get_intrinsic_function_body_impl<'ctx>(
     context: &'ctx inkwell::context::Context,
     module: &Module<'ctx>,
@@ -169,13 +264,21 @@ fn get_intrinsic_function_body_impl<'ctx>(
     param_types: &[BasicMetadataTypeEnum<'ctx>],
 ) -> FunctionValue<'ctx> {
     let function_name = format!("__quantum__qis__{}__body", name.to_lowercase());
-    if let Some(function) = get_function(module, function_name.as_str()) {
+    get_or_declare_void_function(context, module, &function_name, param_types)
+}
+
+fn get_or_declare_void_function<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+    function_name: &str,
+    param_types: &[BasicMetadataTypeEnum<'ctx>],
+) -> FunctionValue<'ctx> {
+    if let Some(function) = get_function(module, function_name) {
         function
     } else {
         let void_type = context.void_type();
         let fn_type = void_type.fn_type(param_types, false);
-        let fn_value =
-            module.add_function(function_name.as_str(), fn_type, Some(Linkage::External));
+        let fn_value = module.add_function(function_name, fn_type, Some(Linkage::External));
         fn_value
     }
 }
@@ -219,6 +322,24 @@ pub(crate) fn get_intrinsic_m_function_body<'ctx>(
     }
 }
 
+/// `declare %Result* @__quantum__qis__measure__body(%Array*, %Array*)`
+pub(crate) fn measure_body<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+) -> FunctionValue<'ctx> {
+    let function_name = "__quantum__qis__measure__body";
+    if let Some(function) = get_function(module, function_name) {
+        function
+    } else {
+        let result_ptr_type = result(context, module).ptr_type(AddressSpace::Generic);
+        let array_ptr_type = array(context, module).ptr_type(AddressSpace::Generic);
+        let fn_type =
+            result_ptr_type.fn_type(&[array_ptr_type.into(), array_ptr_type.into()], false);
+        let fn_value = module.add_function(function_name, fn_type, Some(Linkage::External));
+        fn_value
+    }
+}
+
 /// `declare void @__quantum__qis__{}__adj(%Qubit*)`
 pub(crate) fn get_intrinsic_function_adj<'ctx>(
     context: &'ctx inkwell::context::Context,
@@ -302,6 +423,90 @@ mod qis_declaration_tests {
         );
     }
 
+    #[test]
+    fn ccx_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = ccx_body(&context, &module);
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare void @__quantum__qis__ccx__body(%Qubit*, %Qubit*, %Qubit*)\n",
+            str_val.to_string()
+        );
+    }
+
+    #[test]
+    fn ccz_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = ccz_body(&context, &module);
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare void @__quantum__qis__ccz__body(%Qubit*, %Qubit*, %Qubit*)\n",
+            str_val.to_string()
+        );
+    }
+
+    #[test]
+    fn swap_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = swap_body(&context, &module);
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare void @__quantum__qis__swap__body(%Qubit*, %Qubit*)\n",
+            str_val.to_string()
+        );
+    }
+
+    #[test]
+    fn x_ctl_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = get_intrinsic_function_ctl(&context, &module, "x");
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare void @__quantum__qis__x__ctl(%Array*, %Qubit*)\n",
+            str_val.to_string()
+        );
+    }
+
+    #[test]
+    fn s_ctladj_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = get_intrinsic_function_ctladj(&context, &module, "s");
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare void @__quantum__qis__s__ctladj(%Array*, %Qubit*)\n",
+            str_val.to_string()
+        );
+    }
+
+    #[test]
+    fn rx_ctl_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = get_rotated_intrinsic_function_ctl(&context, &module, "rx");
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare void @__quantum__qis__rx__ctl(%Array*, double, %Qubit*)\n",
+            str_val.to_string()
+        );
+    }
+
+    #[test]
+    fn rz_ctladj_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = get_rotated_intrinsic_function_ctladj(&context, &module, "rz");
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare void @__quantum__qis__rz__ctladj(%Array*, double, %Qubit*)\n",
+            str_val.to_string()
+        );
+    }
+
     #[test]
     fn h_is_declared_correctly() {
         let context = Context::create();
@@ -470,6 +675,18 @@ mod qis_declaration_tests {
         );
     }
 
+    #[test]
+    fn measure_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = measure_body(&context, &module);
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare %Result* @__quantum__qis__measure__body(%Array*, %Array*)\n",
+            str_val.to_string()
+        );
+    }
+
     #[test]
     fn read_result_is_declared_correctly() {
         let context = Context::create();
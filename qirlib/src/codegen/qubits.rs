@@ -4,14 +4,25 @@
 use inkwell::{
     builder::Builder,
     module::Module,
-    values::{BasicValue, BasicValueEnum, InstructionValue},
+    values::{BasicValue, BasicValueEnum, InstructionValue, IntValue, PointerValue},
+    AddressSpace,
 };
 
 use super::{
     calls::{emit_call_with_return, emit_void_call},
-    rt::{qubit_allocate, qubit_release},
+    rt::{
+        array_create_1d, array_get_element_ptr_1d, array_update_reference_count, qubit_allocate,
+        qubit_allocate_array, qubit_release, qubit_release_array,
+    },
+    types::{int32, int64, pauli, qubit},
 };
 
+/// The size in bytes of a `%Qubit*` element in an array.
+const QUBIT_PTR_SIZE: u64 = 8;
+
+/// The size in bytes of a `%Pauli` element in an array.
+const PAULI_SIZE: u64 = 1;
+
 pub(crate) fn emit_allocate_qubit<'ctx>(
     context: &'ctx inkwell::context::Context,
     builder: &Builder<'ctx>,
@@ -33,3 +44,150 @@ pub(crate) fn emit_release_qubit<'ctx>(
     let function = qubit_release(context, module);
     emit_void_call(builder, function, &args)
 }
+
+/// Allocates an `%Array*` of `size` new qubits. The array should be released with
+/// `emit_release_qubit_array`.
+pub(crate) fn emit_allocate_qubit_array<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    builder: &Builder<'ctx>,
+    module: &Module<'ctx>,
+    size: u64,
+    result_name: &str,
+) -> PointerValue<'ctx> {
+    let args = [int64(context).const_int(size, false).into()];
+    let function = qubit_allocate_array(context, module);
+    emit_call_with_return(builder, function, &args, result_name).into_pointer_value()
+}
+
+/// Loads the qubit at `index` from an array of qubits.
+pub(crate) fn emit_array_qubit<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    builder: &Builder<'ctx>,
+    module: &Module<'ctx>,
+    array: PointerValue<'ctx>,
+    index: u64,
+    result_name: &str,
+) -> BasicValueEnum<'ctx> {
+    let args = [array.into(), int64(context).const_int(index, false).into()];
+    let element = emit_call_with_return(
+        builder,
+        array_get_element_ptr_1d(context, module),
+        &args,
+        "",
+    );
+    let qubit_ptr_ptr_type = qubit(context, module)
+        .ptr_type(AddressSpace::Generic)
+        .ptr_type(AddressSpace::Generic);
+    let element = builder
+        .build_bitcast(element, qubit_ptr_ptr_type, "")
+        .into_pointer_value();
+    builder.build_load(element, result_name)
+}
+
+pub(crate) fn emit_release_qubit_array<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    builder: &Builder<'ctx>,
+    module: &Module<'ctx>,
+    array: PointerValue<'ctx>,
+) -> InstructionValue<'ctx> {
+    let args = [array.into()];
+    let function = qubit_release_array(context, module);
+    emit_void_call(builder, function, &args)
+}
+
+/// Creates an `%Array*` containing the given qubits. The array should be released with
+/// `emit_release_array` after it is used.
+pub(crate) fn emit_qubit_array<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    builder: &Builder<'ctx>,
+    module: &Module<'ctx>,
+    qubits: &[BasicValueEnum<'ctx>],
+    result_name: &str,
+) -> PointerValue<'ctx> {
+    let args = [
+        int32(context).const_int(QUBIT_PTR_SIZE, false).into(),
+        int64(context).const_int(qubits.len() as u64, false).into(),
+    ];
+    let array = emit_call_with_return(
+        builder,
+        array_create_1d(context, module),
+        &args,
+        result_name,
+    )
+    .into_pointer_value();
+
+    let qubit_ptr_ptr_type = qubit(context, module)
+        .ptr_type(AddressSpace::Generic)
+        .ptr_type(AddressSpace::Generic);
+    for (index, qubit) in qubits.iter().enumerate() {
+        let args = [
+            array.into(),
+            int64(context).const_int(index as u64, false).into(),
+        ];
+        let element = emit_call_with_return(
+            builder,
+            array_get_element_ptr_1d(context, module),
+            &args,
+            "",
+        );
+        let element = builder
+            .build_bitcast(element, qubit_ptr_ptr_type, "")
+            .into_pointer_value();
+        builder.build_store(element, *qubit);
+    }
+
+    array
+}
+
+/// Creates an `%Array*` containing the given `%Pauli` values. The array should be released with
+/// `emit_release_array` after it is used.
+pub(crate) fn emit_pauli_array<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    builder: &Builder<'ctx>,
+    module: &Module<'ctx>,
+    paulis: &[IntValue<'ctx>],
+    result_name: &str,
+) -> PointerValue<'ctx> {
+    let args = [
+        int32(context).const_int(PAULI_SIZE, false).into(),
+        int64(context).const_int(paulis.len() as u64, false).into(),
+    ];
+    let array = emit_call_with_return(
+        builder,
+        array_create_1d(context, module),
+        &args,
+        result_name,
+    )
+    .into_pointer_value();
+
+    let pauli_ptr_type = pauli(context).ptr_type(AddressSpace::Generic);
+    for (index, value) in paulis.iter().enumerate() {
+        let args = [
+            array.into(),
+            int64(context).const_int(index as u64, false).into(),
+        ];
+        let element = emit_call_with_return(
+            builder,
+            array_get_element_ptr_1d(context, module),
+            &args,
+            "",
+        );
+        let element = builder
+            .build_bitcast(element, pauli_ptr_type, "")
+            .into_pointer_value();
+        builder.build_store(element, *value);
+    }
+
+    array
+}
+
+pub(crate) fn emit_release_array<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    builder: &Builder<'ctx>,
+    module: &Module<'ctx>,
+    array: PointerValue<'ctx>,
+) -> InstructionValue<'ctx> {
+    let args = [array.into(), int32(context).const_all_ones().into()];
+    let function = array_update_reference_count(context, module);
+    emit_void_call(builder, function, &args)
+}
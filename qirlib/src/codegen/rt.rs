@@ -7,7 +7,7 @@ use inkwell::{
     AddressSpace,
 };
 
-use super::types::{self, qubit, result};
+use super::types::{self, array, int32, int64, int8, qubit, result};
 
 /// `declare %Result* @__quantum__rt__result_get_zero()`
 pub(crate) fn result_get_zero<'ctx>(
@@ -112,6 +112,166 @@ pub(crate) fn qubit_release<'ctx>(
     }
 }
 
+/// `declare %Array* @__quantum__rt__qubit_allocate_array(i64)`
+pub(crate) fn qubit_allocate_array<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+) -> FunctionValue<'ctx> {
+    if let Some(function) = get_function(module, "qubit_allocate_array") {
+        function
+    } else {
+        let array_ptr_type = array(context, module).ptr_type(AddressSpace::Generic);
+        let fn_type = array_ptr_type.fn_type(&[int64(context).into()], false);
+        let fn_value = module.add_function(
+            get_function_name("qubit_allocate_array").as_str(),
+            fn_type,
+            Some(Linkage::External),
+        );
+        fn_value
+    }
+}
+
+/// `declare void @__quantum__rt__qubit_release_array(%Array*)`
+pub(crate) fn qubit_release_array<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+) -> FunctionValue<'ctx> {
+    if let Some(function) = get_function(module, "qubit_release_array") {
+        function
+    } else {
+        let array_ptr_type = array(context, module).ptr_type(AddressSpace::Generic);
+        let void_type = context.void_type();
+        let fn_type = void_type.fn_type(&[array_ptr_type.into()], false);
+        let fn_value = module.add_function(
+            get_function_name("qubit_release_array").as_str(),
+            fn_type,
+            Some(Linkage::External),
+        );
+        fn_value
+    }
+}
+
+/// `declare %Array* @__quantum__rt__array_create_1d(i32, i64)`
+pub(crate) fn array_create_1d<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+) -> FunctionValue<'ctx> {
+    if let Some(function) = get_function(module, "array_create_1d") {
+        function
+    } else {
+        let array_ptr_type = array(context, module).ptr_type(AddressSpace::Generic);
+        let fn_type =
+            array_ptr_type.fn_type(&[int32(context).into(), int64(context).into()], false);
+        let fn_value = module.add_function(
+            get_function_name("array_create_1d").as_str(),
+            fn_type,
+            Some(Linkage::External),
+        );
+        fn_value
+    }
+}
+
+/// `declare i8* @__quantum__rt__array_get_element_ptr_1d(%Array*, i64)`
+pub(crate) fn array_get_element_ptr_1d<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+) -> FunctionValue<'ctx> {
+    if let Some(function) = get_function(module, "array_get_element_ptr_1d") {
+        function
+    } else {
+        let array_ptr_type = array(context, module).ptr_type(AddressSpace::Generic);
+        let i8_ptr_type = int8(context).ptr_type(AddressSpace::Generic);
+        let fn_type = i8_ptr_type.fn_type(&[array_ptr_type.into(), int64(context).into()], false);
+        let fn_value = module.add_function(
+            get_function_name("array_get_element_ptr_1d").as_str(),
+            fn_type,
+            Some(Linkage::External),
+        );
+        fn_value
+    }
+}
+
+/// `declare void @__quantum__rt__array_update_reference_count(%Array*, i32)`
+pub(crate) fn array_update_reference_count<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+) -> FunctionValue<'ctx> {
+    if let Some(function) = get_function(module, "array_update_reference_count") {
+        function
+    } else {
+        let array_ptr_type = array(context, module).ptr_type(AddressSpace::Generic);
+        let void_type = context.void_type();
+        let fn_type = void_type.fn_type(&[array_ptr_type.into(), int32(context).into()], false);
+        let fn_value = module.add_function(
+            get_function_name("array_update_reference_count").as_str(),
+            fn_type,
+            Some(Linkage::External),
+        );
+        fn_value
+    }
+}
+
+/// `declare void @__quantum__rt__result_record_output(%Result*, i8*)`
+pub(crate) fn result_record_output<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+) -> FunctionValue<'ctx> {
+    if let Some(function) = get_function(module, "result_record_output") {
+        function
+    } else {
+        let result_ptr_type = result(context, module).ptr_type(AddressSpace::Generic);
+        let i8_ptr_type = int8(context).ptr_type(AddressSpace::Generic);
+        let void_type = context.void_type();
+        let fn_type = void_type.fn_type(&[result_ptr_type.into(), i8_ptr_type.into()], false);
+        let fn_value = module.add_function(
+            get_function_name("result_record_output").as_str(),
+            fn_type,
+            Some(Linkage::External),
+        );
+        fn_value
+    }
+}
+
+/// `declare void @__quantum__rt__array_record_output(i64, i8*)`
+pub(crate) fn array_record_output<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+) -> FunctionValue<'ctx> {
+    if let Some(function) = get_function(module, "array_record_output") {
+        function
+    } else {
+        let i8_ptr_type = int8(context).ptr_type(AddressSpace::Generic);
+        let void_type = context.void_type();
+        let fn_type = void_type.fn_type(&[int64(context).into(), i8_ptr_type.into()], false);
+        let fn_value = module.add_function(
+            get_function_name("array_record_output").as_str(),
+            fn_type,
+            Some(Linkage::External),
+        );
+        fn_value
+    }
+}
+
+/// `declare void @__quantum__rt__tuple_record_output(i64, i8*)`
+pub(crate) fn tuple_record_output<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &Module<'ctx>,
+) -> FunctionValue<'ctx> {
+    if let Some(function) = get_function(module, "tuple_record_output") {
+        function
+    } else {
+        let i8_ptr_type = int8(context).ptr_type(AddressSpace::Generic);
+        let void_type = context.void_type();
+        let fn_type = void_type.fn_type(&[int64(context).into(), i8_ptr_type.into()], false);
+        let fn_value = module.add_function(
+            get_function_name("tuple_record_output").as_str(),
+            fn_type,
+            Some(Linkage::External),
+        );
+        fn_value
+    }
+}
+
 fn get_function_name(suffix: &str) -> String {
     format!("__quantum__rt__{}", suffix)
 }
@@ -193,4 +353,100 @@ mod rt_declaration_tests {
             str_val.to_string()
         );
     }
+
+    #[test]
+    fn qubit_allocate_array_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = qubit_allocate_array(&context, &module);
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare %Array* @__quantum__rt__qubit_allocate_array(i64)\n",
+            str_val.to_string()
+        );
+    }
+
+    #[test]
+    fn qubit_release_array_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = qubit_release_array(&context, &module);
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare void @__quantum__rt__qubit_release_array(%Array*)\n",
+            str_val.to_string()
+        );
+    }
+
+    #[test]
+    fn array_create_1d_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = array_create_1d(&context, &module);
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare %Array* @__quantum__rt__array_create_1d(i32, i64)\n",
+            str_val.to_string()
+        );
+    }
+
+    #[test]
+    fn array_get_element_ptr_1d_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = array_get_element_ptr_1d(&context, &module);
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare i8* @__quantum__rt__array_get_element_ptr_1d(%Array*, i64)\n",
+            str_val.to_string()
+        );
+    }
+
+    #[test]
+    fn array_update_reference_count_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = array_update_reference_count(&context, &module);
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare void @__quantum__rt__array_update_reference_count(%Array*, i32)\n",
+            str_val.to_string()
+        );
+    }
+
+    #[test]
+    fn result_record_output_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = result_record_output(&context, &module);
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare void @__quantum__rt__result_record_output(%Result*, i8*)\n",
+            str_val.to_string()
+        );
+    }
+
+    #[test]
+    fn array_record_output_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = array_record_output(&context, &module);
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare void @__quantum__rt__array_record_output(i64, i8*)\n",
+            str_val.to_string()
+        );
+    }
+
+    #[test]
+    fn tuple_record_output_is_declared_correctly() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let function = tuple_record_output(&context, &module);
+        let str_val = function.print_to_string();
+        assert_eq!(
+            "declare void @__quantum__rt__tuple_record_output(i64, i8*)\n",
+            str_val.to_string()
+        );
+    }
 }
@@ -30,6 +30,12 @@ pub(crate) fn bool(context: &inkwell::context::Context) -> IntType {
     context.bool_type()
 }
 
+/// The type of a `%Pauli` value: I = 0, X = 1, Z = 2 and Y = 3.
+#[must_use]
+pub(crate) fn pauli(context: &inkwell::context::Context) -> IntType {
+    context.custom_width_int_type(2)
+}
+
 #[must_use]
 pub(crate) fn qubit<'ctx>(
     context: &'ctx inkwell::context::Context,
@@ -46,6 +52,14 @@ pub(crate) fn result<'ctx>(
     get_or_define_struct(context, module, "Result")
 }
 
+#[must_use]
+pub(crate) fn array<'ctx>(
+    context: &'ctx inkwell::context::Context,
+    module: &inkwell::module::Module<'ctx>,
+) -> StructType<'ctx> {
+    get_or_define_struct(context, module, "Array")
+}
+
 #[must_use]
 pub(crate) fn get_struct<'ctx>(
     module: &inkwell::module::Module<'ctx>,
@@ -97,6 +111,15 @@ mod tests {
         verify_opaque_struct("Result", result(generator.context, &generator.module));
     }
 
+    #[test]
+    fn array_can_be_declared() {
+        let context = Context::create();
+        let module = context.create_module("test");
+        let generator = CodeGenerator::new(&context, module, false, false).unwrap();
+
+        verify_opaque_struct("Array", array(generator.context, &generator.module));
+    }
+
     fn verify_opaque_struct(name: &str, struct_type: StructType) {
         assert_eq!(struct_type.get_name().unwrap().to_str(), Ok(name));
         assert!(struct_type.is_opaque());
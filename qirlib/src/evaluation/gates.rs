@@ -9,8 +9,8 @@ type RESULT = u64;
 use mut_static::MutStatic;
 
 use crate::evaluation::interop::{
-    ClassicalRegister, Controlled, Instruction, Measured, QuantumRegister, Rotated, SemanticModel,
-    Single,
+    ClassicalRegister, Controlled, ControlledGate, DoublyControlled, Gate, Instruction, Measure,
+    Measured, Pauli, QuantumRegister, Rotated, SemanticModel, Single, Swapped,
 };
 
 lazy_static! {
@@ -118,6 +118,66 @@ impl BaseProfile {
             .add_inst(Instruction::Cz(BaseProfile::controlled(control, target)));
     }
 
+    pub fn ccx(&mut self, control1: QUBIT, control2: QUBIT, target: QUBIT) {
+        self.record_max_qubit_id(control1);
+        self.record_max_qubit_id(control2);
+        self.record_max_qubit_id(target);
+
+        log::debug!("ccx {}:{}:{}", control1, control2, target);
+        self.model
+            .add_inst(Instruction::Ccx(BaseProfile::doubly_controlled(
+                control1, control2, target,
+            )));
+    }
+
+    pub fn ccz(&mut self, control1: QUBIT, control2: QUBIT, target: QUBIT) {
+        self.record_max_qubit_id(control1);
+        self.record_max_qubit_id(control2);
+        self.record_max_qubit_id(target);
+
+        log::debug!("ccz {}:{}:{}", control1, control2, target);
+        self.model
+            .add_inst(Instruction::Ccz(BaseProfile::doubly_controlled(
+                control1, control2, target,
+            )));
+    }
+
+    pub fn swap(&mut self, qubit1: QUBIT, qubit2: QUBIT) {
+        self.record_max_qubit_id(qubit1);
+        self.record_max_qubit_id(qubit2);
+
+        log::debug!("swap {}:{}", qubit1, qubit2);
+        self.model.add_inst(Instruction::Swap(Swapped::new(
+            BaseProfile::get_qubit_string(qubit1),
+            BaseProfile::get_qubit_string(qubit2),
+        )));
+    }
+
+    pub fn controlled_gate(&mut self, gate: Gate, adjoint: bool, controls: &[QUBIT], qubit: QUBIT) {
+        for control in controls {
+            self.record_max_qubit_id(*control);
+        }
+        self.record_max_qubit_id(qubit);
+
+        log::debug!(
+            "{:?} ctl{} {:?}:{}",
+            gate,
+            if adjoint { "adj" } else { "" },
+            controls,
+            qubit
+        );
+        self.model
+            .add_inst(Instruction::ControlledGate(ControlledGate {
+                gate,
+                adjoint,
+                controls: controls
+                    .iter()
+                    .map(|control| BaseProfile::get_qubit_string(*control))
+                    .collect(),
+                qubit: BaseProfile::get_qubit_string(qubit),
+            }));
+    }
+
     pub fn h(&mut self, qubit: QUBIT) {
         self.record_max_qubit_id(qubit);
 
@@ -142,6 +202,21 @@ impl BaseProfile {
             .add_inst(Instruction::M(BaseProfile::measured(qubit, Some(result))));
     }
 
+    pub fn measure(&mut self, bases: &[Pauli], qubits: &[QUBIT]) {
+        for qubit in qubits {
+            self.record_max_qubit_id(*qubit);
+        }
+
+        log::debug!("measure {:?} {:?}", bases, qubits);
+        self.model.add_inst(Instruction::Measure(Measure {
+            bases: bases.to_vec(),
+            qubits: qubits
+                .iter()
+                .map(|qubit| BaseProfile::get_qubit_string(*qubit))
+                .collect(),
+        }));
+    }
+
     pub fn rx(&mut self, theta: f64, qubit: QUBIT) {
         self.record_max_qubit_id(qubit);
 
@@ -229,6 +304,14 @@ impl BaseProfile {
         )
     }
 
+    fn doubly_controlled(control1: QUBIT, control2: QUBIT, target: QUBIT) -> DoublyControlled {
+        DoublyControlled::new(
+            BaseProfile::get_qubit_string(control1),
+            BaseProfile::get_qubit_string(control2),
+            BaseProfile::get_qubit_string(target),
+        )
+    }
+
     fn measured(qubit: QUBIT, result: Option<RESULT>) -> Measured {
         Measured::new(
             BaseProfile::get_qubit_string(qubit),
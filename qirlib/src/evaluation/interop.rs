@@ -2,6 +2,7 @@
 // Licensed under the MIT License.
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QuantumRegister {
     pub name: String,
     pub index: u64,
@@ -20,6 +21,7 @@ impl QuantumRegister {
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClassicalRegister {
     pub name: String,
     pub size: u64,
@@ -38,12 +40,14 @@ impl ClassicalRegister {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Register {
     Quantum(QuantumRegister),
     Classical(ClassicalRegister),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Controlled {
     pub control: String,
     pub target: String,
@@ -57,6 +61,40 @@ impl Controlled {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DoublyControlled {
+    pub control1: String,
+    pub control2: String,
+    pub target: String,
+}
+
+impl DoublyControlled {
+    #[must_use]
+    pub fn new(control1: String, control2: String, target: String) -> Self {
+        DoublyControlled {
+            control1,
+            control2,
+            target,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Swapped {
+    pub qubit1: String,
+    pub qubit2: String,
+}
+
+impl Swapped {
+    #[must_use]
+    pub fn new(qubit1: String, qubit2: String) -> Self {
+        Swapped { qubit1, qubit2 }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Measured {
     pub qubit: String,
     pub target: String,
@@ -69,7 +107,26 @@ impl Measured {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Pauli {
+    I,
+    X,
+    Y,
+    Z,
+}
+
+/// A joint measurement of `qubits` in the product of the Pauli `bases`, with one basis for each
+/// qubit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Measure {
+    pub bases: Vec<Pauli>,
+    pub qubits: Vec<String>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rotated {
     pub theta: f64,
     pub qubit: String,
@@ -83,6 +140,7 @@ impl Rotated {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Single {
     pub qubit: String,
 }
@@ -94,13 +152,43 @@ impl Single {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gate {
+    H,
+    S,
+    T,
+    X,
+    Y,
+    Z,
+    Rx(f64),
+    Ry(f64),
+    Rz(f64),
+}
+
+/// A gate, or its adjoint, applied to `qubit` controlled on every qubit in `controls`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControlledGate {
+    pub gate: Gate,
+    pub adjoint: bool,
+    pub controls: Vec<String>,
+    pub qubit: String,
+}
+
 // https://github.com/microsoft/qsharp-language/blob/ageller/profile/Specifications/QIR/Base-Profile.md
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Instruction {
     Cx(Controlled),
     Cz(Controlled),
+    Ccx(DoublyControlled),
+    Ccz(DoublyControlled),
+    Swap(Swapped),
+    ControlledGate(ControlledGate),
     H(Single),
     M(Measured),
+    Measure(Measure),
     Reset(Single),
     Rx(Rotated),
     Ry(Rotated),
@@ -115,6 +203,7 @@ pub enum Instruction {
 }
 
 #[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SemanticModel {
     pub name: String,
     pub registers: Vec<ClassicalRegister>,
@@ -5,11 +5,14 @@
 #![allow(unused_variables)]
 
 use super::gates::BaseProfile;
+use super::interop::{Gate, Pauli};
 use bitvec::prelude::*;
 use lazy_static::lazy_static;
 use mut_static::ForceSomeRwLockWriteGuard;
 use std::collections::HashMap;
+use std::convert::TryInto;
 use std::ffi::c_void;
+use std::mem::size_of;
 use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
 use std::sync::Mutex;
 
@@ -49,6 +52,140 @@ fn get_current_gate_processor() -> ForceSomeRwLockWriteGuard<'static, BaseProfil
     v
 }
 
+/// A one-dimensional array created by `__quantum__rt__array_create_1d`. It is freed when its
+/// reference count drops to zero.
+pub struct QirArray {
+    item_size: usize,
+    data: Vec<u8>,
+    ref_count: i64,
+}
+
+impl QirArray {
+    /// Reads the items of an array of qubits.
+    fn qubits(&self) -> Vec<QUBIT> {
+        self.data
+            .chunks_exact(size_of::<QUBIT>())
+            .map(|item| QUBIT::from_ne_bytes(item.try_into().unwrap()))
+            .collect()
+    }
+
+    /// Reads the items of an array of `%Pauli` values, which are two-bit integers stored in one
+    /// byte each.
+    fn paulis(&self) -> Vec<Pauli> {
+        self.data
+            .iter()
+            .map(|item| match item & 0b11 {
+                0 => Pauli::I,
+                1 => Pauli::X,
+                2 => Pauli::Z,
+                _ => Pauli::Y,
+            })
+            .collect()
+    }
+}
+
+macro_rules! controlled_intrinsic {
+    ($name:ident, $gate:expr, $adjoint:expr) => {
+        /// # Safety
+        ///
+        /// This function should not be called directly. It is intended to be
+        /// called by QIR applications during JIT execution.
+        #[no_mangle]
+        pub unsafe extern "C" fn $name(controls: *const QirArray, qubit: QUBIT) {
+            log::debug!(concat!("/", stringify!($name), "/"));
+            let controls = (*controls).qubits();
+            let mut gs = get_current_gate_processor();
+            gs.controlled_gate($gate, $adjoint, &controls, qubit);
+        }
+    };
+}
+
+macro_rules! rotated_controlled_intrinsic {
+    ($name:ident, $gate:path, $adjoint:expr) => {
+        /// # Safety
+        ///
+        /// This function should not be called directly. It is intended to be
+        /// called by QIR applications during JIT execution.
+        #[no_mangle]
+        pub unsafe extern "C" fn $name(controls: *const QirArray, theta: f64, qubit: QUBIT) {
+            log::debug!(concat!("/", stringify!($name), "/"));
+            let controls = (*controls).qubits();
+            let mut gs = get_current_gate_processor();
+            gs.controlled_gate($gate(theta), $adjoint, &controls, qubit);
+        }
+    };
+}
+
+controlled_intrinsic!(__quantum__qis__h__ctl, Gate::H, false);
+controlled_intrinsic!(__quantum__qis__h__ctladj, Gate::H, true);
+controlled_intrinsic!(__quantum__qis__s__ctl, Gate::S, false);
+controlled_intrinsic!(__quantum__qis__s__ctladj, Gate::S, true);
+controlled_intrinsic!(__quantum__qis__t__ctl, Gate::T, false);
+controlled_intrinsic!(__quantum__qis__t__ctladj, Gate::T, true);
+controlled_intrinsic!(__quantum__qis__x__ctl, Gate::X, false);
+controlled_intrinsic!(__quantum__qis__x__ctladj, Gate::X, true);
+controlled_intrinsic!(__quantum__qis__y__ctl, Gate::Y, false);
+controlled_intrinsic!(__quantum__qis__y__ctladj, Gate::Y, true);
+controlled_intrinsic!(__quantum__qis__z__ctl, Gate::Z, false);
+controlled_intrinsic!(__quantum__qis__z__ctladj, Gate::Z, true);
+rotated_controlled_intrinsic!(__quantum__qis__rx__ctl, Gate::Rx, false);
+rotated_controlled_intrinsic!(__quantum__qis__rx__ctladj, Gate::Rx, true);
+rotated_controlled_intrinsic!(__quantum__qis__ry__ctl, Gate::Ry, false);
+rotated_controlled_intrinsic!(__quantum__qis__ry__ctladj, Gate::Ry, true);
+rotated_controlled_intrinsic!(__quantum__qis__rz__ctl, Gate::Rz, false);
+rotated_controlled_intrinsic!(__quantum__qis__rz__ctladj, Gate::Rz, true);
+
+/// The names and addresses of the `__ctl` and `__ctladj` intrinsics.
+pub(crate) fn controlled_intrinsics() -> [(&'static str, usize); 18] {
+    [
+        ("__quantum__qis__h__ctl", __quantum__qis__h__ctl as usize),
+        (
+            "__quantum__qis__h__ctladj",
+            __quantum__qis__h__ctladj as usize,
+        ),
+        ("__quantum__qis__s__ctl", __quantum__qis__s__ctl as usize),
+        (
+            "__quantum__qis__s__ctladj",
+            __quantum__qis__s__ctladj as usize,
+        ),
+        ("__quantum__qis__t__ctl", __quantum__qis__t__ctl as usize),
+        (
+            "__quantum__qis__t__ctladj",
+            __quantum__qis__t__ctladj as usize,
+        ),
+        ("__quantum__qis__x__ctl", __quantum__qis__x__ctl as usize),
+        (
+            "__quantum__qis__x__ctladj",
+            __quantum__qis__x__ctladj as usize,
+        ),
+        ("__quantum__qis__y__ctl", __quantum__qis__y__ctl as usize),
+        (
+            "__quantum__qis__y__ctladj",
+            __quantum__qis__y__ctladj as usize,
+        ),
+        ("__quantum__qis__z__ctl", __quantum__qis__z__ctl as usize),
+        (
+            "__quantum__qis__z__ctladj",
+            __quantum__qis__z__ctladj as usize,
+        ),
+        ("__quantum__qis__rx__ctl", __quantum__qis__rx__ctl as usize),
+        (
+            "__quantum__qis__rx__ctladj",
+            __quantum__qis__rx__ctladj as usize,
+        ),
+        ("__quantum__qis__ry__ctl", __quantum__qis__ry__ctl as usize),
+        (
+            "__quantum__qis__ry__ctladj",
+            __quantum__qis__ry__ctladj as usize,
+        ),
+        ("__quantum__qis__rz__ctl", __quantum__qis__rz__ctl as usize),
+        (
+            "__quantum__qis__rz__ctladj",
+            __quantum__qis__rz__ctladj as usize,
+        ),
+    ]
+}
+
 /// # Safety
 ///
 /// This function should not be called directly. It is intended to be
@@ -71,6 +208,39 @@ pub unsafe extern "C" fn __quantum__qis__cz__body(control: QUBIT, qubit: QUBIT)
     gs.cz(control, qubit);
 }
 
+/// # Safety
+///
+/// This function should not be called directly. It is intended to be
+/// called by QIR applications during JIT execution.
+#[no_mangle]
+pub unsafe extern "C" fn __quantum__qis__ccx__body(control1: QUBIT, control2: QUBIT, qubit: QUBIT) {
+    log::debug!("/__quantum__qis__ccx__body/");
+    let mut gs = get_current_gate_processor();
+    gs.ccx(control1, control2, qubit);
+}
+
+/// # Safety
+///
+/// This function should not be called directly. It is intended to be
+/// called by QIR applications during JIT execution.
+#[no_mangle]
+pub unsafe extern "C" fn __quantum__qis__ccz__body(control1: QUBIT, control2: QUBIT, qubit: QUBIT) {
+    log::debug!("/__quantum__qis__ccz__body/");
+    let mut gs = get_current_gate_processor();
+    gs.ccz(control1, control2, qubit);
+}
+
+/// # Safety
+///
+/// This function should not be called directly. It is intended to be
+/// called by QIR applications during JIT execution.
+#[no_mangle]
+pub unsafe extern "C" fn __quantum__qis__swap__body(qubit1: QUBIT, qubit2: QUBIT) {
+    log::debug!("/__quantum__qis__swap__body/");
+    let mut gs = get_current_gate_processor();
+    gs.swap(qubit1, qubit2);
+}
+
 /// # Safety
 ///
 /// This function should not be called directly. It is intended to be
@@ -222,6 +392,34 @@ pub extern "C" fn __quantum__qis__m__body(qubit: QUBIT) -> *mut c_void {
     }
 }
 
+/// # Safety
+///
+/// This function should not be called directly. It is intended to be
+/// called by QIR applications during JIT execution.
+///
+/// # Panics
+///
+/// This function will panic if the global state cannot be locked.
+#[no_mangle]
+pub unsafe extern "C" fn __quantum__qis__measure__body(
+    bases: *const QirArray,
+    qubits: *const QirArray,
+) -> *mut c_void {
+    log::debug!("/__quantum__qis__measure__body/");
+    let bases = (*bases).paulis();
+    let qubits = (*qubits).qubits();
+    let mut gs = get_current_gate_processor();
+    gs.measure(&bases, &qubits);
+
+    let mut res = RESULTS.lock().unwrap();
+
+    if res.pop() == Some(true) {
+        __quantum__rt__result_get_one()
+    } else {
+        __quantum__rt__result_get_zero()
+    }
+}
+
 /// # Panics
 ///
 /// This function will panic if the global state cannot be locked or if the result index is too
@@ -288,3 +486,50 @@ pub extern "C" fn __quantum__rt__qubit_release(qubit: QUBIT) {
     log::debug!("/__quantum__rt__qubit_release/");
     (*MAX_QUBIT_ID).fetch_sub(1, Relaxed);
 }
+
+#[no_mangle]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub extern "C" fn __quantum__rt__array_create_1d(item_size: i32, count: i64) -> *mut QirArray {
+    log::debug!("/__quantum__rt__array_create_1d/");
+    let item_size = item_size as usize;
+    Box::into_raw(Box::new(QirArray {
+        item_size,
+        data: vec![0; item_size * count as usize],
+        ref_count: 1,
+    }))
+}
+
+/// # Safety
+///
+/// The array must have been created by `__quantum__rt__array_create_1d` and not yet freed.
+#[no_mangle]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub unsafe extern "C" fn __quantum__rt__array_get_element_ptr_1d(
+    array: *mut QirArray,
+    index: i64,
+) -> *mut i8 {
+    log::debug!("/__quantum__rt__array_get_element_ptr_1d/");
+    let array = &mut *array;
+    let offset = array.item_size * index as usize;
+    array.data[offset..].as_mut_ptr().cast()
+}
+
+/// # Safety
+///
+/// The array must be null or have been created by `__quantum__rt__array_create_1d` and not yet
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn __quantum__rt__array_update_reference_count(
+    array: *mut QirArray,
+    increment: i32,
+) {
+    log::debug!("/__quantum__rt__array_update_reference_count/");
+    if array.is_null() {
+        return;
+    }
+
+    (*array).ref_count += i64::from(increment);
+    if (*array).ref_count <= 0 {
+        drop(Box::from_raw(array));
+    }
+}
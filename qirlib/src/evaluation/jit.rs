@@ -14,6 +14,7 @@ use inkwell::{
     execution_engine::ExecutionEngine,
     module::Module,
     targets::{InitializationConfig, Target, TargetMachine},
+    types::BasicTypeEnum,
     values::FunctionValue,
     OptimizationLevel,
 };
@@ -25,26 +26,66 @@ use std::path::Path;
 /// - Module fails to load.
 /// - LLVM fails to initialize local JIT Engine and components
 /// - Entrypoint cannot be resolved
+/// - Entrypoint has parameters
 /// - Module contains unknown external functions
 pub fn run_module_file(
     path: impl AsRef<Path>,
     entry_point: Option<&str>,
     result_stream: Option<BitVec>,
+) -> Result<SemanticModel, String> {
+    run_module_file_with_args(path, entry_point, &[], result_stream)
+}
+
+/// Runs the entry point of the module file with the given arguments, as `run_module_with_args`
+/// does.
+///
+/// # Errors
+///
+/// - Path has an unsupported extension.
+/// - Module fails to load.
+/// - LLVM fails to initialize local JIT Engine and components
+/// - Entrypoint cannot be resolved
+/// - Entrypoint parameters do not match the arguments
+/// - Module contains unknown external functions
+pub fn run_module_file_with_args(
+    path: impl AsRef<Path>,
+    entry_point: Option<&str>,
+    args: &[f64],
+    result_stream: Option<BitVec>,
 ) -> Result<SemanticModel, String> {
     let context = Context::create();
     let module = module::load_file(path, &context)?;
-    run_module(&module, entry_point, result_stream)
+    run_module_with_args(&module, entry_point, args, result_stream)
 }
 
 /// # Errors
 ///
 /// - LLVM fails to initialize local JIT Engine and components
 /// - Entrypoint cannot be resolved
+/// - Entrypoint has parameters
 /// - Module contains unknown external functions
 pub fn run_module(
     module: &Module,
     entry_point: Option<&str>,
     result_stream: Option<BitVec>,
+) -> Result<SemanticModel, String> {
+    run_module_with_args(module, entry_point, &[], result_stream)
+}
+
+/// Runs the entry point of the module. The entry point must return void, and may only have double
+/// parameters, one for each of the given arguments.
+///
+/// # Errors
+///
+/// - LLVM fails to initialize local JIT Engine and components
+/// - Entrypoint cannot be resolved
+/// - Entrypoint parameters do not match the arguments
+/// - Module contains unknown external functions
+pub fn run_module_with_args(
+    module: &Module,
+    entry_point: Option<&str>,
+    args: &[f64],
+    result_stream: Option<BitVec>,
 ) -> Result<SemanticModel, String> {
     Target::initialize_native(&InitializationConfig::default())?;
 
@@ -76,7 +117,7 @@ pub fn run_module(
     let _simulator = Simulator::new(module, &execution_engine)?;
 
     unsafe {
-        run_entry_point(&execution_engine, entry_point)?;
+        run_entry_point(&execution_engine, entry_point, args)?;
     }
 
     Ok(Simulator::get_model())
@@ -85,13 +126,36 @@ pub fn run_module(
 unsafe fn run_entry_point(
     execution_engine: &ExecutionEngine,
     entry_point: FunctionValue,
+    args: &[f64],
 ) -> Result<(), String> {
-    if entry_point.count_params() == 0 && entry_point.get_type().get_return_type().is_none() {
-        execution_engine.run_function(entry_point, &[]);
-        Ok(())
-    } else {
-        Err("Entry point has parameters or a non-void return type.".to_owned())
+    let ty = entry_point.get_type();
+    let context = ty.get_context();
+    let double_type = context.f64_type();
+    if ty.get_return_type().is_some()
+        || ty
+            .get_param_types()
+            .iter()
+            .any(|param| *param != BasicTypeEnum::FloatType(double_type))
+    {
+        return Err("Entry point has non-double parameters or a non-void return type.".to_owned());
     }
+
+    let count = entry_point.count_params() as usize;
+    if count != args.len() {
+        return Err(format!(
+            "Entry point expects {} arguments, got {}.",
+            count,
+            args.len()
+        ));
+    }
+
+    let args: Vec<_> = args
+        .iter()
+        .map(|arg| double_type.create_generic_value(*arg))
+        .collect();
+    let args: Vec<_> = args.iter().collect();
+    execution_engine.run_function(entry_point, &args);
+    Ok(())
 }
 
 fn choose_entry_point<'ctx>(
@@ -138,8 +202,11 @@ pub(crate) fn module_functions<'ctx>(
 
 #[cfg(test)]
 mod tests {
-    use super::{run_module, run_module_file};
-    use crate::evaluation::interop::{Instruction, SemanticModel, Single};
+    use super::{run_module, run_module_file, run_module_file_with_args};
+    use crate::evaluation::interop::{
+        ControlledGate, DoublyControlled, Gate, Instruction, Rotated, SemanticModel, Single,
+        Swapped,
+    };
     use crate::module;
     use inkwell::context::Context;
     use serial_test::serial;
@@ -243,7 +310,7 @@ mod tests {
         let result = run_test_module(ENTRY_POINT_TYPES, Some("App__IntParam"));
         assert_eq!(
             result.err(),
-            Some("Entry point has parameters or a non-void return type.".to_owned())
+            Some("Entry point has non-double parameters or a non-void return type.".to_owned())
         );
         Ok(())
     }
@@ -254,21 +321,85 @@ mod tests {
         let result = run_test_module(ENTRY_POINT_TYPES, Some("App__IntReturn"));
         assert_eq!(
             result.err(),
-            Some("Entry point has parameters or a non-void return type.".to_owned())
+            Some("Entry point has non-double parameters or a non-void return type.".to_owned())
         );
         Ok(())
     }
 
     #[serial]
     #[test]
-    fn fails_if_unknown_external_func() -> Result<(), String> {
-        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        path.push("resources");
-        path.push("tests");
-        path.push("test_unknown_external_func");
-        path.set_extension("ll");
+    fn runs_entry_point_with_double_params() -> Result<(), String> {
+        let model =
+            run_module_file_with_args(test_file("entry_point_double_params"), None, &[0.5], None)?;
+        assert_eq!(
+            model.instructions,
+            vec![Instruction::Rx(Rotated::new(0.5, "0".to_owned()))]
+        );
+        Ok(())
+    }
 
-        let result = run_module_file(path, None, None);
+    #[serial]
+    #[test]
+    fn fails_if_wrong_number_of_args() -> Result<(), String> {
+        let result = run_module_file(test_file("entry_point_double_params"), None, None);
+        assert_eq!(
+            result.err(),
+            Some("Entry point expects 1 arguments, got 0.".to_owned())
+        );
+        Ok(())
+    }
+
+    #[serial]
+    #[test]
+    fn runs_multi_qubit_gates() -> Result<(), String> {
+        let model = run_module_file(test_file("multi_qubit_gates"), None, None)?;
+        assert_eq!(
+            model.instructions,
+            vec![
+                Instruction::Ccx(DoublyControlled::new(
+                    "0".to_owned(),
+                    "1".to_owned(),
+                    "2".to_owned()
+                )),
+                Instruction::Ccz(DoublyControlled::new(
+                    "2".to_owned(),
+                    "0".to_owned(),
+                    "1".to_owned()
+                )),
+                Instruction::Swap(Swapped::new("0".to_owned(), "2".to_owned())),
+            ]
+        );
+        Ok(())
+    }
+
+    #[serial]
+    #[test]
+    fn runs_controlled_gates() -> Result<(), String> {
+        let model = run_module_file(test_file("controlled_gates"), None, None)?;
+        assert_eq!(
+            model.instructions,
+            vec![
+                Instruction::ControlledGate(ControlledGate {
+                    gate: Gate::X,
+                    adjoint: false,
+                    controls: vec!["0".to_owned(), "1".to_owned()],
+                    qubit: "2".to_owned(),
+                }),
+                Instruction::ControlledGate(ControlledGate {
+                    gate: Gate::Rz(0.5),
+                    adjoint: true,
+                    controls: vec!["0".to_owned(), "1".to_owned()],
+                    qubit: "2".to_owned(),
+                }),
+            ]
+        );
+        Ok(())
+    }
+
+    #[serial]
+    #[test]
+    fn fails_if_unknown_external_func() -> Result<(), String> {
+        let result = run_module_file(test_file("test_unknown_external_func"), None, None);
         assert_eq!(
             result.err(),
             Some("Unsupported functions `__quantum__rt__bool_to_string`.".to_owned())
@@ -281,4 +412,13 @@ mod tests {
         let module = module::load_memory(bytes, "test", &context)?;
         run_module(&module, entry_point, None)
     }
+
+    fn test_file(name: &str) -> PathBuf {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("resources");
+        path.push("tests");
+        path.push(name);
+        path.set_extension("ll");
+        path
+    }
 }
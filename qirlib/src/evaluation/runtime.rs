@@ -11,6 +11,7 @@ use log;
 use inkwell::values::FunctionValue;
 
 use super::gates::GateScope;
+use super::intrinsics::controlled_intrinsics;
 use super::jit::module_functions;
 
 pub(crate) struct Simulator {
@@ -68,6 +69,15 @@ impl<'ctx> Simulator {
         if let Some(ins) = intrinsics.cz {
             ee.add_global_mapping(&ins, super::intrinsics::__quantum__qis__cz__body as usize);
         }
+        if let Some(ins) = intrinsics.ccx {
+            ee.add_global_mapping(&ins, super::intrinsics::__quantum__qis__ccx__body as usize);
+        }
+        if let Some(ins) = intrinsics.ccz {
+            ee.add_global_mapping(&ins, super::intrinsics::__quantum__qis__ccz__body as usize);
+        }
+        if let Some(ins) = intrinsics.swap {
+            ee.add_global_mapping(&ins, super::intrinsics::__quantum__qis__swap__body as usize);
+        }
         if let Some(ins) = intrinsics.h {
             ee.add_global_mapping(&ins, super::intrinsics::__quantum__qis__h__body as usize);
         }
@@ -101,6 +111,9 @@ impl<'ctx> Simulator {
         if let Some(ins) = intrinsics.r_z {
             ee.add_global_mapping(&ins, super::intrinsics::__quantum__qis__rz__body as usize);
         }
+        for (function, address) in intrinsics.controlled {
+            ee.add_global_mapping(&function, address);
+        }
         if let Some(ins) = intrinsics.reset {
             ee.add_global_mapping(
                 &ins,
@@ -116,6 +129,13 @@ impl<'ctx> Simulator {
             ee.add_global_mapping(&ins, super::intrinsics::__quantum__qis__mz__body as usize);
         }
 
+        if let Some(ins) = intrinsics.measure {
+            ee.add_global_mapping(
+                &ins,
+                super::intrinsics::__quantum__qis__measure__body as usize,
+            );
+        }
+
         if let Some(ins) = intrinsics.read_result {
             ee.add_global_mapping(
                 &ins,
@@ -154,14 +174,36 @@ impl<'ctx> Simulator {
                 super::intrinsics::__quantum__rt__qubit_release as usize,
             );
         }
+        if let Some(ins) = runtime.array_create_1d {
+            ee.add_global_mapping(
+                &ins,
+                super::intrinsics::__quantum__rt__array_create_1d as usize,
+            );
+        }
+        if let Some(ins) = runtime.array_get_element_ptr_1d {
+            ee.add_global_mapping(
+                &ins,
+                super::intrinsics::__quantum__rt__array_get_element_ptr_1d as usize,
+            );
+        }
+        if let Some(ins) = runtime.array_update_reference_count {
+            ee.add_global_mapping(
+                &ins,
+                super::intrinsics::__quantum__rt__array_update_reference_count as usize,
+            );
+        }
     }
 }
 
 pub struct Intrinsics<'ctx> {
     pub cnot: Option<FunctionValue<'ctx>>,
     pub cz: Option<FunctionValue<'ctx>>,
+    pub ccx: Option<FunctionValue<'ctx>>,
+    pub ccz: Option<FunctionValue<'ctx>>,
+    pub swap: Option<FunctionValue<'ctx>>,
     pub m: Option<FunctionValue<'ctx>>,
     pub mz: Option<FunctionValue<'ctx>>,
+    pub measure: Option<FunctionValue<'ctx>>,
     pub r_x: Option<FunctionValue<'ctx>>,
     pub r_y: Option<FunctionValue<'ctx>>,
     pub r_z: Option<FunctionValue<'ctx>>,
@@ -175,6 +217,8 @@ pub struct Intrinsics<'ctx> {
     pub t: Option<FunctionValue<'ctx>>,
     pub t_adj: Option<FunctionValue<'ctx>>,
     pub read_result: Option<FunctionValue<'ctx>>,
+    /// The `__ctl` and `__ctladj` intrinsics declared in the module, with their addresses.
+    pub controlled: Vec<(FunctionValue<'ctx>, usize)>,
 }
 
 impl<'ctx> Intrinsics<'ctx> {
@@ -182,8 +226,12 @@ impl<'ctx> Intrinsics<'ctx> {
         let intrinsics = Intrinsics {
             cnot: Intrinsics::get_qis_intrinsic_function_body(module, "cnot"),
             cz: Intrinsics::get_qis_intrinsic_function_body(module, "Cz"),
+            ccx: Intrinsics::get_qis_intrinsic_function_body(module, "Ccx"),
+            ccz: Intrinsics::get_qis_intrinsic_function_body(module, "Ccz"),
+            swap: Intrinsics::get_qis_intrinsic_function_body(module, "Swap"),
             m: Intrinsics::get_qis_intrinsic_function_body(module, "M"),
             mz: Intrinsics::get_qis_intrinsic_function_body(module, "mz"),
+            measure: Intrinsics::get_qis_intrinsic_function_body(module, "measure"),
             r_x: Intrinsics::get_qis_intrinsic_function_body(module, "Rx"),
             r_y: Intrinsics::get_qis_intrinsic_function_body(module, "Ry"),
             r_z: Intrinsics::get_qis_intrinsic_function_body(module, "Rz"),
@@ -197,6 +245,10 @@ impl<'ctx> Intrinsics<'ctx> {
             t: Intrinsics::get_qis_intrinsic_function_body(module, "T"),
             t_adj: Intrinsics::get_qis_intrinsic_function_adj(module, "T"),
             read_result: Intrinsics::get_qis_intrinsic_function_body(module, "read_result"),
+            controlled: controlled_intrinsics()
+                .iter()
+                .filter_map(|(name, address)| Some((get_function(module, name)?, *address)))
+                .collect(),
         };
 
         intrinsics
@@ -223,8 +275,12 @@ impl<'ctx> Intrinsics<'ctx> {
             name,
             "__quantum__qis__cnot__body"
                 | "__quantum__qis__cz__body"
+                | "__quantum__qis__ccx__body"
+                | "__quantum__qis__ccz__body"
+                | "__quantum__qis__swap__body"
                 | "__quantum__qis__m__body"
                 | "__quantum__qis__mz__body"
+                | "__quantum__qis__measure__body"
                 | "__quantum__qis__rx__body"
                 | "__quantum__qis__ry__body"
                 | "__quantum__qis__rz__body"
@@ -238,7 +294,9 @@ impl<'ctx> Intrinsics<'ctx> {
                 | "__quantum__qis__t__body"
                 | "__quantum__qis__t__adj"
                 | "__quantum__qis__read_result__body"
-        )
+        ) || controlled_intrinsics()
+            .iter()
+            .any(|(controlled, _)| *controlled == name)
     }
 }
 
@@ -248,6 +306,9 @@ pub struct Runtime<'ctx> {
     pub result_equal: Option<FunctionValue<'ctx>>,
     pub qubit_allocate: Option<FunctionValue<'ctx>>,
     pub qubit_release: Option<FunctionValue<'ctx>>,
+    pub array_create_1d: Option<FunctionValue<'ctx>>,
+    pub array_get_element_ptr_1d: Option<FunctionValue<'ctx>>,
+    pub array_update_reference_count: Option<FunctionValue<'ctx>>,
 }
 
 impl<'ctx> Runtime<'ctx> {
@@ -258,6 +319,15 @@ impl<'ctx> Runtime<'ctx> {
             result_equal: Runtime::get_rt_intrinsic_function_body(module, "result_equal"),
             qubit_allocate: Runtime::get_rt_intrinsic_function_body(module, "qubit_allocate"),
             qubit_release: Runtime::get_rt_intrinsic_function_body(module, "qubit_release"),
+            array_create_1d: Runtime::get_rt_intrinsic_function_body(module, "array_create_1d"),
+            array_get_element_ptr_1d: Runtime::get_rt_intrinsic_function_body(
+                module,
+                "array_get_element_ptr_1d",
+            ),
+            array_update_reference_count: Runtime::get_rt_intrinsic_function_body(
+                module,
+                "array_update_reference_count",
+            ),
         };
 
         intrinsics
@@ -279,6 +349,9 @@ impl<'ctx> Runtime<'ctx> {
                 | "__quantum__rt__result_equal"
                 | "__quantum__rt__qubit_allocate"
                 | "__quantum__rt__qubit_release"
+                | "__quantum__rt__array_create_1d"
+                | "__quantum__rt__array_get_element_ptr_1d"
+                | "__quantum__rt__array_update_reference_count"
         )
     }
 }
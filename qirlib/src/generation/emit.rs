@@ -4,25 +4,34 @@
 use crate::{
     codegen::CodeGenerator,
     generation::{
-        interop::{self, ReturnType, SemanticModel, ValueType},
+        error::GenerationError,
+        interop::{
+            self, ClassicalRegister, Instruction, NamedMetadata, Parameter, Profile,
+            QuantumRegister, ReturnType, SemanticModel, Subroutine, ValueType,
+        },
         qir,
+        scope::Scope,
     },
     passes::run_basic_passes_on,
 };
 use inkwell::{
     attributes::AttributeLoc,
     context::Context,
-    module::Linkage,
+    module::{Linkage, Module},
     types::{BasicType, BasicTypeEnum, FunctionType},
-    values::{BasicValueEnum, FunctionValue, PointerValue},
+    values::{BasicMetadataValueEnum, BasicValueEnum, FunctionValue, PointerValue},
     AddressSpace,
 };
-use std::{collections::HashMap, convert::Into};
+use std::{collections::HashMap, convert::Into, ffi::CStr, iter, slice};
 
 /// # Errors
 ///
-/// Will return `Err` if module fails verification that the current `Module` is valid.
-pub fn ir(model: &SemanticModel) -> Result<String, String> {
+/// Will return `Err` if
+///  - an instruction refers to an unknown qubit, result, or function.
+///  - an instruction refers to a qubit or result by a name that more than one register element has.
+///  - an instruction or dynamic allocation is not allowed by the profile of the model.
+///  - module fails verification that the current `Module` is valid.
+pub fn ir(model: &SemanticModel) -> Result<String, GenerationError> {
     let ctx = Context::create();
     let generator = populate_context(&ctx, model)?;
     run_basic_passes_on(&generator.module);
@@ -31,8 +40,12 @@ pub fn ir(model: &SemanticModel) -> Result<String, String> {
 
 /// # Errors
 ///
-/// Will return `Err` if module fails verification that the current `Module` is valid.
-pub fn bitcode(model: &SemanticModel) -> Result<Vec<u8>, String> {
+/// Will return `Err` if
+///  - an instruction refers to an unknown qubit, result, or function.
+///  - an instruction refers to a qubit or result by a name that more than one register element has.
+///  - an instruction or dynamic allocation is not allowed by the profile of the model.
+///  - module fails verification that the current `Module` is valid.
+pub fn bitcode(model: &SemanticModel) -> Result<Vec<u8>, GenerationError> {
     let ctx = Context::create();
     let generator = populate_context(&ctx, model)?;
     run_basic_passes_on(&generator.module);
@@ -43,28 +56,382 @@ pub fn bitcode(model: &SemanticModel) -> Result<Vec<u8>, String> {
 ///
 /// Will return `Err` if
 ///  - module cannot be loaded.
+///  - an instruction refers to an unknown qubit, result, or function.
+///  - an instruction refers to a qubit or result by a name that more than one register element has.
+///  - an instruction or dynamic allocation is not allowed by the profile of the model.
 ///  - module fails verification that the current `Module` is valid.
 pub fn populate_context<'a>(
     ctx: &'a Context,
     model: &'a SemanticModel,
-) -> Result<CodeGenerator<'a>, String> {
-    let module = ctx.create_module(&model.name);
-    let generator = CodeGenerator::new(
+) -> Result<CodeGenerator<'a>, GenerationError> {
+    populate_module(ctx, ctx.create_module(&model.name), model)
+}
+
+/// Emits the model into an existing module, such as one loaded with `module::load_file`, so that
+/// generated code can be added to hand-written or compiler-produced QIR. Types, declarations and
+/// module flags with the same values that the module already has are reused. A function in the module that the model
+/// calls as an external function must have the declared type, and a declaration of a subroutine
+/// or entry point with the same type is defined by the generated body.
+///
+/// # Errors
+///
+/// Will return `Err` if
+///  - the module already defines a subroutine or entry point of the model, or has a function with
+///    the same name and another type.
+///  - the module has a module flag for the profile with a different value.
+///  - an instruction refers to an unknown qubit, result, or function.
+///  - an instruction refers to a qubit or result by a name that more than one register element has.
+///  - an instruction or dynamic allocation is not allowed by the profile of the model.
+///  - module fails verification that the current `Module` is valid.
+pub fn populate_module<'a>(
+    ctx: &'a Context,
+    module: Module<'a>,
+    model: &'a SemanticModel,
+) -> Result<CodeGenerator<'a>, GenerationError> {
+    let mut generator = CodeGenerator::new(
         ctx,
         module,
         model.use_static_qubit_alloc,
         model.use_static_result_alloc,
-    )?;
-    build_entry_function(&generator, model)?;
+    )
+    .map_err(GenerationError::Verification)?;
+    generator.use_array_qubit_alloc = model.use_array_qubit_alloc;
+    generator.max_block_size = model.max_block_size;
+
+    if let Some(profile) = model.profile {
+        if !profile.allows_dynamic_alloc()
+            && !(model.use_static_qubit_alloc && model.use_static_result_alloc)
+        {
+            return Err(GenerationError::NotInProfile(profile));
+        }
+        check_profile(profile, &model.instructions)?;
+        for subroutine in &model.subroutines {
+            check_profile(profile, &subroutine.instructions)?;
+        }
+        for entry_point in &model.entry_points {
+            check_profile(profile, &entry_point.instructions)?;
+        }
+        add_module_flags(
+            &generator,
+            model.use_static_qubit_alloc,
+            model.use_static_result_alloc,
+        )?;
+    }
+    add_metadata(&generator, &model.metadata)?;
+
+    let bodies = iter::once(&model.instructions)
+        .chain(model.subroutines.iter().map(|s| &s.instructions))
+        .chain(model.entry_points.iter().map(|e| &e.instructions));
+    if bodies.into_iter().any(|body| has_spans(body)) {
+        generator.enable_debug_info();
+    }
+
+    add_external_functions(&generator, model.external_functions.iter())?;
+    build_subroutines(&generator, &model.subroutines)?;
+    let output = if model.record_output {
+        Output::Record {
+            grouped: model.profile.map_or(true, Profile::allows_grouped_output),
+        }
+    } else {
+        Output::Discard
+    };
+    build_entry_functions(&generator, model, output)?;
+    if model.interop_wrappers {
+        build_interop_wrappers(&generator, model)?;
+    }
+
+    let mut attributes: Vec<_> = model
+        .entry_point_attributes
+        .iter()
+        .map(|(key, value)| generator.context.create_string_attribute(key, value))
+        .collect();
+    if let Some(profile) = model.profile {
+        attributes.push(
+            generator
+                .context
+                .create_string_attribute("qir_profiles", profile_name(profile)),
+        );
+    }
+
+    let names = iter::once(model.entry_point_name.as_str())
+        .chain(model.entry_points.iter().map(|e| e.name.as_str()));
+    for entry_point in names.filter_map(|name| generator.module.get_function(name)) {
+        for attribute in &attributes {
+            entry_point.add_attribute(AttributeLoc::Function, *attribute);
+        }
+    }
+
+    generator.finalize_debug_info();
+    generator
+        .module
+        .verify()
+        .map_err(|e| GenerationError::Verification(e.to_string()))?;
     Ok(generator)
 }
 
-fn build_entry_function(generator: &CodeGenerator, model: &SemanticModel) -> Result<(), String> {
-    add_external_functions(generator, model.external_functions.iter());
-    let entry_point = qir::create_entry_point(generator.context, &generator.module);
+fn check_profile(profile: Profile, instructions: &[Instruction]) -> Result<(), GenerationError> {
+    for inst in instructions {
+        if !profile.allows(inst) {
+            return Err(GenerationError::NotInProfile(profile));
+        }
+
+        match inst {
+            Instruction::If(if_) => {
+                check_profile(profile, &if_.then_insts)?;
+                check_profile(profile, &if_.else_insts)?;
+            }
+            Instruction::Switch(switch) => {
+                for case in &switch.cases {
+                    check_profile(profile, &case.insts)?;
+                }
+                check_profile(profile, &switch.default_insts)?;
+            }
+            Instruction::For(for_) => check_profile(profile, &for_.body)?,
+            Instruction::Spanned(spanned) => {
+                check_profile(profile, slice::from_ref(spanned.inst.as_ref()))?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn has_spans(instructions: &[Instruction]) -> bool {
+    instructions.iter().any(|inst| match inst {
+        Instruction::Spanned(_) => true,
+        Instruction::If(if_) => has_spans(&if_.then_insts) || has_spans(&if_.else_insts),
+        Instruction::Switch(switch) => {
+            switch.cases.iter().any(|case| has_spans(&case.insts))
+                || has_spans(&switch.default_insts)
+        }
+        Instruction::For(for_) => has_spans(&for_.body),
+        _ => false,
+    })
+}
+
+fn profile_name(profile: Profile) -> &'static str {
+    match profile {
+        Profile::Base => "base_profile",
+        Profile::Adaptive => "adaptive_profile",
+    }
+}
+
+/// Adds the module flags that declare the QIR version and whether qubits and results are managed
+/// dynamically.
+fn add_module_flags(
+    generator: &CodeGenerator,
+    use_static_qubit_alloc: bool,
+    use_static_result_alloc: bool,
+) -> Result<(), GenerationError> {
+    const ERROR: u64 = 1;
+    const MAX: u64 = 7;
+
+    let context = generator.context;
+    let i32_type = context.i32_type();
+    let bool_type = context.bool_type();
+    let flags = [
+        ("qir_major_version", ERROR, i32_type.const_int(1, false)),
+        ("qir_minor_version", MAX, i32_type.const_int(0, false)),
+        (
+            "dynamic_qubit_management",
+            ERROR,
+            bool_type.const_int((!use_static_qubit_alloc).into(), false),
+        ),
+        (
+            "dynamic_result_management",
+            ERROR,
+            bool_type.const_int((!use_static_result_alloc).into(), false),
+        ),
+    ];
+
+    for (name, behavior, value) in flags {
+        // An existing module may already have the flag, and flags must be unique.
+        match module_flag(generator, name) {
+            Some(existing) if existing == BasicMetadataValueEnum::from(value) => continue,
+            Some(_) => return Err(GenerationError::ModuleFlagMismatch(name.to_string())),
+            None => {}
+        }
+
+        let flag = context.metadata_node(&[
+            i32_type.const_int(behavior, false).into(),
+            context.metadata_string(name).into(),
+            value.into(),
+        ]);
+        generator
+            .module
+            .add_global_metadata("llvm.module.flags", &flag)
+            .map_err(|e| GenerationError::Verification(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+/// The value of the named module flag, if the module has it.
+fn module_flag<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    name: &str,
+) -> Option<BasicMetadataValueEnum<'ctx>> {
+    generator
+        .module
+        .get_global_metadata("llvm.module.flags")
+        .into_iter()
+        .find_map(|flag| match flag.get_node_values().as_slice() {
+            [_, BasicMetadataValueEnum::MetadataValue(key), value]
+                if key.get_string_value().map(CStr::to_bytes) == Some(name.as_bytes()) =>
+            {
+                Some(*value)
+            }
+            _ => None,
+        })
+}
+
+fn add_metadata(
+    generator: &CodeGenerator,
+    metadata: &[NamedMetadata],
+) -> Result<(), GenerationError> {
+    let context = generator.context;
+    for named in metadata {
+        let values: Vec<BasicMetadataValueEnum> = named
+            .values
+            .iter()
+            .map(|value| context.metadata_string(value).into())
+            .collect();
+        generator
+            .module
+            .add_global_metadata(&named.name, &context.metadata_node(&values))
+            .map_err(|e| GenerationError::Verification(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+fn build_subroutines(
+    generator: &CodeGenerator,
+    subroutines: &[Subroutine],
+) -> Result<(), GenerationError> {
+    // Declare every subroutine before building any bodies so that subroutines can call each other.
+    let functions = subroutines
+        .iter()
+        .map(|subroutine| {
+            let ty = get_function_type(generator, &subroutine.function_type());
+            qir::declare_function(
+                &generator.module,
+                &subroutine.name,
+                ty,
+                Some(Linkage::Internal),
+            )
+            .ok_or_else(|| GenerationError::FunctionExists(subroutine.name.clone()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (subroutine, function) in subroutines.iter().zip(functions) {
+        let entry = generator.context.append_basic_block(function, "entry");
+        generator.builder.position_at_end(entry);
+        generator.attach_debug_info(function);
+
+        let mut qubits = Scope::new();
+        let mut results = Scope::new();
+        let mut variables = HashMap::new();
+        for (param, value) in subroutine.params.iter().zip(function.get_param_iter()) {
+            value.set_name(&param.name);
+            match param.ty {
+                ValueType::Qubit => qubits.insert_variable(param.name.clone(), value),
+                ValueType::Result => {
+                    results.insert_variable(param.name.clone(), Some(value.into_pointer_value()));
+                }
+                ValueType::Integer { .. } | ValueType::Double | ValueType::String => {}
+            }
+            variables.insert(param.name.clone(), value);
+        }
+
+        // A dynamically allocated result is passed by value, so measuring into a parameter would
+        // only rebind it inside the subroutine. The only results in scope are the parameters.
+        if !generator.use_static_result_alloc {
+            if let Some(target) = qir::instructions::measured_results(&subroutine.instructions)
+                .into_iter()
+                .find(|target| results.resolve(target).is_ok())
+            {
+                return Err(GenerationError::MeasureParameter(target.to_string()));
+            }
+        }
+
+        write_instructions(
+            &subroutine.instructions,
+            generator,
+            &qubits,
+            &mut results,
+            &mut variables,
+            function,
+        )?;
+
+        generator.builder.build_return(None);
+    }
+
+    Ok(())
+}
+
+/// How an entry function makes the final values of its results available.
+#[derive(Clone, Copy)]
+enum Output {
+    /// The results are not output.
+    Discard,
+
+    /// The results are recorded with the output recording functions of the runtime, grouped by
+    /// register with array and tuple records if `grouped` is set.
+    Record { grouped: bool },
+}
+
+/// Builds the `main` entry function and each additional one, with the given output.
+fn build_entry_functions(
+    generator: &CodeGenerator,
+    model: &SemanticModel,
+    output: Output,
+) -> Result<(), GenerationError> {
+    build_entry_function(
+        generator,
+        &model.entry_point_name,
+        &model.params,
+        &model.registers,
+        &model.qubits,
+        &model.instructions,
+        output,
+    )?;
+
+    for entry_point in &model.entry_points {
+        build_entry_function(
+            generator,
+            &entry_point.name,
+            &entry_point.params,
+            &entry_point.registers,
+            &entry_point.qubits,
+            &entry_point.instructions,
+            output,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn build_entry_function(
+    generator: &CodeGenerator,
+    name: &str,
+    params: &[Parameter],
+    registers: &[ClassicalRegister],
+    qubits: &[QuantumRegister],
+    instructions: &[Instruction],
+    output: Output,
+) -> Result<(), GenerationError> {
+    let param_types: Vec<_> = params
+        .iter()
+        .map(|param| get_basic_type(generator, &param.ty).into())
+        .collect();
+    let entry_point =
+        qir::create_entry_point(generator.context, &generator.module, name, &param_types)
+            .ok_or_else(|| GenerationError::FunctionExists(name.to_string()))?;
 
     if generator.use_static_qubit_alloc {
-        let num_qubits = format!("{}", model.qubits.len());
+        let num_qubits = format!("{}", qubits.len());
         let required_qubits = generator
             .context
             .create_string_attribute("requiredQubits", &num_qubits);
@@ -72,7 +439,7 @@ fn build_entry_function(generator: &CodeGenerator, model: &SemanticModel) -> Res
     }
 
     if generator.use_static_result_alloc {
-        let num_results: u64 = model.registers.iter().map(|f| f.size).sum();
+        let num_results: u64 = registers.iter().map(|f| f.size).sum();
         let num_result_string = format!("{}", num_results);
         let required_results = generator
             .context
@@ -82,29 +449,148 @@ fn build_entry_function(generator: &CodeGenerator, model: &SemanticModel) -> Res
 
     let entry = generator.context.append_basic_block(entry_point, "entry");
     generator.builder.position_at_end(entry);
+    generator.attach_debug_info(entry_point);
+
+    let mut variables = HashMap::new();
+    for (param, value) in params.iter().zip(entry_point.get_param_iter()) {
+        value.set_name(&param.name);
+        variables.insert(param.name.clone(), value);
+    }
+
+    let (qubits, qubit_arrays) = write_qubits(qubits, generator);
+    let mut results = write_registers(registers, generator);
+    write_instructions(
+        instructions,
+        generator,
+        &qubits,
+        &mut results,
+        &mut variables,
+        entry_point,
+    )?;
+
+    match output {
+        Output::Discard => {}
+        Output::Record { grouped } => {
+            qir::output::record_output(generator, registers, &results, grouped);
+        }
+    }
 
-    let qubits = write_qubits(model, generator);
-    let mut registers = write_registers(model, generator);
-    write_instructions(model, generator, &qubits, &mut registers, entry_point);
+    if !generator.use_static_qubit_alloc {
+        if generator.use_array_qubit_alloc {
+            for array in qubit_arrays {
+                generator.emit_release_qubit_array(array);
+            }
+        } else {
+            free_qubits(generator, &qubits);
+        }
+    }
+
+    generator.builder.build_return(None);
+    Ok(())
+}
+
+/// Builds an `InteropFriendly` wrapper for the `main` entry function and each additional one.
+///
+/// # Errors
+///
+/// Returns `InteropDynamicResults` if results are dynamically allocated, because the wrapper reads
+/// the results after the entry function returns.
+fn build_interop_wrappers(
+    generator: &CodeGenerator,
+    model: &SemanticModel,
+) -> Result<(), GenerationError> {
+    if !generator.use_static_result_alloc {
+        return Err(GenerationError::InteropDynamicResults);
+    }
 
-    if !model.use_static_qubit_alloc {
-        free_qubits(generator, &qubits);
+    build_interop_wrapper(
+        generator,
+        &model.entry_point_name,
+        &model.params,
+        &model.registers,
+    )?;
+
+    for entry_point in &model.entry_points {
+        build_interop_wrapper(
+            generator,
+            &entry_point.name,
+            &entry_point.params,
+            &entry_point.registers,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Builds a wrapper named after the entry function with an `__Interop` suffix. The wrapper takes
+/// the parameters of the entry function followed by a buffer, calls the entry function, and then
+/// writes the values of its results into the buffer.
+fn build_interop_wrapper(
+    generator: &CodeGenerator,
+    entry_point: &str,
+    params: &[Parameter],
+    registers: &[ClassicalRegister],
+) -> Result<(), GenerationError> {
+    let entry_function = generator
+        .module
+        .get_function(entry_point)
+        .ok_or_else(|| GenerationError::UnknownFunction(entry_point.to_string()))?;
+
+    let buffer_type = generator.context.i8_type().ptr_type(AddressSpace::Generic);
+    let param_types: Vec<_> = params
+        .iter()
+        .map(|param| get_basic_type(generator, &param.ty).into())
+        .chain(iter::once(buffer_type.into()))
+        .collect();
+    let name = qir::interop_function_name(entry_point);
+    let wrapper =
+        qir::create_interop_function(generator.context, &generator.module, &name, &param_types)
+            .ok_or(GenerationError::FunctionExists(name))?;
+
+    let entry = generator.context.append_basic_block(wrapper, "entry");
+    generator.builder.position_at_end(entry);
+    generator.attach_debug_info(wrapper);
+
+    let mut args = vec![];
+    for (param, value) in params.iter().zip(wrapper.get_param_iter()) {
+        value.set_name(&param.name);
+        args.push(value.into());
     }
+    generator.builder.build_call(entry_function, &args, "");
+
+    let buffer = wrapper
+        .get_last_param()
+        .expect("Wrapper has a buffer parameter.");
+    buffer.set_name("results");
+    let results = write_registers(registers, generator);
+    qir::output::write_output(generator, registers, &results, buffer.into_pointer_value());
 
     generator.builder.build_return(None);
-    generator.module.verify().map_err(|e| e.to_string())
+    Ok(())
 }
 
+/// Declares the external functions in order of name, so that the module does not depend on the
+/// iteration order of the map they came from.
 fn add_external_functions<'a>(
     generator: &CodeGenerator,
     functions: impl Iterator<Item = (&'a String, &'a interop::FunctionType)>,
-) {
+) -> Result<(), GenerationError> {
+    let mut functions: Vec<_> = functions.collect();
+    functions.sort_unstable_by_key(|&(name, _)| name);
     for (name, ty) in functions {
         let ty = get_function_type(generator, ty);
-        generator
-            .module
-            .add_function(name, ty, Some(Linkage::External));
+        match generator.module.get_function(name) {
+            None => {
+                generator
+                    .module
+                    .add_function(name, ty, Some(Linkage::External));
+            }
+            Some(function) if function.get_type() == ty => {}
+            Some(_) => return Err(GenerationError::FunctionExists(name.clone())),
+        }
     }
+
+    Ok(())
 }
 
 fn get_function_type<'ctx>(
@@ -136,25 +622,38 @@ fn get_basic_type<'ctx>(generator: &CodeGenerator<'ctx>, ty: &ValueType) -> Basi
         ValueType::Result => {
             BasicTypeEnum::PointerType(generator.result_type().ptr_type(AddressSpace::Generic))
         }
+        ValueType::String => {
+            BasicTypeEnum::PointerType(generator.context.i8_type().ptr_type(AddressSpace::Generic))
+        }
     }
 }
 
-fn free_qubits<'ctx>(
-    generator: &CodeGenerator<'ctx>,
-    qubits: &HashMap<String, BasicValueEnum<'ctx>>,
-) {
-    for (_, value) in qubits.iter() {
+fn free_qubits<'ctx>(generator: &CodeGenerator<'ctx>, qubits: &Scope<BasicValueEnum<'ctx>>) {
+    for value in qubits.values() {
         generator.emit_release_qubit(value);
     }
 }
 
+/// Emits the qubits of an entry point, returning the value of each qubit and the arrays that the
+/// qubits were allocated in, if any.
 fn write_qubits<'ctx>(
-    model: &SemanticModel,
+    qubits: &[QuantumRegister],
     generator: &CodeGenerator<'ctx>,
-) -> HashMap<String, BasicValueEnum<'ctx>> {
+) -> (Scope<BasicValueEnum<'ctx>>, Vec<PointerValue<'ctx>>) {
+    if !generator.use_static_qubit_alloc && generator.use_array_qubit_alloc {
+        write_qubit_arrays(qubits, generator)
+    } else {
+        (write_single_qubits(qubits, generator), vec![])
+    }
+}
+
+fn write_single_qubits<'ctx>(
+    qubits: &[QuantumRegister],
+    generator: &CodeGenerator<'ctx>,
+) -> Scope<BasicValueEnum<'ctx>> {
+    let mut values = Scope::new();
     if generator.use_static_qubit_alloc {
-        let mut qubits: HashMap<String, BasicValueEnum<'ctx>> = HashMap::new();
-        for (id, qubit) in model.qubits.iter().enumerate() {
+        for (id, qubit) in qubits.iter().enumerate() {
             let indexed_name = format!("{}{}", &qubit.name[..], qubit.index);
             let int_value = generator.usize_to_i64(id).into_int_value();
             let qubit_ptr_type = generator.qubit_type().ptr_type(AddressSpace::Generic);
@@ -163,57 +662,74 @@ fn write_qubits<'ctx>(
                 generator
                     .builder
                     .build_int_to_ptr(int_value, qubit_ptr_type, &indexed_name);
-            qubits.insert(indexed_name, intptr.into());
+            values.insert_element(&qubit.name, qubit.index, intptr.into());
         }
-        qubits
     } else {
-        let qubits = model
-            .qubits
-            .iter()
-            .map(|reg| {
-                let indexed_name = format!("{}{}", &reg.name[..], reg.index);
-                let value = generator.emit_allocate_qubit(indexed_name.as_str());
-                (indexed_name, value)
-            })
-            .collect();
+        for qubit in qubits {
+            let indexed_name = format!("{}{}", &qubit.name[..], qubit.index);
+            let value = generator.emit_allocate_qubit(indexed_name.as_str());
+            values.insert_element(&qubit.name, qubit.index, value);
+        }
+    }
+    values
+}
+
+/// Allocates one array for each register of qubits, in the order that the registers first appear,
+/// and loads each qubit from its register's array.
+fn write_qubit_arrays<'ctx>(
+    qubits: &[QuantumRegister],
+    generator: &CodeGenerator<'ctx>,
+) -> (Scope<BasicValueEnum<'ctx>>, Vec<PointerValue<'ctx>>) {
+    let mut registers: Vec<(&str, u64)> = vec![];
+    let mut positions = HashMap::new();
+    for qubit in qubits {
+        let position = *positions.entry(qubit.name.as_str()).or_insert_with(|| {
+            registers.push((&qubit.name, 0));
+            registers.len() - 1
+        });
+        let size = &mut registers[position].1;
+        *size = (*size).max(qubit.index + 1);
+    }
+
+    let arrays: Vec<_> = registers
+        .iter()
+        .map(|&(name, size)| generator.emit_allocate_qubit_array(size, name))
+        .collect();
 
-        qubits
+    let mut values = Scope::new();
+    for qubit in qubits {
+        let indexed_name = format!("{}{}", qubit.name, qubit.index);
+        let array = arrays[positions[qubit.name.as_str()]];
+        let value = generator.emit_array_qubit(array, qubit.index, &indexed_name);
+        values.insert_element(&qubit.name, qubit.index, value);
     }
+
+    (values, arrays)
 }
 
 fn write_registers<'ctx>(
-    model: &SemanticModel,
+    registers: &[ClassicalRegister],
     generator: &CodeGenerator<'ctx>,
-) -> HashMap<String, Option<PointerValue<'ctx>>> {
+) -> Scope<Option<PointerValue<'ctx>>> {
+    let mut values = Scope::new();
     if generator.use_static_result_alloc {
-        let mut registers: HashMap<String, Option<PointerValue<'ctx>>> = HashMap::new();
         let mut id = 0;
-        let number_of_registers = model.registers.len() as u64;
-        if number_of_registers > 0 {
-            for register in &model.registers {
-                for index in 0..register.size {
-                    let indexed_name = format!("{}{}", register.name, index);
-                    let intptr = create_result_static_ptr(&indexed_name, generator, id);
-                    registers.insert(indexed_name, intptr.into());
-                    id += 1;
-                }
+        for register in registers {
+            for index in 0..register.size {
+                let indexed_name = format!("{}{}", register.name, index);
+                let intptr = create_result_static_ptr(&indexed_name, generator, id);
+                values.insert_element(&register.name, index, Some(intptr));
+                id += 1;
             }
         }
-
-        registers
     } else {
-        let mut registers: HashMap<String, Option<PointerValue<'ctx>>> = HashMap::new();
-        let number_of_registers = model.registers.len() as u64;
-        if number_of_registers > 0 {
-            for register in &model.registers {
-                for index in 0..register.size {
-                    let name = format!("{}{}", register.name, index);
-                    registers.insert(name, None);
-                }
+        for register in registers {
+            for index in 0..register.size {
+                values.insert_element(&register.name, index, None);
             }
         }
-        registers
     }
+    values
 }
 
 fn create_result_static_ptr<'ctx>(
@@ -228,423 +744,36 @@ fn create_result_static_ptr<'ctx>(
         .build_int_to_ptr(int_value, result_ptr_type, indexed_name)
 }
 
+/// Emits the instructions of a function body. If the generator has a maximum block size, each run
+/// of that many top-level instructions goes into a new basic block that the previous one branches
+/// to.
 fn write_instructions<'ctx>(
-    model: &SemanticModel,
+    instructions: &[Instruction],
     generator: &CodeGenerator<'ctx>,
-    qubits: &HashMap<String, BasicValueEnum<'ctx>>,
-    registers: &mut HashMap<String, Option<PointerValue<'ctx>>>,
-    entry_point: FunctionValue,
-) {
-    for inst in &model.instructions {
-        qir::instructions::emit(generator, inst, qubits, registers, entry_point);
-    }
-}
-
-#[cfg(test)]
-mod result_alloc_tests {
-    use crate::generation::{
-        emit,
-        interop::{
-            ClassicalRegister, Instruction, Measured, QuantumRegister, SemanticModel, Single,
-        },
-    };
-    use std::collections::HashMap;
-
-    fn get_model(
-        name: String,
-        use_static_qubit_alloc: bool,
-        use_static_result_alloc: bool,
-    ) -> SemanticModel {
-        SemanticModel {
-            name,
-            registers: vec![ClassicalRegister::new("r".to_string(), 1)],
-            qubits: vec![QuantumRegister::new("q".to_string(), 0)],
-            instructions: vec![Instruction::M(Measured::new(
-                "q0".to_string(),
-                "r0".to_string(),
-            ))],
-            use_static_qubit_alloc,
-            use_static_result_alloc,
-            external_functions: HashMap::new(),
+    qubits: &Scope<BasicValueEnum<'ctx>>,
+    registers: &mut Scope<Option<PointerValue<'ctx>>>,
+    variables: &mut HashMap<String, BasicValueEnum<'ctx>>,
+    entry_point: FunctionValue<'ctx>,
+) -> Result<(), GenerationError> {
+    // Resolve every operand up front, so that emitting an instruction only indexes into the scopes.
+    let instructions = qir::resolved::resolve(instructions, qubits, registers);
+
+    let block_size = generator.max_block_size.unwrap_or(0);
+    for (index, inst) in instructions.iter().enumerate() {
+        if block_size > 0 && index > 0 && index % block_size == 0 {
+            // Values are only defined in earlier blocks of the chain, so they dominate every use.
+            let block = generator
+                .context
+                .append_basic_block(entry_point, &format!("block{}", index / block_size));
+            generator.builder.build_unconditional_branch(block);
+            generator.builder.position_at_end(block);
         }
-    }
-
-    #[test]
-    fn when_dynamic_qubit_and_dynamic_result_alloc_is_used_then_only_entypoint_attribute_is_emitted(
-    ) -> Result<(), String> {
-        let model = get_model("test".to_owned(), false, false);
-        let actual_ir: String = emit::ir(&model)?;
-        assert!(actual_ir.contains("attributes #0 = { \"EntryPoint\" }"));
-        Ok(())
-    }
-
-    #[test]
-    fn when_static_qubit_alloc_is_used_then_required_attribute_is_emitted() -> Result<(), String> {
-        let model = get_model("test".to_owned(), true, false);
-        let actual_ir: String = emit::ir(&model)?;
-        assert!(actual_ir.contains("attributes #0 = { \"EntryPoint\" \"requiredQubits\"=\"1\" }"));
-        Ok(())
-    }
-
-    #[test]
-    fn when_static_result_alloc_is_used_then_required_attribute_is_emitted() -> Result<(), String> {
-        let model = get_model("test".to_owned(), false, true);
-        let actual_ir: String = emit::ir(&model)?;
-        assert!(actual_ir.contains("attributes #0 = { \"EntryPoint\" \"requiredResults\"=\"1\" }"));
-        Ok(())
-    }
-
-    #[test]
-    fn when_static_qubit_and_static_result_alloc_is_used_then_both_required_attribute_are_emitted(
-    ) -> Result<(), String> {
-        let model = get_model("test".to_owned(), true, true);
-        let actual_ir: String = emit::ir(&model)?;
-        assert!(actual_ir.contains(
-            "attributes #0 = { \"EntryPoint\" \"requiredQubits\"=\"1\" \"requiredResults\"=\"1\" }"
-        ));
-        Ok(())
-    }
-
-    #[test]
-    fn when_static_result_alloc_is_used_then_emitted_attribute_sums_registers_correctly(
-    ) -> Result<(), String> {
-        let model = SemanticModel {
-            name: "test".to_owned(),
-            registers: vec![
-                ClassicalRegister::new("r".to_string(), 1),
-                ClassicalRegister::new("r".to_string(), 3),
-                ClassicalRegister::new("r".to_string(), 4),
-            ],
-            qubits: vec![QuantumRegister::new("q".to_string(), 0)],
-            instructions: vec![Instruction::M(Measured::new(
-                "q0".to_string(),
-                "r0".to_string(),
-            ))],
-            use_static_qubit_alloc: false,
-            use_static_result_alloc: true,
-            external_functions: HashMap::new(),
-        };
-        let actual_ir: String = emit::ir(&model)?;
-        assert!(actual_ir.contains("attributes #0 = { \"EntryPoint\" \"requiredResults\"=\"8\" }"));
-        Ok(())
-    }
 
-    #[test]
-    fn when_static_result_alloc_is_used_and_no_registers_declared_then_emitted_attribute_sums_correctly(
-    ) -> Result<(), String> {
-        let model = SemanticModel {
-            name: "test".to_owned(),
-            registers: vec![],
-            qubits: vec![QuantumRegister::new("q".to_string(), 0)],
-            instructions: vec![Instruction::H(Single::new("q0".to_string()))],
-            use_static_qubit_alloc: false,
-            use_static_result_alloc: true,
-            external_functions: HashMap::new(),
-        };
-        let actual_ir: String = emit::ir(&model)?;
-        assert!(actual_ir.contains("attributes #0 = { \"EntryPoint\" \"requiredResults\"=\"0\" }"));
-        Ok(())
+        qir::instructions::emit(generator, inst, qubits, registers, variables, entry_point)?;
     }
 
-    #[test]
-    fn when_dynamic_result_alloc_is_used_then_m_body_is_emitted() -> Result<(), String> {
-        let model = get_model("test".to_owned(), false, false);
-        let actual_ir: String = emit::ir(&model)?;
-        assert!(actual_ir.contains("declare %Result* @__quantum__qis__m__body(%Qubit*)"));
-        Ok(())
-    }
-
-    #[test]
-    fn when_static_result_alloc_is_used_then_mz_body_is_emitted() -> Result<(), String> {
-        let model = get_model("test".to_owned(), false, true);
-        let actual_ir: String = emit::ir(&model)?;
-        assert!(actual_ir.contains("declare void @__quantum__qis__mz__body(%Qubit*, %Result*)"));
-        Ok(())
-    }
+    Ok(())
 }
 
-/// These tests compare generated IR against reference files in the "resources/tests" folder. If
-/// changes to code generation break the tests:
-///
-/// 1. Run the tests with the `PYQIR_TEST_SAVE_REFERENCES` environment variable set to regenerate
-///    the reference files.
-/// 2. Review the changes and make sure they look reasonable.
-/// 3. Unset the environment variable and run the tests again to confirm that they pass.
 #[cfg(test)]
-mod if_tests {
-    use crate::generation::{
-        emit,
-        interop::{
-            ClassicalRegister, If, Instruction, Measured, QuantumRegister, SemanticModel, Single,
-        },
-    };
-    use normalize_line_endings::normalized;
-    use std::{collections::HashMap, env, fs, path::PathBuf};
-
-    const PYQIR_TEST_SAVE_REFERENCES: &str = "PYQIR_TEST_SAVE_REFERENCES";
-
-    #[test]
-    fn test_if_then() -> Result<(), String> {
-        let model = SemanticModel {
-            name: "test_if_then".to_string(),
-            registers: vec![ClassicalRegister::new("r".to_string(), 1)],
-            qubits: vec![QuantumRegister::new("q".to_string(), 0)],
-            instructions: vec![
-                Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
-                Instruction::If(If {
-                    condition: "r0".to_string(),
-                    then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
-                    else_insts: vec![],
-                }),
-            ],
-            use_static_qubit_alloc: true,
-            use_static_result_alloc: false,
-            external_functions: HashMap::new(),
-        };
-
-        check_or_save_reference_ir(&model)
-    }
-
-    #[test]
-    fn test_if_else() -> Result<(), String> {
-        let model = SemanticModel {
-            name: "test_if_else".to_string(),
-            registers: vec![ClassicalRegister::new("r".to_string(), 1)],
-            qubits: vec![QuantumRegister::new("q".to_string(), 0)],
-            instructions: vec![
-                Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
-                Instruction::If(If {
-                    condition: "r0".to_string(),
-                    then_insts: vec![],
-                    else_insts: vec![Instruction::X(Single::new("q0".to_string()))],
-                }),
-            ],
-            use_static_qubit_alloc: true,
-            use_static_result_alloc: false,
-            external_functions: HashMap::new(),
-        };
-
-        check_or_save_reference_ir(&model)
-    }
-
-    #[test]
-    fn test_if_then_continue() -> Result<(), String> {
-        let model = SemanticModel {
-            name: "test_if_then_continue".to_string(),
-            registers: vec![ClassicalRegister::new("r".to_string(), 1)],
-            qubits: vec![QuantumRegister::new("q".to_string(), 0)],
-            instructions: vec![
-                Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
-                Instruction::If(If {
-                    condition: "r0".to_string(),
-                    then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
-                    else_insts: vec![],
-                }),
-                Instruction::H(Single::new("q0".to_string())),
-            ],
-            use_static_qubit_alloc: true,
-            use_static_result_alloc: false,
-            external_functions: HashMap::new(),
-        };
-
-        check_or_save_reference_ir(&model)
-    }
-
-    #[test]
-    fn test_if_else_continue() -> Result<(), String> {
-        let model = SemanticModel {
-            name: "test_if_else_continue".to_string(),
-            registers: vec![ClassicalRegister::new("r".to_string(), 1)],
-            qubits: vec![QuantumRegister::new("q".to_string(), 0)],
-            instructions: vec![
-                Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
-                Instruction::If(If {
-                    condition: "r0".to_string(),
-                    then_insts: vec![],
-                    else_insts: vec![Instruction::X(Single::new("q0".to_string()))],
-                }),
-                Instruction::H(Single::new("q0".to_string())),
-            ],
-            use_static_qubit_alloc: true,
-            use_static_result_alloc: false,
-            external_functions: HashMap::new(),
-        };
-
-        check_or_save_reference_ir(&model)
-    }
-
-    #[test]
-    fn test_if_then_else_continue() -> Result<(), String> {
-        let model = SemanticModel {
-            name: "test_if_then_else_continue".to_string(),
-            registers: vec![ClassicalRegister::new("r".to_string(), 1)],
-            qubits: vec![QuantumRegister::new("q".to_string(), 0)],
-            instructions: vec![
-                Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
-                Instruction::If(If {
-                    condition: "r0".to_string(),
-                    then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
-                    else_insts: vec![Instruction::Y(Single::new("q0".to_string()))],
-                }),
-                Instruction::H(Single::new("q0".to_string())),
-            ],
-            use_static_qubit_alloc: true,
-            use_static_result_alloc: false,
-            external_functions: HashMap::new(),
-        };
-
-        check_or_save_reference_ir(&model)
-    }
-
-    #[test]
-    fn test_if_then_then() -> Result<(), String> {
-        let model = SemanticModel {
-            name: "test_if_then_then".to_string(),
-            registers: vec![ClassicalRegister::new("r".to_string(), 2)],
-            qubits: vec![QuantumRegister::new("q".to_string(), 0)],
-            instructions: vec![
-                Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
-                Instruction::M(Measured::new("q0".to_string(), "r1".to_string())),
-                Instruction::If(If {
-                    condition: "r0".to_string(),
-                    then_insts: vec![Instruction::If(If {
-                        condition: "r1".to_string(),
-                        then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
-                        else_insts: vec![],
-                    })],
-                    else_insts: vec![],
-                }),
-            ],
-            use_static_qubit_alloc: true,
-            use_static_result_alloc: false,
-            external_functions: HashMap::new(),
-        };
-
-        check_or_save_reference_ir(&model)
-    }
-
-    #[test]
-    fn test_if_else_else() -> Result<(), String> {
-        let model = SemanticModel {
-            name: "test_if_else_else".to_string(),
-            registers: vec![ClassicalRegister::new("r".to_string(), 2)],
-            qubits: vec![QuantumRegister::new("q".to_string(), 0)],
-            instructions: vec![
-                Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
-                Instruction::M(Measured::new("q0".to_string(), "r1".to_string())),
-                Instruction::If(If {
-                    condition: "r0".to_string(),
-                    then_insts: vec![],
-                    else_insts: vec![Instruction::If(If {
-                        condition: "r1".to_string(),
-                        then_insts: vec![],
-                        else_insts: vec![Instruction::X(Single::new("q0".to_string()))],
-                    })],
-                }),
-            ],
-            use_static_qubit_alloc: true,
-            use_static_result_alloc: false,
-            external_functions: HashMap::new(),
-        };
-
-        check_or_save_reference_ir(&model)
-    }
-
-    #[test]
-    fn test_if_then_else() -> Result<(), String> {
-        let model = SemanticModel {
-            name: "test_if_then_else".to_string(),
-            registers: vec![ClassicalRegister::new("r".to_string(), 2)],
-            qubits: vec![QuantumRegister::new("q".to_string(), 0)],
-            instructions: vec![
-                Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
-                Instruction::M(Measured::new("q0".to_string(), "r1".to_string())),
-                Instruction::If(If {
-                    condition: "r0".to_string(),
-                    then_insts: vec![Instruction::If(If {
-                        condition: "r1".to_string(),
-                        then_insts: vec![],
-                        else_insts: vec![Instruction::X(Single::new("q0".to_string()))],
-                    })],
-                    else_insts: vec![],
-                }),
-            ],
-            use_static_qubit_alloc: true,
-            use_static_result_alloc: false,
-            external_functions: HashMap::new(),
-        };
-
-        check_or_save_reference_ir(&model)
-    }
-
-    #[test]
-    fn test_if_else_then() -> Result<(), String> {
-        let model = SemanticModel {
-            name: "test_if_else_then".to_string(),
-            registers: vec![ClassicalRegister::new("r".to_string(), 2)],
-            qubits: vec![QuantumRegister::new("q".to_string(), 0)],
-            instructions: vec![
-                Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
-                Instruction::M(Measured::new("q0".to_string(), "r1".to_string())),
-                Instruction::If(If {
-                    condition: "r0".to_string(),
-                    then_insts: vec![],
-                    else_insts: vec![Instruction::If(If {
-                        condition: "r1".to_string(),
-                        then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
-                        else_insts: vec![],
-                    })],
-                }),
-            ],
-            use_static_qubit_alloc: true,
-            use_static_result_alloc: false,
-            external_functions: HashMap::new(),
-        };
-
-        check_or_save_reference_ir(&model)
-    }
-
-    #[test]
-    fn test_results_default_to_zero_if_not_measured() -> Result<(), String> {
-        let model = SemanticModel {
-            name: "test_results_default_to_zero_if_not_measured".to_string(),
-            registers: vec![ClassicalRegister::new("r".to_string(), 1)],
-            qubits: vec![QuantumRegister::new("q".to_string(), 0)],
-            instructions: vec![Instruction::If(If {
-                condition: "r0".to_string(),
-                then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
-                else_insts: vec![Instruction::H(Single::new("q0".to_string()))],
-            })],
-            use_static_qubit_alloc: true,
-            use_static_result_alloc: false,
-            external_functions: HashMap::new(),
-        };
-
-        check_or_save_reference_ir(&model)
-    }
-
-    fn check_or_save_reference_ir(model: &SemanticModel) -> Result<(), String> {
-        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        path.push("resources");
-        path.push("tests");
-        path.push(&model.name);
-        path.set_extension("ll");
-
-        let actual_ir: String = normalized(emit::ir(model)?.chars()).collect();
-
-        if env::var(PYQIR_TEST_SAVE_REFERENCES).is_ok() {
-            fs::create_dir_all(path.parent().unwrap()).map_err(|e| e.to_string())?;
-            fs::write(&path, actual_ir).map_err(|e| e.to_string())?;
-
-            Err(format!(
-                "Saved reference IR. Run again without the {} environment variable.",
-                PYQIR_TEST_SAVE_REFERENCES
-            ))
-        } else {
-            let contents = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-            let expected_ir: String = normalized(contents.chars()).collect();
-            assert_eq!(expected_ir, actual_ir);
-            Ok(())
-        }
-    }
-}
+mod tests;
@@ -0,0 +1,192 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{functions, ir, model};
+use crate::generation::{
+    emit,
+    error::GenerationError,
+    interop::{
+        BinaryKind, BinaryOp, Call, Comparison, ComparisonKind, Condition, For, If, Instruction,
+        IntegerValue, ReturnType, SemanticModel, Single, Value, ValueType,
+    },
+};
+
+fn get_model(instructions: Vec<Instruction>) -> SemanticModel {
+    SemanticModel {
+        external_functions: functions(&[(
+            "record",
+            &[ValueType::Integer { width: 64 }],
+            ReturnType::Void,
+        )]),
+        ..model(1, 0, instructions)
+    }
+}
+
+fn int(value: u64) -> Value {
+    Value::Integer(IntegerValue::new(64, value).unwrap())
+}
+
+fn variable(name: &str) -> Value {
+    Value::Variable(name.to_string())
+}
+
+#[test]
+fn integer_arithmetic() -> Result<(), String> {
+    let model = get_model(vec![
+        Instruction::BinaryOp(BinaryOp {
+            kind: BinaryKind::Add,
+            lhs: int(2),
+            rhs: int(3),
+            result: "x".to_string(),
+        }),
+        Instruction::BinaryOp(BinaryOp {
+            kind: BinaryKind::Xor,
+            lhs: variable("x"),
+            rhs: variable("x"),
+            result: "y".to_string(),
+        }),
+        Instruction::Call(Call {
+            name: "record".to_string(),
+            args: vec![variable("y")],
+            result: None,
+        }),
+    ]);
+
+    let ir = ir(&model)?;
+    assert!(ir.contains("call void @record(i64 0)"));
+    Ok(())
+}
+
+#[test]
+fn comparison_as_condition() -> Result<(), String> {
+    let model = get_model(vec![
+        Instruction::Comparison(Comparison {
+            kind: ComparisonKind::Lt,
+            lhs: Value::Double(1.0),
+            rhs: Value::Double(2.0),
+            result: "c".to_string(),
+        }),
+        Instruction::If(If {
+            condition: Condition::Variable("c".to_string()),
+            then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+            else_insts: vec![],
+        }),
+    ]);
+
+    let ir = ir(&model)?;
+    assert!(ir.contains("call void @__quantum__qis__x__body(%Qubit* null)"));
+    Ok(())
+}
+
+#[test]
+fn ordered_comparisons_are_signed_or_unsigned() -> Result<(), String> {
+    let comparison = |kind, result: &str| {
+        Instruction::Comparison(Comparison {
+            kind,
+            lhs: variable("i"),
+            rhs: int(5),
+            result: result.to_string(),
+        })
+    };
+    let model = get_model(vec![Instruction::For(For {
+        index: "i".to_string(),
+        count: 10,
+        body: vec![
+            comparison(ComparisonKind::Lt, "a"),
+            comparison(ComparisonKind::ULt, "b"),
+            comparison(ComparisonKind::Ge, "c"),
+            comparison(ComparisonKind::UGe, "d"),
+        ],
+    })]);
+
+    let ir = ir(&model)?;
+    assert!(ir.contains("%a = icmp slt i64 %i, 5"));
+    assert!(ir.contains("%b = icmp ult i64 %i, 5"));
+    assert!(ir.contains("%c = icmp sge i64 %i, 5"));
+    assert!(ir.contains("%d = icmp uge i64 %i, 5"));
+    Ok(())
+}
+
+#[test]
+fn unsigned_comparison_of_doubles_is_reported() {
+    let model = get_model(vec![Instruction::Comparison(Comparison {
+        kind: ComparisonKind::ULt,
+        lhs: Value::Double(1.0),
+        rhs: Value::Double(2.0),
+        result: "c".to_string(),
+    })]);
+
+    assert_eq!(
+        emit::ir(&model),
+        Err(GenerationError::InvalidOperands("c".to_string()))
+    );
+}
+
+#[test]
+fn variables_are_scoped_to_blocks() {
+    let model = get_model(vec![
+        Instruction::Comparison(Comparison {
+            kind: ComparisonKind::Eq,
+            lhs: int(1),
+            rhs: int(1),
+            result: "c".to_string(),
+        }),
+        Instruction::If(If {
+            condition: Condition::Variable("c".to_string()),
+            then_insts: vec![Instruction::BinaryOp(BinaryOp {
+                kind: BinaryKind::Mul,
+                lhs: int(2),
+                rhs: int(3),
+                result: "x".to_string(),
+            })],
+            else_insts: vec![],
+        }),
+        Instruction::Call(Call {
+            name: "record".to_string(),
+            args: vec![variable("x")],
+            result: None,
+        }),
+    ]);
+
+    assert_eq!(
+        emit::ir(&model),
+        Err(GenerationError::UnknownVariable("x".to_string()))
+    );
+}
+
+#[test]
+fn mismatched_operands_are_reported() {
+    let model = get_model(vec![Instruction::BinaryOp(BinaryOp {
+        kind: BinaryKind::Sub,
+        lhs: int(1),
+        rhs: Value::Double(1.0),
+        result: "x".to_string(),
+    })]);
+
+    assert_eq!(
+        emit::ir(&model),
+        Err(GenerationError::InvalidOperands("x".to_string()))
+    );
+}
+
+#[test]
+fn non_boolean_condition_is_reported() {
+    let model = get_model(vec![
+        Instruction::BinaryOp(BinaryOp {
+            kind: BinaryKind::Add,
+            lhs: int(1),
+            rhs: int(1),
+            result: "x".to_string(),
+        }),
+        Instruction::If(If {
+            condition: Condition::Variable("x".to_string()),
+            then_insts: vec![],
+            else_insts: vec![],
+        }),
+    ]);
+
+    assert_eq!(
+        emit::ir(&model),
+        Err(GenerationError::InvalidCondition("x".to_string()))
+    );
+}
@@ -0,0 +1,46 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ir, model};
+use crate::generation::interop::{Controlled, Instruction, Measured, SemanticModel, Single};
+
+const NUM_QUBITS: u64 = 64;
+
+fn get_model(num_gates: usize, max_block_size: Option<usize>) -> SemanticModel {
+    let qubit = |index: usize| format!("q{}", index as u64 % NUM_QUBITS);
+    let mut instructions: Vec<_> = (0..num_gates)
+        .map(|index| {
+            if index % 2 == 0 {
+                Instruction::H(Single::new(qubit(index)))
+            } else {
+                Instruction::Cx(Controlled::new(qubit(index), qubit(index + 1)))
+            }
+        })
+        .collect();
+    instructions.push(Instruction::M(Measured::new("q0", "r0")));
+
+    SemanticModel {
+        use_static_qubit_alloc: false,
+        use_static_result_alloc: false,
+        max_block_size,
+        ..model(NUM_QUBITS, 1, instructions)
+    }
+}
+
+#[test]
+fn body_is_split_into_blocks() -> Result<(), String> {
+    let ir = ir(&get_model(4, Some(2)))?;
+    assert!(ir.contains("br label %block1\n\nblock1:"));
+    assert!(ir.contains("br label %block2\n\nblock2:"));
+    assert!(!ir.contains("block3"));
+    Ok(())
+}
+
+#[test]
+fn body_is_not_split_by_default() -> Result<(), String> {
+    for max_block_size in [None, Some(0), Some(5)] {
+        let ir = ir(&get_model(4, max_block_size))?;
+        assert!(!ir.contains("block"));
+    }
+    Ok(())
+}
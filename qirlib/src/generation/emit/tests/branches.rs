@@ -0,0 +1,251 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! These tests compare generated IR against reference files in the "resources/tests" folder. If
+//! changes to code generation break the tests:
+//!
+//! 1. Run the tests with the `PYQIR_TEST_SAVE_REFERENCES` environment variable set to regenerate
+//!    the reference files.
+//! 2. Review the changes and make sure they look reasonable.
+//! 3. Unset the environment variable and run the tests again to confirm that they pass.
+
+use super::{ir, model};
+use crate::generation::interop::{Condition, If, Instruction, Measured, SemanticModel, Single};
+use normalize_line_endings::normalized;
+use std::{env, fs, path::PathBuf};
+
+const PYQIR_TEST_SAVE_REFERENCES: &str = "PYQIR_TEST_SAVE_REFERENCES";
+
+fn get_model(name: &str, num_results: u64, instructions: Vec<Instruction>) -> SemanticModel {
+    SemanticModel {
+        name: name.to_string(),
+        use_static_result_alloc: false,
+        ..model(1, num_results, instructions)
+    }
+}
+
+#[test]
+fn test_if_then() -> Result<(), String> {
+    let model = get_model(
+        "test_if_then",
+        1,
+        vec![
+            Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+            Instruction::If(If {
+                condition: Condition::Result("r0".into()),
+                then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+                else_insts: vec![],
+            }),
+        ],
+    );
+
+    check_or_save_reference_ir(&model)
+}
+
+#[test]
+fn test_if_else() -> Result<(), String> {
+    let model = get_model(
+        "test_if_else",
+        1,
+        vec![
+            Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+            Instruction::If(If {
+                condition: Condition::Result("r0".into()),
+                then_insts: vec![],
+                else_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+            }),
+        ],
+    );
+
+    check_or_save_reference_ir(&model)
+}
+
+#[test]
+fn test_if_then_continue() -> Result<(), String> {
+    let model = get_model(
+        "test_if_then_continue",
+        1,
+        vec![
+            Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+            Instruction::If(If {
+                condition: Condition::Result("r0".into()),
+                then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+                else_insts: vec![],
+            }),
+            Instruction::H(Single::new("q0".to_string())),
+        ],
+    );
+
+    check_or_save_reference_ir(&model)
+}
+
+#[test]
+fn test_if_else_continue() -> Result<(), String> {
+    let model = get_model(
+        "test_if_else_continue",
+        1,
+        vec![
+            Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+            Instruction::If(If {
+                condition: Condition::Result("r0".into()),
+                then_insts: vec![],
+                else_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+            }),
+            Instruction::H(Single::new("q0".to_string())),
+        ],
+    );
+
+    check_or_save_reference_ir(&model)
+}
+
+#[test]
+fn test_if_then_else_continue() -> Result<(), String> {
+    let model = get_model(
+        "test_if_then_else_continue",
+        1,
+        vec![
+            Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+            Instruction::If(If {
+                condition: Condition::Result("r0".into()),
+                then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+                else_insts: vec![Instruction::Y(Single::new("q0".to_string()))],
+            }),
+            Instruction::H(Single::new("q0".to_string())),
+        ],
+    );
+
+    check_or_save_reference_ir(&model)
+}
+
+#[test]
+fn test_if_then_then() -> Result<(), String> {
+    let model = get_model(
+        "test_if_then_then",
+        2,
+        vec![
+            Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+            Instruction::M(Measured::new("q0".to_string(), "r1".to_string())),
+            Instruction::If(If {
+                condition: Condition::Result("r0".into()),
+                then_insts: vec![Instruction::If(If {
+                    condition: Condition::Result("r1".into()),
+                    then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+                    else_insts: vec![],
+                })],
+                else_insts: vec![],
+            }),
+        ],
+    );
+
+    check_or_save_reference_ir(&model)
+}
+
+#[test]
+fn test_if_else_else() -> Result<(), String> {
+    let model = get_model(
+        "test_if_else_else",
+        2,
+        vec![
+            Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+            Instruction::M(Measured::new("q0".to_string(), "r1".to_string())),
+            Instruction::If(If {
+                condition: Condition::Result("r0".into()),
+                then_insts: vec![],
+                else_insts: vec![Instruction::If(If {
+                    condition: Condition::Result("r1".into()),
+                    then_insts: vec![],
+                    else_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+                })],
+            }),
+        ],
+    );
+
+    check_or_save_reference_ir(&model)
+}
+
+#[test]
+fn test_if_then_else() -> Result<(), String> {
+    let model = get_model(
+        "test_if_then_else",
+        2,
+        vec![
+            Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+            Instruction::M(Measured::new("q0".to_string(), "r1".to_string())),
+            Instruction::If(If {
+                condition: Condition::Result("r0".into()),
+                then_insts: vec![Instruction::If(If {
+                    condition: Condition::Result("r1".into()),
+                    then_insts: vec![],
+                    else_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+                })],
+                else_insts: vec![],
+            }),
+        ],
+    );
+
+    check_or_save_reference_ir(&model)
+}
+
+#[test]
+fn test_if_else_then() -> Result<(), String> {
+    let model = get_model(
+        "test_if_else_then",
+        2,
+        vec![
+            Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+            Instruction::M(Measured::new("q0".to_string(), "r1".to_string())),
+            Instruction::If(If {
+                condition: Condition::Result("r0".into()),
+                then_insts: vec![],
+                else_insts: vec![Instruction::If(If {
+                    condition: Condition::Result("r1".into()),
+                    then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+                    else_insts: vec![],
+                })],
+            }),
+        ],
+    );
+
+    check_or_save_reference_ir(&model)
+}
+
+#[test]
+fn test_results_default_to_zero_if_not_measured() -> Result<(), String> {
+    let model = get_model(
+        "test_results_default_to_zero_if_not_measured",
+        1,
+        vec![Instruction::If(If {
+            condition: Condition::Result("r0".into()),
+            then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+            else_insts: vec![Instruction::H(Single::new("q0".to_string()))],
+        })],
+    );
+
+    check_or_save_reference_ir(&model)
+}
+
+fn check_or_save_reference_ir(model: &SemanticModel) -> Result<(), String> {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("resources");
+    path.push("tests");
+    path.push(&model.name);
+    path.set_extension("ll");
+
+    let actual_ir = ir(model)?;
+    let actual_ir: String = normalized(actual_ir.chars()).collect();
+
+    if env::var(PYQIR_TEST_SAVE_REFERENCES).is_ok() {
+        fs::create_dir_all(path.parent().unwrap()).map_err(|e| e.to_string())?;
+        fs::write(&path, actual_ir).map_err(|e| e.to_string())?;
+
+        Err(format!(
+            "Saved reference IR. Run again without the {} environment variable.",
+            PYQIR_TEST_SAVE_REFERENCES
+        ))
+    } else {
+        let contents = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        let expected_ir: String = normalized(contents.chars()).collect();
+        assert_eq!(expected_ir, actual_ir);
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{functions, ir, model};
+use crate::generation::{
+    emit,
+    error::GenerationError,
+    interop::{
+        Call, Condition, If, Instruction, ReturnType, Rotated, SemanticModel, Single, Value,
+        ValueType,
+    },
+};
+
+fn get_model(instructions: Vec<Instruction>) -> SemanticModel {
+    SemanticModel {
+        use_static_result_alloc: false,
+        external_functions: functions(&[
+            ("get_angle", &[], ReturnType::Value(ValueType::Double)),
+            ("get_result", &[], ReturnType::Value(ValueType::Result)),
+            ("reset_all", &[], ReturnType::Void),
+            ("message", &[ValueType::String], ReturnType::Void),
+        ]),
+        ..model(1, 1, instructions)
+    }
+}
+
+fn call(name: &str, result: Option<&str>) -> Instruction {
+    Instruction::Call(Call {
+        name: name.to_string(),
+        args: vec![],
+        result: result.map(ToString::to_string),
+    })
+}
+
+#[test]
+fn return_value_feeds_rotation() -> Result<(), String> {
+    let model = get_model(vec![
+        call("get_angle", Some("theta")),
+        Instruction::Rx(Rotated::new(
+            Value::Variable("theta".to_string()),
+            "q0".to_string(),
+        )),
+    ]);
+
+    let ir = ir(&model)?;
+    assert!(ir.contains("%theta = call double @get_angle()"));
+    assert!(ir.contains("call void @__quantum__qis__rx__body(double %theta, %Qubit* null)"));
+    Ok(())
+}
+
+#[test]
+fn return_value_feeds_condition() -> Result<(), String> {
+    let model = get_model(vec![
+        call("get_result", Some("r")),
+        Instruction::If(If {
+            condition: Condition::Variable("r".to_string()),
+            then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+            else_insts: vec![],
+        }),
+    ]);
+
+    let ir = ir(&model)?;
+    assert!(ir.contains("%r = call %Result* @get_result()"));
+    assert!(ir.contains("call i1 @__quantum__rt__result_equal(%Result* %r,"));
+    Ok(())
+}
+
+#[test]
+fn return_value_can_be_ignored() -> Result<(), String> {
+    let model = get_model(vec![call("get_angle", None)]);
+    let ir = ir(&model)?;
+    assert!(ir.contains("call double @get_angle()"));
+    Ok(())
+}
+
+#[test]
+fn void_return_value_is_reported() {
+    let model = get_model(vec![call("reset_all", Some("x"))]);
+    assert_eq!(
+        emit::ir(&model),
+        Err(GenerationError::VoidReturn("reset_all".to_string()))
+    );
+}
+
+fn message(value: &str) -> Instruction {
+    Instruction::Call(Call {
+        name: "message".to_string(),
+        args: vec![Value::String(value.to_string())],
+        result: None,
+    })
+}
+
+#[test]
+fn strings_are_global_constants() -> Result<(), String> {
+    let model = get_model(vec![message("hello"), message("")]);
+    let ir = ir(&model)?;
+    assert!(ir.contains("@0 = private unnamed_addr constant [6 x i8] c\"hello\\00\", align 1"));
+    assert!(ir.contains("@1 = private unnamed_addr constant [1 x i8] zeroinitializer, align 1"));
+    assert!(ir.contains(
+        "call void @message(i8* getelementptr inbounds ([6 x i8], [6 x i8]* @0, i32 0, i32 0))"
+    ));
+    assert!(ir.contains("declare void @message(i8*)"));
+    Ok(())
+}
+
+#[test]
+fn equal_strings_share_a_global() -> Result<(), String> {
+    let model = get_model(vec![message("hello"), message("hello"), message("world")]);
+    let ir = ir(&model)?;
+    assert_eq!(ir.matches("private unnamed_addr constant").count(), 2);
+    assert_eq!(
+        ir.matches(
+            "call void @message(i8* getelementptr inbounds ([6 x i8], [6 x i8]* @0, i32 0, i32 0))"
+        )
+        .count(),
+        2
+    );
+    Ok(())
+}
+
+#[test]
+fn null_in_string_is_reported() {
+    let model = get_model(vec![message("a\0b")]);
+    assert_eq!(
+        emit::ir(&model),
+        Err(GenerationError::NulInString("a\0b".to_string()))
+    );
+}
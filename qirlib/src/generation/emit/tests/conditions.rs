@@ -0,0 +1,101 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ir, model};
+use crate::generation::{
+    emit,
+    error::GenerationError,
+    interop::{Condition, If, Instruction, Measured, SemanticModel, Single},
+};
+
+fn get_model(condition: Condition, use_static_result_alloc: bool) -> SemanticModel {
+    let instructions = vec![
+        Instruction::M(Measured::new("q0", "r0")),
+        Instruction::M(Measured::new("q0", "r1")),
+        Instruction::M(Measured::new("q0", "r2")),
+        Instruction::If(If {
+            condition,
+            then_insts: vec![Instruction::X(Single::new("q0"))],
+            else_insts: vec![],
+        }),
+    ];
+
+    SemanticModel {
+        use_static_result_alloc,
+        ..model(1, 3, instructions)
+    }
+}
+
+fn results(names: &[&str]) -> Vec<Condition> {
+    names
+        .iter()
+        .map(|&name| Condition::Result(name.into()))
+        .collect()
+}
+
+#[test]
+fn and_not_reads_each_result() -> Result<(), String> {
+    let condition = Condition::And(vec![
+        Condition::Result("r0".into()),
+        Condition::Not(Box::new(Condition::Result("r1".into()))),
+    ]);
+
+    let static_ir = ir(&get_model(condition.clone(), true))?;
+    assert_eq!(
+        static_ir
+            .matches("call i1 @__quantum__qis__read_result__body(")
+            .count(),
+        2
+    );
+    assert_eq!(static_ir.matches(" = xor i1 ").count(), 1);
+    assert_eq!(static_ir.matches(" = and i1 ").count(), 1);
+
+    let dynamic_ir = ir(&get_model(condition, false))?;
+    assert_eq!(
+        dynamic_ir
+            .matches("call i1 @__quantum__rt__result_equal(")
+            .count(),
+        2
+    );
+    assert_eq!(dynamic_ir.matches(" = and i1 ").count(), 1);
+    Ok(())
+}
+
+#[test]
+fn or_and_parity_combine_every_result() -> Result<(), String> {
+    let or_ir = ir(&get_model(
+        Condition::Or(results(&["r0", "r1", "r2"])),
+        true,
+    ))?;
+    assert_eq!(or_ir.matches(" = or i1 ").count(), 2);
+
+    let xor_ir = ir(&get_model(
+        Condition::Xor(results(&["r0", "r1", "r2"])),
+        true,
+    ))?;
+    assert_eq!(xor_ir.matches(" = xor i1 ").count(), 2);
+    Ok(())
+}
+
+#[test]
+fn empty_conditions_are_constant() -> Result<(), String> {
+    let and_ir = ir(&get_model(Condition::And(vec![]), true))?;
+    assert!(and_ir.contains("br i1 true, label %then, label %else"));
+
+    let xor_ir = ir(&get_model(Condition::Xor(vec![]), true))?;
+    assert!(xor_ir.contains("br i1 false, label %then, label %else"));
+    Ok(())
+}
+
+#[test]
+fn unknown_nested_result_fails() {
+    let condition = Condition::Or(vec![
+        Condition::Result("r0".into()),
+        Condition::Not(Box::new(Condition::Result("r3".into()))),
+    ]);
+
+    assert_eq!(
+        emit::ir(&get_model(condition, true)).err(),
+        Some(GenerationError::UnknownResult("r3".to_string()))
+    );
+}
@@ -0,0 +1,52 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ir, model};
+use crate::generation::interop::{ControlledGate, Gate, Instruction, SemanticModel, Value};
+
+fn get_model(instructions: Vec<Instruction>) -> SemanticModel {
+    model(3, 1, instructions)
+}
+
+fn controlled(gate: Gate, adjoint: bool) -> Instruction {
+    Instruction::ControlledGate(ControlledGate {
+        gate,
+        adjoint,
+        controls: vec!["q0".into(), "q1".into()],
+        target: "q2".into(),
+    })
+}
+
+#[test]
+fn controls_are_passed_in_an_array() -> Result<(), String> {
+    let model = get_model(vec![controlled(Gate::X, false)]);
+    let ir = ir(&model)?;
+    assert!(ir.contains("%controls = call %Array* @__quantum__rt__array_create_1d(i32 8, i64 2)"));
+    assert!(
+        ir.contains("call i8* @__quantum__rt__array_get_element_ptr_1d(%Array* %controls, i64 1)")
+    );
+    assert!(ir.contains(
+        "call void @__quantum__qis__x__ctl(%Array* %controls, %Qubit* inttoptr (i64 2 to %Qubit*))"
+    ));
+    assert!(ir.contains(
+        "call void @__quantum__rt__array_update_reference_count(%Array* %controls, i32 -1)"
+    ));
+    Ok(())
+}
+
+#[test]
+fn adjoint_uses_ctladj_unless_self_adjoint() -> Result<(), String> {
+    let model = get_model(vec![
+        controlled(Gate::S, true),
+        controlled(Gate::H, true),
+        controlled(Gate::Rz(Value::Double(0.5)), true),
+    ]);
+    let ir = ir(&model)?;
+    assert!(ir.contains("call void @__quantum__qis__s__ctladj(%Array* %controls,"));
+    assert!(ir.contains("call void @__quantum__qis__h__ctl(%Array* %controls"));
+    assert!(!ir.contains("@__quantum__qis__h__ctladj"));
+    assert!(ir.contains(
+        "call void @__quantum__qis__rz__ctladj(%Array* %controls2, double 5.000000e-01, %Qubit* inttoptr (i64 2 to %Qubit*))"
+    ));
+    Ok(())
+}
@@ -0,0 +1,85 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ir, model};
+use crate::generation::interop::{
+    Condition, If, Instruction, Measured, SemanticModel, Single, SourceSpan, Spanned,
+};
+
+fn get_model(instructions: Vec<Instruction>) -> SemanticModel {
+    model(1, 1, instructions)
+}
+
+fn spanned(line: u32, column: Option<u32>, inst: Instruction) -> Instruction {
+    Instruction::Spanned(Spanned {
+        span: SourceSpan {
+            file: "test.py".to_string(),
+            line,
+            column,
+        },
+        inst: Box::new(inst),
+    })
+}
+
+#[test]
+fn spanned_instruction_has_location() -> Result<(), String> {
+    let model = get_model(vec![
+        Instruction::X(Single::new("q0".to_string())),
+        spanned(3, Some(5), Instruction::H(Single::new("q0".to_string()))),
+    ]);
+    let ir = ir(&model)?;
+    assert!(ir.contains("call void @__quantum__qis__h__body(%Qubit* null), !dbg"));
+    assert!(ir.contains("!DILocation(line: 3, column: 5, scope:"));
+    assert!(ir.contains("!DIFile(filename: \"test.py\""));
+    assert!(ir.contains("!\"Debug Info Version\", i32 3}"));
+    Ok(())
+}
+
+#[test]
+fn nested_spans_have_locations() -> Result<(), String> {
+    let model = get_model(vec![
+        spanned(
+            1,
+            None,
+            Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+        ),
+        Instruction::If(If {
+            condition: Condition::Result("r0".into()),
+            then_insts: vec![spanned(
+                2,
+                None,
+                Instruction::X(Single::new("q0".to_string())),
+            )],
+            else_insts: vec![],
+        }),
+    ]);
+    let ir = ir(&model)?;
+    assert!(ir.contains("!DILocation(line: 1, scope:"));
+    assert!(ir.contains("!DILocation(line: 2, scope:"));
+    Ok(())
+}
+
+#[test]
+fn no_debug_info_without_spans() -> Result<(), String> {
+    let model = get_model(vec![Instruction::H(Single::new("q0".to_string()))]);
+    let ir = ir(&model)?;
+    assert!(!ir.contains("!dbg"));
+    assert!(!ir.contains("Debug Info Version"));
+    Ok(())
+}
+
+#[test]
+fn locations_in_one_file_share_a_scope() -> Result<(), String> {
+    let model = get_model(vec![
+        spanned(1, None, Instruction::H(Single::new("q0".to_string()))),
+        spanned(2, None, Instruction::X(Single::new("q0".to_string()))),
+        spanned(3, None, Instruction::Z(Single::new("q0".to_string()))),
+    ]);
+    let ir = ir(&model)?;
+    assert_eq!(ir.matches("!DIFile(filename: \"test.py\"").count(), 1);
+    assert_eq!(ir.matches("!DILexicalBlock(").count(), 1);
+    for line in 1..=3 {
+        assert!(ir.contains(&format!("!DILocation(line: {}, scope:", line)));
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{functions, ir, model};
+use crate::generation::{
+    emit,
+    interop::{Call, Instruction, Measured, ReturnType, SemanticModel, Single},
+};
+
+const NAMES: [&str; 8] = ["f", "g", "h", "i", "j", "k", "l", "m"];
+
+fn get_model() -> SemanticModel {
+    let mut instructions: Vec<_> = NAMES
+        .iter()
+        .map(|name| {
+            Instruction::Call(Call {
+                name: (*name).to_string(),
+                args: vec![],
+                result: None,
+            })
+        })
+        .collect();
+    for index in 0..16 {
+        let name = format!("q{}", index);
+        instructions.push(Instruction::H(Single::new(name.clone())));
+        instructions.push(Instruction::M(Measured::new(name, format!("r{}", index))));
+    }
+
+    let external_functions: Vec<_> = NAMES
+        .iter()
+        .map(|name| (*name, &[][..], ReturnType::Void))
+        .collect();
+    SemanticModel {
+        use_static_qubit_alloc: false,
+        use_static_result_alloc: false,
+        record_output: true,
+        external_functions: functions(&external_functions),
+        ..model(16, 16, instructions)
+    }
+}
+
+#[test]
+fn repeated_emission_is_identical() -> Result<(), String> {
+    let expected = ir(&get_model())?;
+    let bitcode = emit::bitcode(&get_model()).map_err(|e| e.to_string())?;
+    for _ in 0..16 {
+        assert_eq!(ir(&get_model())?, expected);
+        assert_eq!(
+            emit::bitcode(&get_model()).map_err(|e| e.to_string())?,
+            bitcode
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn qubits_are_released_in_allocation_order() -> Result<(), String> {
+    let ir = ir(&get_model())?;
+    let releases: Vec<_> = ir
+        .lines()
+        .filter(|line| line.contains("call void @__quantum__rt__qubit_release("))
+        .collect();
+    let expected: Vec<_> = (0..16)
+        .map(|index| {
+            format!(
+                "  call void @__quantum__rt__qubit_release(%Qubit* %q{})",
+                index
+            )
+        })
+        .collect();
+    assert_eq!(releases, expected);
+    Ok(())
+}
+
+#[test]
+fn external_functions_are_declared_in_order_of_name() -> Result<(), String> {
+    let ir = ir(&get_model())?;
+    let declarations: Vec<_> = ir
+        .lines()
+        .filter_map(|line| line.strip_prefix("declare void @"))
+        .filter(|line| line.len() == 3)
+        .collect();
+    assert_eq!(
+        declarations,
+        ["f()", "g()", "h()", "i()", "j()", "k()", "l()", "m()"]
+    );
+    Ok(())
+}
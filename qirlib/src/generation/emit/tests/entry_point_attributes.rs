@@ -0,0 +1,66 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ir, model};
+use crate::generation::interop::{
+    EntryPoint, Instruction, NamedMetadata, Profile, SemanticModel, Single,
+};
+
+fn get_model() -> SemanticModel {
+    SemanticModel {
+        entry_point_name: "program".to_string(),
+        entry_point_attributes: vec![
+            ("output_labeling_schema".to_string(), "labeled".to_string()),
+            ("vendor_tag".to_string(), String::new()),
+        ],
+        ..model(1, 1, vec![Instruction::H(Single::new("q0"))])
+    }
+}
+
+#[test]
+fn entry_point_has_name_and_attributes() -> Result<(), String> {
+    let ir = ir(&get_model())?;
+    assert!(ir.contains("define void @program() #0 {"));
+    assert!(!ir.contains("@main"));
+    assert!(ir.contains(
+        "attributes #0 = { \"EntryPoint\" \"output_labeling_schema\"=\"labeled\" \"requiredQubits\"=\"1\" \"requiredResults\"=\"1\" \"vendor_tag\" }"
+    ));
+    Ok(())
+}
+
+#[test]
+fn every_entry_point_has_attributes() -> Result<(), String> {
+    let mut model = get_model();
+    model.profile = Some(Profile::Base);
+    model
+        .entry_points
+        .push(EntryPoint::new("other".to_string()));
+    let ir = ir(&model)?;
+    assert!(ir.contains("define void @other() #1 {"));
+    assert_eq!(
+        ir.matches("\"output_labeling_schema\"=\"labeled\"").count(),
+        2
+    );
+    assert_eq!(ir.matches("\"qir_profiles\"=\"base_profile\"").count(), 2);
+    Ok(())
+}
+
+#[test]
+fn module_has_named_metadata() -> Result<(), String> {
+    let mut model = get_model();
+    model.metadata = vec![
+        NamedMetadata {
+            name: "vendor.info".to_string(),
+            values: vec!["a".to_string(), "b".to_string()],
+        },
+        NamedMetadata {
+            name: "vendor.info".to_string(),
+            values: vec![],
+        },
+    ];
+    let ir = ir(&model)?;
+    assert!(ir.contains("!vendor.info = !{!0, !1}"));
+    assert!(ir.contains("!0 = !{!\"a\", !\"b\"}"));
+    assert!(ir.contains("!1 = !{}"));
+    Ok(())
+}
@@ -0,0 +1,92 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ir, model};
+use crate::generation::{
+    emit,
+    error::GenerationError,
+    interop::{
+        ClassicalRegister, Controlled, EntryPoint, Instruction, Measured, Parameter,
+        QuantumRegister, Rotated, SemanticModel, Single, Value, ValueType,
+    },
+};
+
+fn get_model(entry_points: Vec<EntryPoint>) -> SemanticModel {
+    SemanticModel {
+        entry_points,
+        ..model(1, 1, vec![Instruction::M(Measured::new("q0", "r0"))])
+    }
+}
+
+fn bell() -> EntryPoint {
+    EntryPoint {
+        name: "bell".to_string(),
+        params: vec![],
+        registers: vec![ClassicalRegister::new("c".to_string(), 2)],
+        qubits: vec![
+            QuantumRegister::new("q".to_string(), 0),
+            QuantumRegister::new("q".to_string(), 1),
+        ],
+        instructions: vec![
+            Instruction::H(Single::new("q0".to_string())),
+            Instruction::Cx(Controlled::new("q0".to_string(), "q1".to_string())),
+            Instruction::M(Measured::new("q0".to_string(), "c0".to_string())),
+            Instruction::M(Measured::new("q1".to_string(), "c1".to_string())),
+        ],
+    }
+}
+
+#[test]
+fn each_entry_point_has_its_own_attributes() -> Result<(), String> {
+    let model = get_model(vec![bell()]);
+    let ir = ir(&model)?;
+    assert!(ir.contains("define void @main() #0"));
+    assert!(ir.contains("define void @bell() #1"));
+    assert!(ir.contains(
+        "attributes #0 = { \"EntryPoint\" \"requiredQubits\"=\"1\" \"requiredResults\"=\"1\" }"
+    ));
+    assert!(ir.contains(
+        "attributes #1 = { \"EntryPoint\" \"requiredQubits\"=\"2\" \"requiredResults\"=\"2\" }"
+    ));
+    Ok(())
+}
+
+#[test]
+fn entry_point_params_are_variables() -> Result<(), String> {
+    let mut model = get_model(vec![]);
+    model.params = vec![
+        Parameter::new("theta".to_string(), ValueType::Double),
+        Parameter::new("phi".to_string(), ValueType::Double),
+    ];
+    model.instructions = vec![
+        Instruction::Rx(Rotated::new(
+            Value::Variable("theta".to_string()),
+            "q0".to_string(),
+        )),
+        Instruction::Rz(Rotated::new(
+            Value::Variable("phi".to_string()),
+            "q0".to_string(),
+        )),
+    ];
+
+    let ir = ir(&model)?;
+    assert!(ir.contains("define void @main(double %theta, double %phi) #0"));
+    assert!(ir.contains("call void @__quantum__qis__rx__body(double %theta, %Qubit* null)"));
+    assert!(ir.contains("call void @__quantum__qis__rz__body(double %phi, %Qubit* null)"));
+    Ok(())
+}
+
+#[test]
+fn entry_point_cannot_use_registers_of_another() {
+    let mut entry_point = bell();
+    entry_point.instructions = vec![Instruction::M(Measured::new(
+        "q0".to_string(),
+        "r0".to_string(),
+    ))];
+
+    let model = get_model(vec![entry_point]);
+    assert_eq!(
+        emit::ir(&model),
+        Err(GenerationError::UnknownResult("r0".to_string()))
+    );
+}
@@ -0,0 +1,60 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::model;
+use crate::generation::{
+    emit,
+    error::GenerationError,
+    interop::{Call, Instruction, Measured, SemanticModel, Single, Value},
+};
+
+fn get_model(instructions: Vec<Instruction>) -> SemanticModel {
+    model(1, 1, instructions)
+}
+
+#[test]
+fn unknown_qubit_is_reported() {
+    let model = get_model(vec![Instruction::H(Single::new("q1".to_string()))]);
+    assert_eq!(
+        emit::ir(&model),
+        Err(GenerationError::UnknownQubit("q1".to_string()))
+    );
+}
+
+#[test]
+fn unknown_result_is_reported() {
+    let model = get_model(vec![Instruction::M(Measured::new(
+        "q0".to_string(),
+        "r1".to_string(),
+    ))]);
+    assert_eq!(
+        emit::ir(&model),
+        Err(GenerationError::UnknownResult("r1".to_string()))
+    );
+}
+
+#[test]
+fn unknown_function_is_reported() {
+    let model = get_model(vec![Instruction::Call(Call {
+        name: "foo".to_string(),
+        args: vec![],
+        result: None,
+    })]);
+    assert_eq!(
+        emit::bitcode(&model),
+        Err(GenerationError::UnknownFunction("foo".to_string()))
+    );
+}
+
+#[test]
+fn unknown_variable_is_reported() {
+    let model = get_model(vec![Instruction::Call(Call {
+        name: "foo".to_string(),
+        args: vec![Value::Variable("i".to_string())],
+        result: None,
+    })]);
+    assert_eq!(
+        emit::ir(&model),
+        Err(GenerationError::UnknownVariable("i".to_string()))
+    );
+}
@@ -0,0 +1,118 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{functions, model};
+use crate::generation::{
+    emit,
+    error::GenerationError,
+    interop::{Instruction, Profile, ReturnType, SemanticModel, Single, ValueType},
+};
+use inkwell::{context::Context, memory_buffer::MemoryBuffer, module::Module};
+
+const EXISTING_IR: &str = r#"
+    %Qubit = type opaque
+
+    declare void @__quantum__qis__h__body(%Qubit*)
+    declare void @kernel()
+
+    define void @main() #0 {
+    entry:
+      call void @__quantum__qis__h__body(%Qubit* null)
+      call void @kernel()
+      ret void
+    }
+
+    define void @helper(%Qubit* %q) {
+    entry:
+      ret void
+    }
+
+    attributes #0 = { "EntryPoint" }
+
+    !llvm.module.flags = !{!0}
+    !0 = !{i32 1, !"qir_major_version", i32 1}
+"#;
+
+fn load(context: &Context) -> Module {
+    load_ir(context, EXISTING_IR)
+}
+
+fn load_ir<'ctx>(context: &'ctx Context, ir: &str) -> Module<'ctx> {
+    let buffer = MemoryBuffer::create_from_memory_range_copy(ir.as_bytes(), "test");
+    context.create_module_from_ir(buffer).unwrap()
+}
+
+fn get_model() -> SemanticModel {
+    SemanticModel {
+        profile: Some(Profile::Base),
+        external_functions: functions(&[("helper", &[ValueType::Qubit], ReturnType::Void)]),
+        entry_point_name: "kernel".to_string(),
+        ..model(1, 0, vec![Instruction::H(Single::new("q0"))])
+    }
+}
+
+#[test]
+fn model_defines_declared_kernel() -> Result<(), String> {
+    let context = Context::create();
+    let model = get_model();
+    let generator =
+        emit::populate_module(&context, load(&context), &model).map_err(|e| e.to_string())?;
+    let ir = generator.get_ir();
+
+    assert!(ir.contains("define void @kernel() #1 {"));
+    assert!(!ir.contains("declare void @kernel()"));
+    assert_eq!(
+        ir.matches("declare void @__quantum__qis__h__body(%Qubit*)")
+            .count(),
+        1
+    );
+    assert!(!ir.contains("%Qubit."));
+    assert!(!ir.contains("@helper.1"));
+    assert_eq!(ir.matches("!\"qir_major_version\"").count(), 1);
+    Ok(())
+}
+
+#[test]
+fn defined_function_is_not_replaced() {
+    let context = Context::create();
+    let mut model = get_model();
+    model.entry_point_name = "main".to_string();
+
+    assert_eq!(
+        emit::populate_module(&context, load(&context), &model).err(),
+        Some(GenerationError::FunctionExists("main".to_string()))
+    );
+}
+
+#[test]
+fn external_function_type_must_match() {
+    let context = Context::create();
+    let mut model = get_model();
+    model
+        .external_functions
+        .get_mut("helper")
+        .unwrap()
+        .param_types
+        .clear();
+
+    assert_eq!(
+        emit::populate_module(&context, load(&context), &model).err(),
+        Some(GenerationError::FunctionExists("helper".to_string()))
+    );
+}
+
+#[test]
+fn module_flag_must_match() {
+    let context = Context::create();
+    let ir = EXISTING_IR.replace(
+        "!{i32 1, !\"qir_major_version\", i32 1}",
+        "!{i32 1, !\"qir_major_version\", i32 2}",
+    );
+
+    assert_eq!(
+        emit::populate_module(&context, load_ir(&context, &ir), &get_model()).err(),
+        Some(GenerationError::ModuleFlagMismatch(
+            "qir_major_version".to_string()
+        ))
+    );
+}
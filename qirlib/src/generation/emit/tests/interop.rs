@@ -0,0 +1,82 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ir, model};
+use crate::generation::{
+    emit,
+    error::GenerationError,
+    interop::{
+        EntryPoint, Instruction, Measured, Parameter, Rotated, SemanticModel, Value, ValueType,
+    },
+};
+
+fn get_model(use_static_result_alloc: bool) -> SemanticModel {
+    let instructions = vec![
+        Instruction::Rx(Rotated::new(Value::Variable("theta".to_string()), "q0")),
+        Instruction::M(Measured::new("q0", "r0")),
+    ];
+
+    SemanticModel {
+        use_static_qubit_alloc: false,
+        use_static_result_alloc,
+        params: vec![Parameter::new("theta".to_string(), ValueType::Double)],
+        interop_wrappers: true,
+        ..model(2, 2, instructions)
+    }
+}
+
+#[test]
+fn wrapper_calls_entry_point() -> Result<(), String> {
+    let ir = ir(&get_model(true))?;
+    assert!(ir.contains("define void @main(double %theta) #0 {"));
+    assert!(ir.contains("define void @main__Interop(double %theta, i8* %results) #1 {"));
+    assert!(ir.contains("attributes #1 = { \"InteropFriendly\" }"));
+
+    let wrapper = &ir[ir.find("@main__Interop").ok_or("Missing wrapper.")?..];
+    let wrapper = &wrapper[..wrapper.find("\n}").ok_or("Unterminated wrapper.")?];
+    assert_eq!(wrapper.matches("call void @main(double %theta)").count(), 1);
+    assert!(!wrapper.contains("call %Qubit* @__quantum__rt__qubit_allocate()"));
+    assert!(!wrapper.contains("@__quantum__qis__"));
+    Ok(())
+}
+
+#[test]
+fn wrapper_writes_results_to_buffer() -> Result<(), String> {
+    let ir = ir(&get_model(true))?;
+    assert!(ir.contains("call i1 @__quantum__qis__read_result__body(%Result* null)"));
+    assert!(ir.contains(
+        "call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 1 to %Result*))"
+    ));
+    assert!(ir.contains("getelementptr inbounds i8, i8* %results, i64 0"));
+    assert!(ir.contains("getelementptr inbounds i8, i8* %results, i64 1"));
+    assert_eq!(ir.matches("store i8 ").count(), 2);
+    Ok(())
+}
+
+#[test]
+fn wrappers_require_static_results() {
+    assert_eq!(
+        emit::ir(&get_model(false)).err(),
+        Some(GenerationError::InteropDynamicResults)
+    );
+}
+
+#[test]
+fn every_entry_point_has_a_wrapper() -> Result<(), String> {
+    let mut model = get_model(true);
+    model
+        .entry_points
+        .push(EntryPoint::new("other".to_string()));
+    let ir = ir(&model)?;
+    assert!(ir.contains("define void @other__Interop(i8* %results) #1 {"));
+    Ok(())
+}
+
+#[test]
+fn wrappers_are_optional() -> Result<(), String> {
+    let mut model = get_model(false);
+    model.interop_wrappers = false;
+    let ir = ir(&model)?;
+    assert!(!ir.contains("Interop"));
+    Ok(())
+}
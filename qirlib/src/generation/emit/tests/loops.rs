@@ -0,0 +1,118 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{functions, ir, model};
+use crate::generation::interop::{
+    Call, Condition, For, If, Instruction, Measured, ReturnType, SemanticModel, Single, Value,
+    ValueType,
+};
+
+fn get_model(instructions: Vec<Instruction>) -> SemanticModel {
+    SemanticModel {
+        use_static_result_alloc: false,
+        external_functions: functions(&[(
+            "record",
+            &[ValueType::Integer { width: 64 }],
+            ReturnType::Void,
+        )]),
+        ..model(1, 1, instructions)
+    }
+}
+
+#[test]
+fn loop_index_is_a_phi_node() -> Result<(), String> {
+    let model = get_model(vec![Instruction::For(For {
+        index: "i".to_string(),
+        count: 4,
+        body: vec![
+            Instruction::H(Single::new("q0".to_string())),
+            Instruction::Call(Call {
+                name: "record".to_string(),
+                args: vec![Value::Variable("i".to_string())],
+                result: None,
+            }),
+        ],
+    })]);
+
+    let ir = ir(&model)?;
+    assert!(ir.contains("%i = phi i64 [ 0, %entry ], [ %next, %body ]"));
+    assert!(ir.contains("icmp ult i64 %i, 4"));
+    assert!(ir.contains("call void @record(i64 %i)"));
+    Ok(())
+}
+
+#[test]
+fn loop_body_can_branch() -> Result<(), String> {
+    let model = get_model(vec![Instruction::For(For {
+        index: "i".to_string(),
+        count: 2,
+        body: vec![
+            Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+            Instruction::If(If {
+                condition: Condition::Result("r0".into()),
+                then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+                else_insts: vec![],
+            }),
+        ],
+    })]);
+
+    let ir = ir(&model)?;
+    assert!(ir.contains("%i = phi i64 [ 0, %entry ], [ %next, %continue ]"));
+    Ok(())
+}
+
+#[test]
+fn result_measured_in_loop_is_read_after_it() -> Result<(), String> {
+    let model = get_model(vec![
+        Instruction::For(For {
+            index: "i".to_string(),
+            count: 3,
+            body: vec![
+                Instruction::If(If {
+                    condition: Condition::Result("r0".into()),
+                    then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+                    else_insts: vec![],
+                }),
+                Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+            ],
+        }),
+        Instruction::If(If {
+            condition: Condition::Result("r0".into()),
+            then_insts: vec![Instruction::H(Single::new("q0".to_string()))],
+            else_insts: vec![],
+        }),
+    ]);
+
+    let ir = ir(&model)?;
+    assert!(ir.contains("%r0 = phi %Result* [ %zero, %entry ], [ %r01, %continue ]"));
+    assert!(ir.contains("call i1 @__quantum__rt__result_equal(%Result* %r0, %Result* %one"));
+    Ok(())
+}
+
+#[test]
+fn result_measured_in_branch_of_loop_is_merged() -> Result<(), String> {
+    let model = get_model(vec![
+        Instruction::For(For {
+            index: "i".to_string(),
+            count: 2,
+            body: vec![Instruction::If(If {
+                condition: Condition::Result("r0".into()),
+                then_insts: vec![Instruction::M(Measured::new(
+                    "q0".to_string(),
+                    "r0".to_string(),
+                ))],
+                else_insts: vec![],
+            })],
+        }),
+        Instruction::If(If {
+            condition: Condition::Result("r0".into()),
+            then_insts: vec![Instruction::H(Single::new("q0".to_string()))],
+            else_insts: vec![],
+        }),
+    ]);
+
+    let ir = ir(&model)?;
+    assert!(ir.contains("%r02 = phi %Result* [ %r01, %then ], [ %r0, %else ]"));
+    assert!(ir.contains("%r0 = phi %Result* [ %zero, %entry ], [ %r02, %continue ]"));
+    Ok(())
+}
@@ -0,0 +1,72 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ir, model};
+use crate::generation::{
+    emit,
+    error::GenerationError,
+    interop::{Condition, If, Instruction, Measure, Pauli, Profile, SemanticModel, Single},
+};
+
+fn get_model(measure: Measure, use_static_result_alloc: bool) -> SemanticModel {
+    let instructions = vec![
+        Instruction::Measure(measure),
+        Instruction::If(If {
+            condition: Condition::Result("r0".into()),
+            then_insts: vec![Instruction::X(Single::new("q0"))],
+            else_insts: vec![],
+        }),
+    ];
+
+    SemanticModel {
+        use_static_result_alloc,
+        ..model(2, 1, instructions)
+    }
+}
+
+fn zx_measure() -> Measure {
+    Measure {
+        bases: vec![Pauli::Z, Pauli::X],
+        qubits: vec!["q0".into(), "q1".into()],
+        target: "r0".into(),
+    }
+}
+
+#[test]
+fn measure_passes_paulis_and_qubits() -> Result<(), String> {
+    let ir = ir(&get_model(zx_measure(), false))?;
+    assert!(ir.contains("%bases = call %Array* @__quantum__rt__array_create_1d(i32 1, i64 2)"));
+    assert!(ir.contains("%qubits = call %Array* @__quantum__rt__array_create_1d(i32 8, i64 2)"));
+    assert!(ir.contains("store i2 -2, i2* "));
+    assert!(ir.contains("store i2 1, i2* "));
+    assert!(ir.contains(
+        "%r0 = call %Result* @__quantum__qis__measure__body(%Array* %bases, %Array* %qubits)"
+    ));
+    assert_eq!(
+        ir.matches("call void @__quantum__rt__array_update_reference_count(")
+            .count(),
+        2
+    );
+    assert!(ir.contains("call i1 @__quantum__rt__result_equal(%Result* %r0, "));
+    Ok(())
+}
+
+#[test]
+fn measure_fails_with_static_results() {
+    assert_eq!(
+        emit::ir(&get_model(zx_measure(), true)).err(),
+        Some(GenerationError::StaticMeasure("r0".to_string()))
+    );
+}
+
+#[test]
+fn measure_is_not_in_base_profile() {
+    let mut model = get_model(zx_measure(), false);
+    model.instructions.truncate(1);
+    model.profile = Some(Profile::Base);
+
+    assert_eq!(
+        emit::ir(&model).err(),
+        Some(GenerationError::NotInProfile(Profile::Base))
+    );
+}
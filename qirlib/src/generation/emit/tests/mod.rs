@@ -0,0 +1,70 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Tests of emission, one module per feature. Models are built from `model` and customized with
+//! struct update syntax.
+
+mod arithmetic;
+mod blocks;
+mod branches;
+mod calls;
+mod conditions;
+mod controlled_gates;
+mod debug_info;
+mod determinism;
+mod entry_point_attributes;
+mod entry_points;
+mod errors;
+mod existing_module;
+mod interop;
+mod loops;
+mod measure;
+mod output;
+mod profiles;
+mod qubit_arrays;
+mod references;
+mod result_alloc;
+mod subroutines;
+mod switches;
+
+use crate::generation::{
+    emit,
+    interop::{
+        ClassicalRegister, FunctionType, Instruction, QuantumRegister, ReturnType, SemanticModel,
+        ValueType,
+    },
+};
+use std::collections::HashMap;
+
+/// A model named `test` with `num_qubits` statically allocated qubits in register `q`, a register
+/// `r` of `num_results` results, and the instructions.
+fn model(num_qubits: u64, num_results: u64, instructions: Vec<Instruction>) -> SemanticModel {
+    SemanticModel {
+        registers: vec![ClassicalRegister::new("r".to_string(), num_results)],
+        qubits: (0..num_qubits)
+            .map(|index| QuantumRegister::new("q".to_string(), index))
+            .collect(),
+        instructions,
+        use_static_qubit_alloc: true,
+        ..SemanticModel::new("test".to_owned())
+    }
+}
+
+/// External functions with the given names, parameter types and return types.
+fn functions(functions: &[(&str, &[ValueType], ReturnType)]) -> HashMap<String, FunctionType> {
+    functions
+        .iter()
+        .map(|(name, param_types, return_type)| {
+            let ty = FunctionType {
+                param_types: param_types.to_vec(),
+                return_type: return_type.clone(),
+            };
+            ((*name).to_string(), ty)
+        })
+        .collect()
+}
+
+/// Emits the model as IR, with the error as a string.
+fn ir(model: &SemanticModel) -> Result<String, String> {
+    emit::ir(model).map_err(|e| e.to_string())
+}
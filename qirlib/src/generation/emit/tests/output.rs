@@ -0,0 +1,77 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ir, model};
+use crate::generation::interop::{ClassicalRegister, Instruction, Measured, SemanticModel};
+
+fn get_model(registers: Vec<ClassicalRegister>, use_static_result_alloc: bool) -> SemanticModel {
+    SemanticModel {
+        registers,
+        use_static_result_alloc,
+        record_output: true,
+        ..model(1, 0, vec![Instruction::M(Measured::new("q0", "a0"))])
+    }
+}
+
+fn labeled(name: &str, size: u64, label: &str) -> ClassicalRegister {
+    ClassicalRegister {
+        label: Some(label.to_string()),
+        ..ClassicalRegister::new(name.to_string(), size)
+    }
+}
+
+#[test]
+fn single_register_is_recorded_as_array() -> Result<(), String> {
+    let model = get_model(vec![labeled("a", 2, "a")], true);
+    let ir = ir(&model)?;
+    assert!(!ir.contains("@__quantum__rt__tuple_record_output"));
+    assert!(ir.contains("@0 = private unnamed_addr constant [2 x i8] c\"a\\00\""));
+    assert!(ir.contains(
+        "call void @__quantum__rt__array_record_output(i64 2, i8* getelementptr inbounds ([2 x i8], [2 x i8]* @0, i32 0, i32 0))"
+    ));
+    assert!(ir.contains("call void @__quantum__rt__result_record_output(%Result* null, i8* null)"));
+    assert!(ir.contains(
+        "call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 1 to %Result*), i8* null)"
+    ));
+    Ok(())
+}
+
+#[test]
+fn registers_are_recorded_as_tuple_of_arrays() -> Result<(), String> {
+    let registers = vec![
+        labeled("a", 1, "a"),
+        ClassicalRegister::new("b".to_string(), 1),
+    ];
+    let model = get_model(registers, true);
+    let ir = ir(&model)?;
+    let tuple = ir
+        .find("call void @__quantum__rt__tuple_record_output(i64 2, i8* null)")
+        .ok_or("Missing tuple.")?;
+    let unlabeled = ir
+        .find("call void @__quantum__rt__array_record_output(i64 1, i8* null)")
+        .ok_or("Missing array.")?;
+    assert!(tuple < unlabeled);
+    Ok(())
+}
+
+#[test]
+fn unmeasured_dynamic_result_is_recorded_as_zero() -> Result<(), String> {
+    let model = get_model(vec![ClassicalRegister::new("a".to_string(), 2)], false);
+    let ir = ir(&model)?;
+    assert!(ir.contains("%zero = call %Result* @__quantum__rt__result_get_zero()"));
+    assert!(ir.contains("call void @__quantum__rt__result_record_output(%Result* %a0, i8* null)"));
+    assert!(ir.contains("call void @__quantum__rt__result_record_output(%Result* %zero, i8* null)"));
+    Ok(())
+}
+
+#[test]
+fn output_is_not_recorded_by_default() -> Result<(), String> {
+    let model = SemanticModel {
+        record_output: false,
+        profile: None,
+        ..get_model(vec![labeled("a", 1, "a")], true)
+    };
+    let ir = ir(&model)?;
+    assert!(!ir.contains("record_output"));
+    Ok(())
+}
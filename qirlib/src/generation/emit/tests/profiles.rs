@@ -0,0 +1,172 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{functions, ir, model};
+use crate::generation::{
+    emit,
+    error::GenerationError,
+    interop::{
+        Call, ClassicalRegister, Condition, ControlledGate, EntryPoint, Gate, If, Instruction,
+        Measured, Profile, ReturnType, SemanticModel, Single,
+    },
+};
+
+fn get_model(profile: Profile, instructions: Vec<Instruction>) -> SemanticModel {
+    SemanticModel {
+        profile: Some(profile),
+        ..model(1, 1, instructions)
+    }
+}
+
+fn measure_and_flip() -> Vec<Instruction> {
+    vec![
+        Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+        Instruction::If(If {
+            condition: Condition::Result("r0".into()),
+            then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+            else_insts: vec![],
+        }),
+    ]
+}
+
+#[test]
+fn base_profile_has_module_flags() -> Result<(), String> {
+    let model = get_model(
+        Profile::Base,
+        vec![Instruction::H(Single::new("q0".to_string()))],
+    );
+    let ir = ir(&model)?;
+    assert!(ir.contains("!llvm.module.flags = !{!0, !1, !2, !3}"));
+    assert!(ir.contains("!0 = !{i32 1, !\"qir_major_version\", i32 1}"));
+    assert!(ir.contains("!1 = !{i32 7, !\"qir_minor_version\", i32 0}"));
+    assert!(ir.contains("!2 = !{i32 1, !\"dynamic_qubit_management\", i1 false}"));
+    assert!(ir.contains("!3 = !{i32 1, !\"dynamic_result_management\", i1 false}"));
+    assert!(ir.contains("\"qir_profiles\"=\"base_profile\""));
+    Ok(())
+}
+
+#[test]
+fn dynamic_allocation_is_flagged() -> Result<(), String> {
+    let model = SemanticModel {
+        use_static_qubit_alloc: false,
+        use_static_result_alloc: false,
+        ..get_model(Profile::Adaptive, measure_and_flip())
+    };
+    let ir = ir(&model)?;
+    assert!(ir.contains("!{i32 1, !\"dynamic_qubit_management\", i1 true}"));
+    assert!(ir.contains("!{i32 1, !\"dynamic_result_management\", i1 true}"));
+    assert!(ir.contains("\"qir_profiles\"=\"adaptive_profile\""));
+    Ok(())
+}
+
+#[test]
+fn every_entry_point_has_profile_attribute() -> Result<(), String> {
+    let mut model = get_model(Profile::Base, vec![]);
+    model
+        .entry_points
+        .push(EntryPoint::new("other".to_string()));
+    let ir = ir(&model)?;
+    assert_eq!(ir.matches("\"qir_profiles\"=\"base_profile\"").count(), 2);
+    Ok(())
+}
+
+#[test]
+fn base_profile_rejects_branching() {
+    let model = get_model(Profile::Base, measure_and_flip());
+    assert_eq!(
+        emit::ir(&model).err(),
+        Some(GenerationError::NotInProfile(Profile::Base))
+    );
+}
+
+#[test]
+fn base_profile_rejects_array_runtime() {
+    // The __ctl function of a controlled gate takes its controls as a runtime array.
+    let controlled = get_model(
+        Profile::Base,
+        vec![Instruction::ControlledGate(ControlledGate {
+            gate: Gate::X,
+            adjoint: false,
+            controls: vec![],
+            target: "q0".into(),
+        })],
+    );
+    let array_call = SemanticModel {
+        external_functions: functions(&[(
+            "__quantum__rt__array_record_output",
+            &[],
+            ReturnType::Void,
+        )]),
+        ..get_model(
+            Profile::Base,
+            vec![Instruction::Call(Call {
+                name: "__quantum__rt__array_record_output".to_string(),
+                args: vec![],
+                result: None,
+            })],
+        )
+    };
+
+    for model in [controlled, array_call] {
+        assert_eq!(
+            emit::ir(&model).err(),
+            Some(GenerationError::NotInProfile(Profile::Base))
+        );
+    }
+}
+
+#[test]
+fn base_profile_records_only_results() -> Result<(), String> {
+    let model = SemanticModel {
+        registers: vec![
+            ClassicalRegister::new("a".to_string(), 1),
+            ClassicalRegister::new("b".to_string(), 1),
+        ],
+        record_output: true,
+        ..get_model(Profile::Base, vec![])
+    };
+    let ir = ir(&model)?;
+    assert!(!ir.contains("@__quantum__rt__tuple_record_output"));
+    assert!(!ir.contains("@__quantum__rt__array_record_output"));
+    assert_eq!(
+        ir.matches("call void @__quantum__rt__result_record_output(")
+            .count(),
+        2
+    );
+    Ok(())
+}
+
+#[test]
+fn base_profile_rejects_dynamic_allocation() {
+    let instructions = vec![Instruction::M(Measured::new(
+        "q0".to_string(),
+        "r0".to_string(),
+    ))];
+    let dynamic_qubits = SemanticModel {
+        use_static_qubit_alloc: false,
+        ..get_model(Profile::Base, instructions.clone())
+    };
+    let dynamic_results = SemanticModel {
+        use_static_result_alloc: false,
+        ..get_model(Profile::Base, instructions)
+    };
+
+    for model in [dynamic_qubits, dynamic_results] {
+        assert_eq!(
+            emit::ir(&model).err(),
+            Some(GenerationError::NotInProfile(Profile::Base))
+        );
+    }
+}
+
+#[test]
+fn no_flags_without_profile() -> Result<(), String> {
+    let model = SemanticModel {
+        profile: None,
+        ..get_model(Profile::Base, vec![])
+    };
+    let ir = ir(&model)?;
+    assert!(!ir.contains("llvm.module.flags"));
+    assert!(!ir.contains("qir_profiles"));
+    Ok(())
+}
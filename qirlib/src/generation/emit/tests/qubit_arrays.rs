@@ -0,0 +1,46 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ir, model};
+use crate::generation::interop::{
+    Controlled, Instruction, Measured, QuantumRegister, SemanticModel, Single,
+};
+
+fn get_model(use_static_qubit_alloc: bool) -> SemanticModel {
+    let mut model = model(
+        2,
+        1,
+        vec![
+            Instruction::H(Single::new("q0")),
+            Instruction::Cx(Controlled::new("q0", "a0")),
+            Instruction::M(Measured::new("q1", "r0")),
+        ],
+    );
+    model.qubits.push(QuantumRegister::new("a".to_string(), 0));
+    model.use_static_qubit_alloc = use_static_qubit_alloc;
+    model.use_array_qubit_alloc = true;
+    model
+}
+
+#[test]
+fn registers_are_allocated_as_arrays() -> Result<(), String> {
+    let ir = ir(&get_model(false))?;
+    assert!(ir.contains("%q = call %Array* @__quantum__rt__qubit_allocate_array(i64 2)"));
+    assert!(ir.contains("%a = call %Array* @__quantum__rt__qubit_allocate_array(i64 1)"));
+    assert!(ir.contains("call i8* @__quantum__rt__array_get_element_ptr_1d(%Array* %q, i64 1)"));
+    assert!(ir.contains("%q1 = load %Qubit*, %Qubit** "));
+    assert!(ir.contains("call void @__quantum__qis__cnot__body(%Qubit* %q0, %Qubit* %a0)"));
+    assert!(ir.contains("call void @__quantum__rt__qubit_release_array(%Array* %q)"));
+    assert!(ir.contains("call void @__quantum__rt__qubit_release_array(%Array* %a)"));
+    assert!(!ir.contains("@__quantum__rt__qubit_allocate()"));
+    assert!(!ir.contains("@__quantum__rt__qubit_release(%Qubit*)"));
+    Ok(())
+}
+
+#[test]
+fn static_allocation_ignores_arrays() -> Result<(), String> {
+    let ir = ir(&get_model(true))?;
+    assert!(!ir.contains("qubit_allocate_array"));
+    assert!(!ir.contains("qubit_release_array"));
+    Ok(())
+}
@@ -0,0 +1,81 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ir, model};
+use crate::generation::{
+    emit,
+    error::GenerationError,
+    interop::{
+        ClassicalRegister, Controlled, Instruction, Measured, QuantumRegister, Reference,
+        SemanticModel, Single,
+    },
+};
+
+/// Registers `r` and `r1` have elements that are both named `r11`, and so do qubit registers `q`
+/// and `q1`.
+fn get_model(instructions: Vec<Instruction>) -> SemanticModel {
+    SemanticModel {
+        registers: vec![
+            ClassicalRegister::new("r".to_string(), 12),
+            ClassicalRegister::new("r1".to_string(), 2),
+        ],
+        qubits: vec![
+            QuantumRegister::new("q".to_string(), 11),
+            QuantumRegister::new("q1".to_string(), 1),
+        ],
+        use_static_qubit_alloc: false,
+        use_static_result_alloc: false,
+        ..model(0, 0, instructions)
+    }
+}
+
+#[test]
+fn indexed_references_do_not_collide() -> Result<(), String> {
+    let model = get_model(vec![
+        Instruction::Cx(Controlled::new(
+            Reference::indexed("q".to_string(), 11),
+            Reference::indexed("q1".to_string(), 1),
+        )),
+        Instruction::M(Measured::new(
+            Reference::indexed("q".to_string(), 11),
+            Reference::indexed("r".to_string(), 11),
+        )),
+        Instruction::M(Measured::new(
+            Reference::indexed("q1".to_string(), 1),
+            Reference::indexed("r1".to_string(), 1),
+        )),
+    ]);
+    let ir = ir(&model)?;
+    assert!(ir.contains("call void @__quantum__qis__cnot__body(%Qubit* %q11, %Qubit* %q1"));
+    assert_eq!(ir.matches("@__quantum__qis__m__body(%Qubit*").count(), 2);
+    Ok(())
+}
+
+#[test]
+fn unique_names_still_resolve() -> Result<(), String> {
+    let mut model = get_model(vec![Instruction::M(Measured::new(
+        "q11".to_string(),
+        "r0".to_string(),
+    ))]);
+    model.qubits.pop();
+    ir(&model)?;
+    Ok(())
+}
+
+#[test]
+fn ambiguous_names_are_rejected() {
+    let model = get_model(vec![Instruction::H(Single::new("q11".to_string()))]);
+    assert_eq!(
+        emit::ir(&model),
+        Err(GenerationError::AmbiguousReference("q11".to_string()))
+    );
+
+    let model = get_model(vec![Instruction::M(Measured::new(
+        Reference::indexed("q".to_string(), 11),
+        "r11".to_string(),
+    ))]);
+    assert_eq!(
+        emit::ir(&model),
+        Err(GenerationError::AmbiguousReference("r11".to_string()))
+    );
+}
@@ -0,0 +1,100 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::model;
+use crate::generation::{
+    emit,
+    error::GenerationError,
+    interop::{ClassicalRegister, Instruction, Measured, SemanticModel, Single},
+};
+
+fn get_model(use_static_qubit_alloc: bool, use_static_result_alloc: bool) -> SemanticModel {
+    SemanticModel {
+        use_static_qubit_alloc,
+        use_static_result_alloc,
+        ..model(1, 1, vec![Instruction::M(Measured::new("q0", "r0"))])
+    }
+}
+
+#[test]
+fn when_dynamic_qubit_and_dynamic_result_alloc_is_used_then_only_entypoint_attribute_is_emitted(
+) -> Result<(), GenerationError> {
+    let model = get_model(false, false);
+    let actual_ir: String = emit::ir(&model)?;
+    assert!(actual_ir.contains("attributes #0 = { \"EntryPoint\" }"));
+    Ok(())
+}
+
+#[test]
+fn when_static_qubit_alloc_is_used_then_required_attribute_is_emitted(
+) -> Result<(), GenerationError> {
+    let model = get_model(true, false);
+    let actual_ir: String = emit::ir(&model)?;
+    assert!(actual_ir.contains("attributes #0 = { \"EntryPoint\" \"requiredQubits\"=\"1\" }"));
+    Ok(())
+}
+
+#[test]
+fn when_static_result_alloc_is_used_then_required_attribute_is_emitted(
+) -> Result<(), GenerationError> {
+    let model = get_model(false, true);
+    let actual_ir: String = emit::ir(&model)?;
+    assert!(actual_ir.contains("attributes #0 = { \"EntryPoint\" \"requiredResults\"=\"1\" }"));
+    Ok(())
+}
+
+#[test]
+fn when_static_qubit_and_static_result_alloc_is_used_then_both_required_attribute_are_emitted(
+) -> Result<(), GenerationError> {
+    let model = get_model(true, true);
+    let actual_ir: String = emit::ir(&model)?;
+    assert!(actual_ir.contains(
+        "attributes #0 = { \"EntryPoint\" \"requiredQubits\"=\"1\" \"requiredResults\"=\"1\" }"
+    ));
+    Ok(())
+}
+
+#[test]
+fn when_static_result_alloc_is_used_then_emitted_attribute_sums_registers_correctly(
+) -> Result<(), GenerationError> {
+    let model = SemanticModel {
+        registers: vec![
+            ClassicalRegister::new("r".to_string(), 1),
+            ClassicalRegister::new("r".to_string(), 3),
+            ClassicalRegister::new("r".to_string(), 4),
+        ],
+        ..get_model(false, true)
+    };
+    let actual_ir: String = emit::ir(&model)?;
+    assert!(actual_ir.contains("attributes #0 = { \"EntryPoint\" \"requiredResults\"=\"8\" }"));
+    Ok(())
+}
+
+#[test]
+fn when_static_result_alloc_is_used_and_no_registers_declared_then_emitted_attribute_sums_correctly(
+) -> Result<(), GenerationError> {
+    let model = SemanticModel {
+        registers: vec![],
+        instructions: vec![Instruction::H(Single::new("q0"))],
+        ..get_model(false, true)
+    };
+    let actual_ir: String = emit::ir(&model)?;
+    assert!(actual_ir.contains("attributes #0 = { \"EntryPoint\" \"requiredResults\"=\"0\" }"));
+    Ok(())
+}
+
+#[test]
+fn when_dynamic_result_alloc_is_used_then_m_body_is_emitted() -> Result<(), GenerationError> {
+    let model = get_model(false, false);
+    let actual_ir: String = emit::ir(&model)?;
+    assert!(actual_ir.contains("declare %Result* @__quantum__qis__m__body(%Qubit*)"));
+    Ok(())
+}
+
+#[test]
+fn when_static_result_alloc_is_used_then_mz_body_is_emitted() -> Result<(), GenerationError> {
+    let model = get_model(false, true);
+    let actual_ir: String = emit::ir(&model)?;
+    assert!(actual_ir.contains("declare void @__quantum__qis__mz__body(%Qubit*, %Result*)"));
+    Ok(())
+}
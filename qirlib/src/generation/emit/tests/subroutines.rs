@@ -0,0 +1,147 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ir, model};
+use crate::generation::{
+    emit,
+    error::GenerationError,
+    interop::{
+        Call, Condition, If, Instruction, Measured, Parameter, Rotated, SemanticModel, Single,
+        Subroutine, Value, ValueType,
+    },
+};
+
+fn get_model(subroutines: Vec<Subroutine>, instructions: Vec<Instruction>) -> SemanticModel {
+    SemanticModel {
+        subroutines,
+        ..model(1, 1, instructions)
+    }
+}
+
+fn call(name: &str, args: Vec<Value>) -> Instruction {
+    Instruction::Call(Call {
+        name: name.to_string(),
+        args,
+        result: None,
+    })
+}
+
+#[test]
+fn subroutine_is_internal_function() -> Result<(), String> {
+    let rotate = Subroutine {
+        name: "rotate".to_string(),
+        params: vec![
+            Parameter::new("target".to_string(), ValueType::Qubit),
+            Parameter::new("theta".to_string(), ValueType::Double),
+        ],
+        instructions: vec![
+            Instruction::H(Single::new("target".to_string())),
+            Instruction::Rz(Rotated::new(
+                Value::Variable("theta".to_string()),
+                "target".to_string(),
+            )),
+        ],
+    };
+
+    let model = get_model(
+        vec![rotate],
+        vec![call(
+            "rotate",
+            vec![Value::Qubit("q0".into()), Value::Double(0.5)],
+        )],
+    );
+
+    let ir = ir(&model)?;
+    assert!(ir.contains("define internal void @rotate(%Qubit* %target, double %theta)"));
+    assert!(ir.contains("call void @__quantum__qis__rz__body(double %theta, %Qubit* %target)"));
+    assert!(ir.contains("call void @rotate(%Qubit* null, double 5.000000e-01)"));
+    Ok(())
+}
+
+#[test]
+fn subroutines_can_call_each_other() -> Result<(), String> {
+    let outer = Subroutine {
+        name: "outer".to_string(),
+        params: vec![Parameter::new("a".to_string(), ValueType::Qubit)],
+        instructions: vec![call("inner", vec![Value::Qubit("a".into())])],
+    };
+    let inner = Subroutine {
+        name: "inner".to_string(),
+        params: vec![Parameter::new("b".to_string(), ValueType::Qubit)],
+        instructions: vec![Instruction::X(Single::new("b".to_string()))],
+    };
+
+    let model = get_model(
+        vec![outer, inner],
+        vec![call("outer", vec![Value::Qubit("q0".into())])],
+    );
+
+    let ir = ir(&model)?;
+    assert!(ir.contains("call void @inner(%Qubit* %a)"));
+    Ok(())
+}
+
+#[test]
+fn subroutine_cannot_use_entry_point_qubits() {
+    let subroutine = Subroutine {
+        name: "f".to_string(),
+        params: vec![],
+        instructions: vec![Instruction::X(Single::new("q0".to_string()))],
+    };
+
+    let model = get_model(vec![subroutine], vec![call("f", vec![])]);
+    assert_eq!(
+        emit::ir(&model),
+        Err(GenerationError::UnknownQubit("q0".to_string()))
+    );
+}
+
+fn measure_in_subroutine(use_static_result_alloc: bool) -> SemanticModel {
+    let measure = Subroutine {
+        name: "measure".to_string(),
+        params: vec![
+            Parameter::new("a".to_string(), ValueType::Qubit),
+            Parameter::new("p".to_string(), ValueType::Result),
+        ],
+        instructions: vec![Instruction::M(Measured::new(
+            "a".to_string(),
+            "p".to_string(),
+        ))],
+    };
+
+    let mut model = get_model(
+        vec![measure],
+        vec![
+            call(
+                "measure",
+                vec![Value::Qubit("q0".into()), Value::Result("r0".into())],
+            ),
+            Instruction::If(If {
+                condition: Condition::Result("r0".into()),
+                then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+                else_insts: vec![],
+            }),
+        ],
+    );
+    model.use_static_result_alloc = use_static_result_alloc;
+    model
+}
+
+#[test]
+fn static_result_parameter_is_measured_in_place() -> Result<(), String> {
+    let model = measure_in_subroutine(true);
+    let ir = ir(&model)?;
+    assert!(ir.contains("call void @__quantum__qis__mz__body(%Qubit* %a, %Result* %p)"));
+    assert!(ir.contains("call void @measure(%Qubit* null, %Result* null)"));
+    assert!(ir.contains("call i1 @__quantum__qis__read_result__body(%Result* null)"));
+    Ok(())
+}
+
+#[test]
+fn dynamic_result_parameter_cannot_be_measured() {
+    let model = measure_in_subroutine(false);
+    assert_eq!(
+        emit::ir(&model),
+        Err(GenerationError::MeasureParameter("p".to_string()))
+    );
+}
@@ -0,0 +1,110 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ir, model};
+use crate::generation::{
+    emit,
+    error::GenerationError,
+    interop::{
+        ClassicalRegister, Condition, If, Instruction, Measured, Reference, SemanticModel, Single,
+        Switch, SwitchCase,
+    },
+};
+
+fn get_model(switch: Switch, size: u64) -> SemanticModel {
+    let mut instructions: Vec<_> = (0..size)
+        .map(|index| {
+            Instruction::M(Measured::new(
+                "q0",
+                Reference::indexed("r".to_string(), index),
+            ))
+        })
+        .collect();
+    instructions.push(Instruction::Switch(switch));
+    model(1, size, instructions)
+}
+
+#[test]
+fn switch_reads_register_as_integer() -> Result<(), String> {
+    let register = ClassicalRegister::new("r".to_string(), 3);
+    let switch = Switch::register(
+        &register,
+        vec![
+            SwitchCase {
+                value: 5,
+                insts: vec![Instruction::X(Single::new("q0"))],
+            },
+            SwitchCase {
+                value: 2,
+                insts: vec![Instruction::Z(Single::new("q0"))],
+            },
+        ],
+        vec![Instruction::H(Single::new("q0"))],
+    );
+
+    let ir = ir(&get_model(switch, 3))?;
+    assert_eq!(
+        ir.matches("call i1 @__quantum__qis__read_result__body(")
+            .count(),
+        3
+    );
+    assert_eq!(ir.matches(" = zext i1 ").count(), 3);
+    assert_eq!(ir.matches(" = shl i64 ").count(), 2);
+    assert_eq!(ir.matches(" = or i64 ").count(), 2);
+    assert!(ir.contains("switch i64 "));
+    assert!(ir.contains("i64 5, label %case"));
+    assert!(ir.contains("i64 2, label %case"));
+    assert_eq!(ir.matches("br label %continue").count(), 3);
+    Ok(())
+}
+
+#[test]
+fn empty_switch_runs_default() -> Result<(), String> {
+    let switch = Switch {
+        results: vec![],
+        cases: vec![],
+        default_insts: vec![Instruction::X(Single::new("q0"))],
+    };
+
+    let ir = ir(&get_model(switch, 0))?;
+    assert!(ir.contains("switch i64 0, label %default ["));
+    Ok(())
+}
+
+#[test]
+fn result_measured_in_case_is_read_after_switch() -> Result<(), String> {
+    let register = ClassicalRegister::new("r".to_string(), 1);
+    let switch = Switch::register(
+        &register,
+        vec![SwitchCase {
+            value: 1,
+            insts: vec![Instruction::M(Measured::new("q0", "r0"))],
+        }],
+        vec![],
+    );
+    let mut model = SemanticModel {
+        use_static_result_alloc: false,
+        ..get_model(switch, 1)
+    };
+    model.instructions.push(Instruction::If(If {
+        condition: Condition::Result("r0".into()),
+        then_insts: vec![Instruction::H(Single::new("q0"))],
+        else_insts: vec![],
+    }));
+
+    let ir = ir(&model)?;
+    assert!(ir.contains("%r02 = phi %Result* [ %r01, %case ], [ %r0, %default ]"));
+    assert!(ir.contains("call i1 @__quantum__rt__result_equal(%Result* %r02, %Result* %one"));
+    Ok(())
+}
+
+#[test]
+fn too_many_results_fail() {
+    let register = ClassicalRegister::new("r".to_string(), 65);
+    let switch = Switch::register(&register, vec![], vec![]);
+
+    assert_eq!(
+        emit::ir(&get_model(switch, 65)).err(),
+        Some(GenerationError::SwitchTooWide(65))
+    );
+}
@@ -0,0 +1,412 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::generation::interop::{Profile, ValueType};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+/// An error that occurred while generating a module from a `SemanticModel`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GenerationError {
+    /// An instruction referred to a qubit that was not declared in the model.
+    UnknownQubit(String),
+
+    /// An instruction referred to a result that was not declared in the model.
+    UnknownResult(String),
+
+    /// An instruction referred to a qubit or result by a name that more than one register element
+    /// has.
+    AmbiguousReference(String),
+
+    /// A statically allocated result was read before it was initialized.
+    UninitializedResult(String),
+
+    /// A call referred to a function that was not declared in the module.
+    UnknownFunction(String),
+
+    /// The module that the model was emitted into already has a function with the name of a
+    /// function in the model, which can't be reused.
+    FunctionExists(String),
+
+    /// An instruction referred to a variable that is not in scope.
+    UnknownVariable(String),
+
+    /// A call bound the return value of a function that returns void.
+    VoidReturn(String),
+
+    /// The operands of the instruction defining the named variable have invalid types.
+    InvalidOperands(String),
+
+    /// An `If` branched on a variable that is not a 1-bit integer or a result.
+    InvalidCondition(String),
+
+    /// A `Switch` read more results than fit in a 64-bit integer.
+    SwitchTooWide(usize),
+
+    /// A `Measure` wrote to the named result, but results are statically allocated.
+    StaticMeasure(String),
+
+    /// A measurement in a subroutine wrote to the named result parameter, but results are
+    /// dynamically allocated and passed by value, so the caller would not see the new result.
+    MeasureParameter(String),
+
+    /// Interop wrappers were requested, but results are dynamically allocated, so a wrapper could
+    /// not read the results of the entry point it calls.
+    InteropDynamicResults,
+
+    /// A string value contains a null character, so it can't be null-terminated.
+    NulInString(String),
+
+    /// The model contains an instruction or uses dynamic allocation that its profile does not
+    /// allow.
+    NotInProfile(Profile),
+
+    /// The module already has the named module flag with a different value than the model needs.
+    ModuleFlagMismatch(String),
+
+    /// The generated module failed LLVM verification.
+    Verification(String),
+}
+
+impl Display for GenerationError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            GenerationError::UnknownQubit(name) => write!(f, "Qubit {} not found.", name),
+            GenerationError::UnknownResult(name) => write!(f, "Result {} not found.", name),
+            GenerationError::AmbiguousReference(name) => {
+                write!(
+                    f,
+                    "Reference {} matches more than one register element.",
+                    name
+                )
+            }
+            GenerationError::UninitializedResult(name) => {
+                write!(f, "Result {} not initialized.", name)
+            }
+            GenerationError::UnknownFunction(name) => write!(f, "Function {} not found.", name),
+            GenerationError::FunctionExists(name) => {
+                write!(f, "Function {} already exists in the module.", name)
+            }
+            GenerationError::UnknownVariable(name) => write!(f, "Variable {} not found.", name),
+            GenerationError::VoidReturn(name) => {
+                write!(f, "Function {} does not return a value.", name)
+            }
+            GenerationError::InvalidOperands(name) => {
+                write!(f, "Operands for variable {} have invalid types.", name)
+            }
+            GenerationError::InvalidCondition(name) => {
+                write!(f, "Condition {} is not a 1-bit integer or a result.", name)
+            }
+            GenerationError::SwitchTooWide(width) => write!(
+                f,
+                "Switch reads {} results, but its value can have at most 64 bits.",
+                width
+            ),
+            GenerationError::StaticMeasure(name) => write!(
+                f,
+                "Measure cannot write to result {} with static result allocation.",
+                name
+            ),
+            GenerationError::MeasureParameter(name) => write!(
+                f,
+                "Measurement cannot write to result parameter {} with dynamic result allocation.",
+                name
+            ),
+            GenerationError::InteropDynamicResults => {
+                write!(f, "Interop wrappers require static result allocation.")
+            }
+            GenerationError::NulInString(value) => {
+                write!(f, "String {:?} contains a null character.", value)
+            }
+            GenerationError::NotInProfile(profile) => write!(
+                f,
+                "The {:?} profile does not allow an instruction or allocation in the model.",
+                profile
+            ),
+            GenerationError::ModuleFlagMismatch(name) => {
+                write!(f, "Module flag {} already has a different value.", name)
+            }
+            GenerationError::Verification(message) => f.write_str(message),
+        }
+    }
+}
+
+impl Error for GenerationError {}
+
+/// A semantic problem found by `SemanticModel::validate`. Problems with an instruction carry the
+/// index of the top-level instruction in which they were found. Problems in a subroutine or an
+/// additional entry point are wrapped in `InFunction`, and their indices refer to its body.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationError {
+    /// More than one register was declared with the same name.
+    DuplicateRegister(String),
+
+    /// A subroutine or entry point has the same name as another function in the module.
+    DuplicateFunction(String),
+
+    /// A subroutine or entry point has more than one parameter with the same name.
+    DuplicateParameter(String),
+
+    /// An entry point has a parameter that is not a double.
+    EntryPointParameterType(String),
+
+    /// A problem in the body of the named subroutine or entry point.
+    InFunction {
+        name: String,
+        error: Box<ValidationError>,
+    },
+
+    /// An instruction is not allowed by the profile of the model.
+    NotInProfile { index: usize, profile: Profile },
+
+    /// The model dynamically allocates qubits or results, which its profile does not allow.
+    DynamicAllocation(Profile),
+
+    /// Interop wrappers were requested, but results are dynamically allocated, so a wrapper could
+    /// not read the results of the entry point it calls.
+    InteropDynamicResults,
+
+    /// A controlled gate used the same qubit as both control and target.
+    SameControlAndTarget { index: usize, qubit: String },
+
+    /// A multi-qubit gate used the same qubit more than once.
+    DuplicateQubit { index: usize, qubit: String },
+
+    /// A `Measure` has a different number of Pauli bases than qubits.
+    BasisCount {
+        index: usize,
+        bases: usize,
+        qubits: usize,
+    },
+
+    /// A `Measure` wrote to a result, but results are statically allocated, and a measurement in a
+    /// Pauli basis returns a new result.
+    StaticMeasure { index: usize, result: String },
+
+    /// An instruction referred to a qubit that was not declared.
+    UndeclaredQubit { index: usize, qubit: String },
+
+    /// An instruction referred to a result that is not a bit of any declared register.
+    UndeclaredResult { index: usize, result: String },
+
+    /// A measurement in a subroutine wrote to a result parameter, but results are dynamically
+    /// allocated and passed by value.
+    MeasureParameter { index: usize, result: String },
+
+    /// An instruction referred to a qubit or result by a name that more than one register element
+    /// has.
+    AmbiguousReference { index: usize, name: String },
+
+    /// A rotation angle was NaN or infinite.
+    NonFiniteAngle { index: usize, theta: f64 },
+
+    /// A rotation angle was not a double.
+    AngleType { index: usize },
+
+    /// A string value contains a null character.
+    NulInString { index: usize },
+
+    /// A call referred to a function that was not declared.
+    UndeclaredFunction { index: usize, name: String },
+
+    /// An instruction referred to a variable that is not in scope.
+    UndeclaredVariable { index: usize, name: String },
+
+    /// An instruction defined a variable with the same name as one that is already in scope.
+    DuplicateVariable { index: usize, name: String },
+
+    /// The operands of an arithmetic or comparison instruction have invalid types.
+    OperandType { index: usize, name: String },
+
+    /// An `If` branched on a variable that is not a 1-bit integer or a result.
+    ConditionType { index: usize, name: String },
+
+    /// A `Switch` read more results than fit in a 64-bit integer.
+    SwitchTooWide { index: usize, width: usize },
+
+    /// A `Switch` has a case whose value has more bits than the switch reads, so it never runs.
+    UnreachableCase { index: usize, value: u64 },
+
+    /// A `Switch` has more than one case with the same value.
+    DuplicateCase { index: usize, value: u64 },
+
+    /// A call bound the return value of a function that returns void.
+    VoidReturn { index: usize, name: String },
+
+    /// A call passed a different number of arguments than the function declares.
+    ArgumentCount {
+        index: usize,
+        name: String,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// A call argument did not match the type of the corresponding parameter.
+    ArgumentType {
+        index: usize,
+        name: String,
+        position: usize,
+        expected: ValueType,
+    },
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ValidationError::DuplicateRegister(name) => {
+                write!(f, "Register {} is declared more than once.", name)
+            }
+            ValidationError::DuplicateFunction(name) => {
+                write!(f, "Function {} is declared more than once.", name)
+            }
+            ValidationError::DuplicateParameter(name) => {
+                write!(f, "Parameter {} is declared more than once.", name)
+            }
+            ValidationError::EntryPointParameterType(name) => {
+                write!(f, "Entry point parameter {} is not a double.", name)
+            }
+            ValidationError::InFunction { name, error } => {
+                write!(f, "Function {}: {}", name, error)
+            }
+            ValidationError::NotInProfile { index, profile } => write!(
+                f,
+                "Instruction {}: not allowed by the {:?} profile.",
+                index, profile
+            ),
+            ValidationError::DynamicAllocation(profile) => write!(
+                f,
+                "The {:?} profile does not allow dynamic qubit or result allocation.",
+                profile
+            ),
+            ValidationError::InteropDynamicResults => {
+                write!(f, "Interop wrappers require static result allocation.")
+            }
+            ValidationError::SameControlAndTarget { index, qubit } => write!(
+                f,
+                "Instruction {}: qubit {} is both control and target.",
+                index, qubit
+            ),
+            ValidationError::DuplicateQubit { index, qubit } => write!(
+                f,
+                "Instruction {}: qubit {} is used more than once.",
+                index, qubit
+            ),
+            ValidationError::BasisCount {
+                index,
+                bases,
+                qubits,
+            } => write!(
+                f,
+                "Instruction {}: measurement has {} bases for {} qubits.",
+                index, bases, qubits
+            ),
+            ValidationError::StaticMeasure { index, result } => write!(
+                f,
+                "Instruction {}: measurement cannot write to result {} with static result allocation.",
+                index, result
+            ),
+            ValidationError::UndeclaredQubit { index, qubit } => {
+                write!(f, "Instruction {}: qubit {} is not declared.", index, qubit)
+            }
+            ValidationError::UndeclaredResult { index, result } => write!(
+                f,
+                "Instruction {}: result {} is not declared.",
+                index, result
+            ),
+            ValidationError::MeasureParameter { index, result } => write!(
+                f,
+                "Instruction {}: measurement writes to result parameter {}, which is passed by value.",
+                index, result
+            ),
+            ValidationError::AmbiguousReference { index, name } => write!(
+                f,
+                "Instruction {}: {} matches more than one register element.",
+                index, name
+            ),
+            ValidationError::NonFiniteAngle { index, theta } => write!(
+                f,
+                "Instruction {}: rotation angle {} is not finite.",
+                index, theta
+            ),
+            ValidationError::AngleType { index } => {
+                write!(f, "Instruction {}: rotation angle is not a double.", index)
+            }
+            ValidationError::NulInString { index } => {
+                write!(
+                    f,
+                    "Instruction {}: string contains a null character.",
+                    index
+                )
+            }
+            ValidationError::UndeclaredFunction { index, name } => write!(
+                f,
+                "Instruction {}: function {} is not declared.",
+                index, name
+            ),
+            ValidationError::UndeclaredVariable { index, name } => write!(
+                f,
+                "Instruction {}: variable {} is not in scope.",
+                index, name
+            ),
+            ValidationError::DuplicateVariable { index, name } => write!(
+                f,
+                "Instruction {}: variable {} is already defined.",
+                index, name
+            ),
+            ValidationError::OperandType { index, name } => write!(
+                f,
+                "Instruction {}: operands for variable {} have invalid types.",
+                index, name
+            ),
+            ValidationError::ConditionType { index, name } => write!(
+                f,
+                "Instruction {}: condition {} is not a 1-bit integer or a result.",
+                index, name
+            ),
+            ValidationError::SwitchTooWide { index, width } => write!(
+                f,
+                "Instruction {}: switch reads {} results, but its value can have at most 64 bits.",
+                index, width
+            ),
+            ValidationError::UnreachableCase { index, value } => write!(
+                f,
+                "Instruction {}: switch case {} does not fit in the results that it reads.",
+                index, value
+            ),
+            ValidationError::DuplicateCase { index, value } => write!(
+                f,
+                "Instruction {}: switch case {} appears more than once.",
+                index, value
+            ),
+            ValidationError::VoidReturn { index, name } => write!(
+                f,
+                "Instruction {}: function {} does not return a value.",
+                index, name
+            ),
+            ValidationError::ArgumentCount {
+                index,
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Instruction {}: function {} expects {} arguments, got {}.",
+                index, name, expected, actual
+            ),
+            ValidationError::ArgumentType {
+                index,
+                name,
+                position,
+                expected,
+            } => write!(
+                f,
+                "Instruction {}: argument {} of function {} should be {:?}.",
+                index, position, name, expected
+            ),
+        }
+    }
+}
+
+impl Error for ValidationError {}
@@ -1,9 +1,17 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use std::collections::HashMap;
+use crate::generation::{error::ValidationError, validation};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+};
+
+#[cfg(feature = "serde")]
+use std::convert::TryFrom;
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QuantumRegister {
     pub name: String,
     pub index: u64,
@@ -22,15 +30,23 @@ impl QuantumRegister {
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClassicalRegister {
     pub name: String,
     pub size: u64,
+
+    /// The label attached to the register when output recording is enabled.
+    pub label: Option<String>,
 }
 
 impl ClassicalRegister {
     #[must_use]
     pub fn new(name: String, size: u64) -> Self {
-        ClassicalRegister { name, size }
+        ClassicalRegister {
+            name,
+            size,
+            label: None,
+        }
     }
 
     #[must_use]
@@ -40,74 +56,370 @@ impl ClassicalRegister {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Register {
     Quantum(QuantumRegister),
     Classical(ClassicalRegister),
 }
 
+/// A reference to a qubit or result.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Reference {
+    /// The element at `index` in the register named `register`.
+    Indexed { register: String, index: u64 },
+
+    /// A qubit or result variable, or a register element named by the register name followed by
+    /// the index, such as `q0`. A register element name is ambiguous if another element has the
+    /// same name, such as index 11 of `q` and index 1 of `q1`, so `Indexed` should be preferred.
+    Named(String),
+}
+
+impl Reference {
+    #[must_use]
+    pub fn indexed(register: String, index: u64) -> Self {
+        Reference::Indexed { register, index }
+    }
+
+    /// The name of the reference, with the index of a register element appended to the register
+    /// name.
+    #[must_use]
+    pub fn flat_name(&self) -> String {
+        match self {
+            Reference::Indexed { register, index } => format!("{}{}", register, index),
+            Reference::Named(name) => name.clone(),
+        }
+    }
+}
+
+impl From<String> for Reference {
+    fn from(name: String) -> Self {
+        Reference::Named(name)
+    }
+}
+
+impl From<&str> for Reference {
+    fn from(name: &str) -> Self {
+        Reference::Named(name.to_owned())
+    }
+}
+
+impl Display for Reference {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Reference::Indexed { register, index } => write!(f, "{}[{}]", register, index),
+            Reference::Named(name) => f.write_str(name),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Controlled {
-    pub control: String,
-    pub target: String,
+    pub control: Reference,
+    pub target: Reference,
 }
 
 impl Controlled {
     #[must_use]
-    pub fn new(control: String, target: String) -> Self {
-        Controlled { control, target }
+    pub fn new(control: impl Into<Reference>, target: impl Into<Reference>) -> Self {
+        Controlled {
+            control: control.into(),
+            target: target.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DoublyControlled {
+    pub control1: Reference,
+    pub control2: Reference,
+    pub target: Reference,
+}
+
+impl DoublyControlled {
+    #[must_use]
+    pub fn new(
+        control1: impl Into<Reference>,
+        control2: impl Into<Reference>,
+        target: impl Into<Reference>,
+    ) -> Self {
+        DoublyControlled {
+            control1: control1.into(),
+            control2: control2.into(),
+            target: target.into(),
+        }
     }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Swapped {
+    pub qubit1: Reference,
+    pub qubit2: Reference,
+}
+
+impl Swapped {
+    #[must_use]
+    pub fn new(qubit1: impl Into<Reference>, qubit2: impl Into<Reference>) -> Self {
+        Swapped {
+            qubit1: qubit1.into(),
+            qubit2: qubit2.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Measured {
-    pub qubit: String,
-    pub target: String,
+    pub qubit: Reference,
+    pub target: Reference,
 }
 
 impl Measured {
     #[must_use]
-    pub fn new(qubit: String, target: String) -> Self {
-        Measured { qubit, target }
+    pub fn new(qubit: impl Into<Reference>, target: impl Into<Reference>) -> Self {
+        Measured {
+            qubit: qubit.into(),
+            target: target.into(),
+        }
     }
 }
 
+/// A single-qubit Pauli operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Pauli {
+    I,
+    X,
+    Y,
+    Z,
+}
+
+/// Jointly measures `qubits` in the product of the Pauli `bases`, with one basis for each qubit,
+/// and writes the outcome to `target`. Measuring one qubit in the Z basis is the same as `M`.
+///
+/// The measurement returns a new result, so it can't be used with static result allocation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Measure {
+    pub bases: Vec<Pauli>,
+    pub qubits: Vec<Reference>,
+    pub target: Reference,
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rotated {
-    pub theta: f64,
-    pub qubit: String,
+    /// The rotation angle, which must be a double.
+    pub theta: Value,
+    pub qubit: Reference,
 }
 
 impl Rotated {
     #[must_use]
-    pub fn new(theta: f64, qubit: String) -> Self {
-        Rotated { theta, qubit }
+    pub fn new(theta: Value, qubit: impl Into<Reference>) -> Self {
+        Rotated {
+            theta,
+            qubit: qubit.into(),
+        }
     }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Single {
-    pub qubit: String,
+    pub qubit: Reference,
 }
 
 impl Single {
     #[must_use]
-    pub fn new(qubit: String) -> Self {
-        Single { qubit }
+    pub fn new(qubit: impl Into<Reference>) -> Self {
+        Single {
+            qubit: qubit.into(),
+        }
     }
 }
 
+/// A single-qubit or rotation gate that can be applied with `ControlledGate`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gate {
+    H,
+    S,
+    T,
+    X,
+    Y,
+    Z,
+    Rx(Value),
+    Ry(Value),
+    Rz(Value),
+}
+
+/// Applies a gate, or its adjoint, to the target qubit controlled on every qubit in `controls`.
+/// It is emitted as a call to the `__ctl` or `__ctladj` variant of the gate's intrinsic, with the
+/// controls passed in an `%Array*`. Gates that are their own adjoint always use `__ctl`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControlledGate {
+    pub gate: Gate,
+    pub adjoint: bool,
+    pub controls: Vec<Reference>,
+    pub target: Reference,
+}
+
+/// A boolean value that an `If` branches on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Condition {
+    /// True if the result is one.
+    Result(Reference),
+
+    /// True if the named variable is a 1-bit integer that is one, or a result that is one.
+    Variable(String),
+
+    /// True if the condition is false.
+    Not(Box<Condition>),
+
+    /// True if every condition is true, or if there are no conditions.
+    And(Vec<Condition>),
+
+    /// True if any condition is true.
+    Or(Vec<Condition>),
+
+    /// True if an odd number of the conditions are true, which is the parity of the results.
+    Xor(Vec<Condition>),
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct If {
-    pub condition: String,
+    pub condition: Condition,
     pub then_insts: Vec<Instruction>,
     pub else_insts: Vec<Instruction>,
 }
 
+/// Reads the results as the bits of an unsigned integer, with the first result as the least
+/// significant bit, and runs the instructions of the case for that value, or `default_insts` if no
+/// case has it. A result that is one is a bit that is one.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Switch {
+    pub results: Vec<Reference>,
+    pub cases: Vec<SwitchCase>,
+    pub default_insts: Vec<Instruction>,
+}
+
+impl Switch {
+    /// Creates a `Switch` on the value of every result in the register.
+    #[must_use]
+    pub fn register(
+        register: &ClassicalRegister,
+        cases: Vec<SwitchCase>,
+        default_insts: Vec<Instruction>,
+    ) -> Self {
+        Switch {
+            results: (0..register.size)
+                .map(|index| Reference::indexed(register.name.clone(), index))
+                .collect(),
+            cases,
+            default_insts,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SwitchCase {
+    pub value: u64,
+    pub insts: Vec<Instruction>,
+}
+
+/// Repeats `body` `count` times. Inside the body, `index` names a 64-bit integer variable that
+/// counts up from zero.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct For {
+    pub index: String,
+    pub count: u64,
+    pub body: Vec<Instruction>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BinaryKind {
+    Add,
+    Sub,
+    Mul,
+    /// Bitwise and. Integer operands only.
+    And,
+    /// Bitwise or. Integer operands only.
+    Or,
+    /// Bitwise exclusive or. Integer operands only.
+    Xor,
+}
+
+/// Computes `lhs op rhs` and binds it to a new variable named `result`. Both operands must have
+/// the same type, which is also the type of the result.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BinaryOp {
+    pub kind: BinaryKind,
+    pub lhs: Value,
+    pub rhs: Value,
+    pub result: String,
+}
+
+/// Comparisons are ordered for doubles. Integers are compared as signed by `Lt`, `Le`, `Gt` and
+/// `Ge`, and as unsigned by `ULt`, `ULe`, `UGt` and `UGe`, which only compare integers. A 1-bit
+/// integer that is one is -1 when it is signed, so booleans should be compared as unsigned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ComparisonKind {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    ULt,
+    ULe,
+    UGt,
+    UGe,
+}
+
+impl ComparisonKind {
+    /// True if the comparison treats integers as unsigned, so it can't compare doubles.
+    #[must_use]
+    pub fn is_unsigned(self) -> bool {
+        matches!(
+            self,
+            ComparisonKind::ULt | ComparisonKind::ULe | ComparisonKind::UGt | ComparisonKind::UGe
+        )
+    }
+}
+
+/// Compares `lhs` with `rhs` and binds the outcome to a new 1-bit integer variable named `result`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Comparison {
+    pub kind: ComparisonKind,
+    pub lhs: Value,
+    pub rhs: Value,
+    pub result: String,
+}
+
 // https://github.com/microsoft/qsharp-language/blob/ageller/profile/Specifications/QIR/Base-Profile.md
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Instruction {
     Cx(Controlled),
     Cz(Controlled),
+    Ccx(DoublyControlled),
+    Ccz(DoublyControlled),
+    Swap(Swapped),
+    ControlledGate(ControlledGate),
     H(Single),
     S(Single),
     SAdj(Single),
@@ -121,45 +433,96 @@ pub enum Instruction {
     Rz(Rotated),
     Reset(Single),
     M(Measured),
+    Measure(Measure),
     Call(Call),
     If(If),
+    Switch(Switch),
+    For(For),
+    BinaryOp(BinaryOp),
+    Comparison(Comparison),
+    Spanned(Spanned),
+}
+
+/// A location in the source code that built an instruction. Lines and columns start at 1. The
+/// column is `None` if it is not known.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceSpan {
+    pub file: String,
+    pub line: u32,
+    pub column: Option<u32>,
+}
+
+/// An instruction annotated with the source location that built it, which is emitted as debug
+/// metadata.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Spanned {
+    pub span: SourceSpan,
+    pub inst: Box<Instruction>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Call {
     pub name: String,
     pub args: Vec<Value>,
+    /// The name of a new variable to bind the return value to, if any. The function must not
+    /// return void if this is set.
+    pub result: Option<String>,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ValueType {
-    Integer { width: u32 },
+    Integer {
+        width: u32,
+    },
     Double,
     Qubit,
     Result,
+
+    /// A pointer to a null-terminated string of bytes, `i8*`.
+    String,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ReturnType {
     Void,
     Value(ValueType),
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FunctionType {
     pub param_types: Vec<ValueType>,
     pub return_type: ReturnType,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
     Integer(IntegerValue),
     Double(f64),
-    Qubit(String),
-    Result(String),
+    Qubit(Reference),
+    Result(Reference),
+
+    /// A string constant, such as a message or a label, which is passed as a pointer to its
+    /// null-terminated bytes. It can't contain a null character.
+    String(String),
+
+    /// A variable defined by an earlier instruction in an enclosing scope. Variables are immutable
+    /// and only visible in the block that defines them and the blocks nested inside it.
+    Variable(String),
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "IntegerValueFields")
+)]
 pub struct IntegerValue {
     width: u32,
     value: u64,
@@ -187,7 +550,164 @@ impl IntegerValue {
     }
 }
 
+/// The fields of a serialized `IntegerValue`, which are checked the same way as by
+/// `IntegerValue::new` when it is deserialized.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct IntegerValueFields {
+    width: u32,
+    value: u64,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<IntegerValueFields> for IntegerValue {
+    type Error = String;
+
+    fn try_from(fields: IntegerValueFields) -> Result<Self, Self::Error> {
+        IntegerValue::new(fields.width, fields.value)
+            .ok_or_else(|| format!("Value too big for {}-bit integer.", fields.width))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Parameter {
+    pub name: String,
+    pub ty: ValueType,
+}
+
+impl Parameter {
+    #[must_use]
+    pub fn new(name: String, ty: ValueType) -> Self {
+        Parameter { name, ty }
+    }
+}
+
+/// A function defined by the model. It is emitted as an internal function that returns void, and
+/// is invoked with a `Call` like an external function.
+///
+/// The body can only refer to the subroutine's own parameters, not to the qubits and registers of
+/// the model. Qubit and result parameters are referred to by name the same way as qubits and
+/// results in the entry point; all parameters can also be referred to as variables.
+///
+/// A statically allocated result parameter can be measured into. A dynamically allocated result is
+/// passed by value, so measuring into a result parameter is an error.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Subroutine {
+    pub name: String,
+    pub params: Vec<Parameter>,
+    pub instructions: Vec<Instruction>,
+}
+
+impl Subroutine {
+    #[must_use]
+    pub fn function_type(&self) -> FunctionType {
+        FunctionType {
+            param_types: self.params.iter().map(|p| p.ty).collect(),
+            return_type: ReturnType::Void,
+        }
+    }
+}
+
+/// An additional entry point with its own qubits and registers. It is emitted as a function with
+/// the `EntryPoint` attribute, alongside the entry point built from the top-level fields of the
+/// model. Like `SemanticModel::params`, its parameters must be doubles.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EntryPoint {
+    pub name: String,
+    pub params: Vec<Parameter>,
+    pub registers: Vec<ClassicalRegister>,
+    pub qubits: Vec<QuantumRegister>,
+    pub instructions: Vec<Instruction>,
+}
+
+impl EntryPoint {
+    #[must_use]
+    pub fn new(name: String) -> Self {
+        EntryPoint {
+            name,
+            ..EntryPoint::default()
+        }
+    }
+}
+
+/// A metadata node of strings in the module-level named metadata `name`, written as
+/// `!name = !{!0}` with `!0 = !{!"value", ...}`. Nodes with the same name are all added to the same
+/// named metadata.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NamedMetadata {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+/// The QIR profile that a generated module targets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Profile {
+    /// Straight-line programs of quantum instructions, without branching or classical computation.
+    /// Qubits and results are statically allocated, so only `M` is allowed for measurement. The
+    /// array runtime is not available, so `ControlledGate`, whose `__ctl` functions take their
+    /// controls as a `__quantum__rt__array_*` array, is not allowed, and neither are calls to the
+    /// array or tuple runtime functions. Output is recorded with
+    /// `__quantum__rt__result_record_output` alone, without the array and tuple records that group
+    /// results by register.
+    Base,
+
+    /// Programs that can also branch on measurement results and compute with classical values.
+    Adaptive,
+}
+
+impl Profile {
+    /// Whether the profile allows the instruction, not counting any instructions nested in it.
+    #[must_use]
+    pub fn allows(self, inst: &Instruction) -> bool {
+        match self {
+            Profile::Base => match inst {
+                Instruction::Measure(_)
+                | Instruction::ControlledGate(_)
+                | Instruction::If(_)
+                | Instruction::Switch(_)
+                | Instruction::For(_)
+                | Instruction::BinaryOp(_)
+                | Instruction::Comparison(_) => false,
+                Instruction::Call(call) => !is_aggregate_runtime(&call.name),
+                _ => true,
+            },
+            Profile::Adaptive => true,
+        }
+    }
+
+    /// Whether recorded output groups the results of each register with an array record, and the
+    /// registers with a tuple record if there is more than one.
+    #[must_use]
+    pub fn allows_grouped_output(self) -> bool {
+        match self {
+            Profile::Base => false,
+            Profile::Adaptive => true,
+        }
+    }
+
+    /// Whether the profile allows qubits or results to be dynamically allocated.
+    #[must_use]
+    pub fn allows_dynamic_alloc(self) -> bool {
+        match self {
+            Profile::Base => false,
+            Profile::Adaptive => true,
+        }
+    }
+}
+
+/// Whether the function is one of the array or tuple functions of the runtime, including their
+/// output recording functions.
+fn is_aggregate_runtime(name: &str) -> bool {
+    name.starts_with("__quantum__rt__array_") || name.starts_with("__quantum__rt__tuple_")
+}
+
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SemanticModel {
     pub name: String,
     pub registers: Vec<ClassicalRegister>,
@@ -195,7 +715,55 @@ pub struct SemanticModel {
     pub instructions: Vec<Instruction>,
     pub use_static_qubit_alloc: bool,
     pub use_static_result_alloc: bool,
+
+    /// Whether dynamically allocated qubits are allocated and released as one array per register,
+    /// rather than one at a time. Has no effect if `use_static_qubit_alloc` is set.
+    pub use_array_qubit_alloc: bool,
+
+    /// Whether entry points end by recording the results in their registers with the output
+    /// recording functions of the runtime, so that the output schema is explicit in the program.
+    /// Under the base profile, only the result records are written.
+    pub record_output: bool,
+
+    /// The profile that the module targets. If set, the module has the QIR version and resource
+    /// management module flags, entry points have the `qir_profiles` attribute, and instructions
+    /// or dynamic allocation that the profile does not allow are rejected.
+    pub profile: Option<Profile>,
+
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::schema::serialize_sorted")
+    )]
     pub external_functions: HashMap<String, FunctionType>,
+    pub subroutines: Vec<Subroutine>,
+    pub entry_points: Vec<EntryPoint>,
+
+    /// The parameters of the `main` entry point, which can be referred to as variables by the
+    /// top-level instructions. Entry point parameters must be doubles, so that the values can be
+    /// supplied when the program is run, such as the angles of a parametric circuit.
+    pub params: Vec<Parameter>,
+
+    /// The name of the function for the `main` entry point, which runs the top-level instructions.
+    pub entry_point_name: String,
+
+    /// Additional string attributes of every entry point, as keys and values, such as
+    /// `output_labeling_schema` or attributes for a specific target. An attribute with an empty
+    /// value is written with only its key.
+    pub entry_point_attributes: Vec<(String, String)>,
+
+    /// Module-level named metadata.
+    pub metadata: Vec<NamedMetadata>,
+
+    /// The most top-level instructions that a basic block of a function body holds. If set, longer
+    /// bodies are split into a chain of basic blocks, which keeps very large circuits manageable
+    /// for LLVM passes that work on one block at a time. A size of zero does not split bodies.
+    pub max_block_size: Option<usize>,
+
+    /// Whether each entry point gets an `InteropFriendly` wrapper named `{entry point}__Interop`,
+    /// which can be called through the C ABI. The wrapper takes the parameters of the entry point
+    /// followed by an `i8*` buffer, calls the entry point, and writes the final value of each result
+    /// into the buffer as one byte, in register order. Wrappers require static result allocation.
+    pub interop_wrappers: bool,
 }
 
 impl SemanticModel {
@@ -208,7 +776,18 @@ impl SemanticModel {
             instructions: vec![],
             use_static_qubit_alloc: false,
             use_static_result_alloc: true,
+            use_array_qubit_alloc: false,
+            record_output: false,
+            profile: None,
             external_functions: HashMap::new(),
+            subroutines: vec![],
+            entry_points: vec![],
+            params: vec![],
+            entry_point_name: "main".to_string(),
+            entry_point_attributes: vec![],
+            metadata: vec![],
+            max_block_size: None,
+            interop_wrappers: false,
         }
     }
 
@@ -222,4 +801,14 @@ impl SemanticModel {
     pub fn add_inst(&mut self, inst: Instruction) {
         self.instructions.push(inst);
     }
+
+    /// Checks the model for semantic problems that would otherwise only be caught during emission
+    /// or by LLVM verification.
+    ///
+    /// # Errors
+    ///
+    /// Returns every problem found in the model, in the order it was found.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        validation::validate(self)
+    }
 }
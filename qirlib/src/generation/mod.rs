@@ -6,8 +6,11 @@ use inkwell::{context::Context, memory_buffer::MemoryBuffer};
 use crate::module;
 
 pub mod emit;
+pub mod error;
 pub mod interop;
 pub mod qir;
+mod scope;
+mod validation;
 
 /// # Errors
 ///
@@ -62,7 +65,6 @@ pub fn bitcode_to_ir(
 
 #[cfg(test)]
 mod module_conversion_tests {
-    use std::collections::HashMap;
 
     use crate::generation::emit;
 
@@ -78,7 +80,6 @@ mod module_conversion_tests {
         use_static_result_alloc: bool,
     ) -> SemanticModel {
         SemanticModel {
-            name,
             registers: vec![ClassicalRegister::new("r".to_string(), 1)],
             qubits: vec![QuantumRegister::new("q".to_string(), 0)],
             instructions: vec![Instruction::M(Measured::new(
@@ -87,14 +88,14 @@ mod module_conversion_tests {
             ))],
             use_static_qubit_alloc,
             use_static_result_alloc,
-            external_functions: HashMap::new(),
+            ..SemanticModel::new(name)
         }
     }
 
     #[test]
     fn ir_round_trip_is_identical() -> Result<(), String> {
         let model = get_model("test".to_owned(), false, false);
-        let actual_ir: String = emit::ir(&model)?;
+        let actual_ir: String = emit::ir(&model).map_err(|e| e.to_string())?;
         let bitcode = ir_to_bitcode(actual_ir.as_str(), &None, &None)?;
         let converted_ir = bitcode_to_ir(
             bitcode.as_slice(),
@@ -108,7 +109,7 @@ mod module_conversion_tests {
     #[test]
     fn module_name_is_normalized() -> Result<(), String> {
         let model = get_model("tests".to_owned(), false, false);
-        let actual_ir: String = emit::ir(&model)?;
+        let actual_ir: String = emit::ir(&model).map_err(|e| e.to_string())?;
         let bitcode = ir_to_bitcode(actual_ir.as_str(), &None, &None)?;
         let converted_ir = bitcode_to_ir(
             bitcode.as_slice(),
@@ -4,220 +4,429 @@
 use crate::{
     codegen::CodeGenerator,
     generation::{
-        interop::{Call, If, Instruction, Value},
-        qir::result,
+        error::GenerationError,
+        interop::{
+            BinaryKind, BinaryOp, Call, Comparison, ComparisonKind, ControlledGate, Gate,
+            Instruction, Pauli, Reference,
+        },
+        qir::{
+            resolved::{self, Condition, For, If, Intrinsic, Operand, Rotation, Switch, Value},
+            result,
+        },
+        scope::{Scope, Unresolved},
     },
 };
-use inkwell::values::{BasicValueEnum, FunctionValue, PointerValue};
-use std::collections::HashMap;
+use inkwell::{
+    basic_block::BasicBlock,
+    values::{BasicMetadataValueEnum, BasicValueEnum, FunctionValue, IntValue, PointerValue},
+    FloatPredicate, IntPredicate,
+};
+use std::{collections::HashMap, slice};
 
-/// # Panics
+/// # Errors
 ///
-/// Panics if the qubit name doesn't exist
+/// - Returns `UnknownQubit` if the qubit doesn't exist.
+/// - Returns `AmbiguousReference` if the qubit name matches more than one register element.
 fn get_qubit<'ctx>(
-    qubits: &HashMap<String, BasicValueEnum<'ctx>>,
+    qubits: &Scope<BasicValueEnum<'ctx>>,
+    qubit: &Operand,
+) -> Result<BasicValueEnum<'ctx>, GenerationError> {
+    match qubit.position {
+        Ok(position) => Ok(*qubits.at(position)),
+        Err(Unresolved::Unknown) => Err(GenerationError::UnknownQubit(qubit.reference.to_string())),
+        Err(Unresolved::Ambiguous) => Err(GenerationError::AmbiguousReference(
+            qubit.reference.to_string(),
+        )),
+    }
+}
+
+/// # Errors
+///
+/// Returns an error if any of the qubits doesn't exist.
+fn get_qubits<'ctx>(
+    qubits: &Scope<BasicValueEnum<'ctx>>,
+    operands: &[Operand],
+) -> Result<Vec<BasicValueEnum<'ctx>>, GenerationError> {
+    operands
+        .iter()
+        .map(|qubit| get_qubit(qubits, qubit))
+        .collect()
+}
+
+/// # Errors
+///
+/// Returns `UnknownVariable` if the variable is not in scope.
+fn get_variable<'ctx>(
+    variables: &HashMap<String, BasicValueEnum<'ctx>>,
     name: &str,
-) -> BasicValueEnum<'ctx> {
-    // TODO: Panicking can be unfriendly to Python clients.
-    // See: https://github.com/qir-alliance/pyqir/issues/31
-    *qubits
+) -> Result<BasicValueEnum<'ctx>, GenerationError> {
+    variables
         .get(name)
-        .unwrap_or_else(|| panic!("Qubit {} not found.", name))
+        .copied()
+        .ok_or_else(|| GenerationError::UnknownVariable(name.to_owned()))
+}
+
+/// # Errors
+///
+/// - Returns `UnknownResult` if the result has not been declared.
+/// - Returns `AmbiguousReference` if the result name matches more than one register element.
+fn result_position(result: &Operand) -> Result<usize, GenerationError> {
+    result.position.map_err(|e| match e {
+        Unresolved::Unknown => GenerationError::UnknownResult(result.reference.to_string()),
+        Unresolved::Ambiguous => GenerationError::AmbiguousReference(result.reference.to_string()),
+    })
 }
 
-/// Gets the most recent value of a result name. Defaults to zero if the result has been declared
-/// but not yet measured.
+/// Gets the most recent value of a result. Defaults to zero if the result has been declared but
+/// not yet measured.
 ///
-/// # Panics
+/// # Errors
 ///
-/// Panics if the result name has not been declared.
+/// - Returns `UnknownResult` if the result has not been declared.
+/// - Returns `AmbiguousReference` if the result name matches more than one register element.
+/// - Returns `UninitializedResult` if static result allocation is used and the result was never
+///   initialized.
 fn get_result<'ctx>(
     generator: &CodeGenerator<'ctx>,
-    results: &HashMap<String, Option<PointerValue<'ctx>>>,
-    name: &str,
-) -> PointerValue<'ctx> {
-    // TODO: Panicking can be unfriendly to Python clients.
-    // See: https://github.com/qir-alliance/pyqir/issues/31
-    if generator.use_static_result_alloc {
-        // error if the key isn't found
-        // error if static result wasn't initialized
-        results
-            .get(name)
-            .unwrap_or_else(|| panic!("Result {} not found.", name))
-            .unwrap_or_else(|| panic!("Result {} not initialized.", name))
-    } else {
-        // error if the key isn't found
+    results: &Scope<Option<PointerValue<'ctx>>>,
+    result: &Operand,
+) -> Result<PointerValue<'ctx>, GenerationError> {
+    let position = result_position(result)?;
+    result_value(generator, *results.at(position), result.reference)
+}
+
+/// # Errors
+///
+/// Returns `UninitializedResult` if static result allocation is used and the result was never
+/// initialized.
+fn result_value<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    value: Option<PointerValue<'ctx>>,
+    result: &Reference,
+) -> Result<PointerValue<'ctx>, GenerationError> {
+    match value {
+        Some(value) => Ok(value),
+        None if generator.use_static_result_alloc => {
+            Err(GenerationError::UninitializedResult(result.to_string()))
+        }
         // return 0 if result is accessed prior to read.
-        results
-            .get(name)
-            .unwrap_or_else(|| panic!("Result {} not found.", name))
-            .unwrap_or_else(|| result::get_zero(generator))
+        None => Ok(result::get_zero(generator)),
+    }
+}
+
+/// # Errors
+///
+/// Returns an error if the value refers to a qubit, result or variable that doesn't exist.
+fn get_value<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    qubits: &Scope<BasicValueEnum<'ctx>>,
+    results: &Scope<Option<PointerValue<'ctx>>>,
+    variables: &HashMap<String, BasicValueEnum<'ctx>>,
+    value: &Value,
+) -> Result<BasicValueEnum<'ctx>, GenerationError> {
+    match value {
+        Value::Integer(value) => Ok(generator
+            .context
+            .custom_width_int_type(value.width())
+            .const_int(value.value(), false)
+            .into()),
+        Value::Double(value) => Ok(generator.context.f64_type().const_float(*value).into()),
+        Value::Qubit(qubit) => get_qubit(qubits, qubit),
+        Value::Result(result) => Ok(get_result(generator, results, result)?.into()),
+        Value::String(value) if value.contains('\0') => {
+            Err(GenerationError::NulInString((*value).to_owned()))
+        }
+        Value::String(value) => Ok(generator.global_string(value).into()),
+        Value::Variable(name) => get_variable(variables, name),
     }
 }
 
 fn measure<'ctx>(
     generator: &CodeGenerator<'ctx>,
-    qubit: &str,
-    target: &str,
-    qubits: &HashMap<String, BasicValueEnum<'ctx>>,
-    results: &mut HashMap<String, Option<PointerValue<'ctx>>>,
-) {
+    qubit: &Operand,
+    target: &Operand,
+    qubits: &Scope<BasicValueEnum<'ctx>>,
+    results: &mut Scope<Option<PointerValue<'ctx>>>,
+) -> Result<(), GenerationError> {
     if generator.use_static_result_alloc {
         // measure the qubit and save the result to a temporary value
         generator.emit_void_call(
             generator.qis_mz_body(),
             &[
-                get_qubit(qubits, qubit).into(),
-                get_result(generator, results, target).into(),
+                get_qubit(qubits, qubit)?.into(),
+                get_result(generator, results, target)?.into(),
             ],
         );
     } else {
         // measure the qubit and save the result to a temporary value
+        let qubit = get_qubit(qubits, qubit)?;
+        let position = result_position(target)?;
         let new_value = generator.emit_call_with_return(
             generator.qis_m_body(),
-            &[get_qubit(qubits, qubit).into()],
-            target,
+            &[qubit.into()],
+            &target.reference.flat_name(),
         );
-        results.insert(target.to_owned(), Some(new_value.into_pointer_value()));
+        *results.at_mut(position) = Some(new_value.into_pointer_value());
     }
+
+    Ok(())
 }
 
-fn controlled<'ctx>(
+fn intrinsic_function<'ctx>(
     generator: &CodeGenerator<'ctx>,
-    intrinsic: FunctionValue<'ctx>,
-    control: BasicValueEnum<'ctx>,
-    qubit: BasicValueEnum<'ctx>,
-) {
-    generator.emit_void_call(intrinsic, &[control.into(), qubit.into()]);
+    intrinsic: Intrinsic,
+) -> FunctionValue<'ctx> {
+    match intrinsic {
+        Intrinsic::Cx => generator.qis_cnot_body(),
+        Intrinsic::Cz => generator.qis_cz_body(),
+        Intrinsic::Ccx => generator.qis_ccx_body(),
+        Intrinsic::Ccz => generator.qis_ccz_body(),
+        Intrinsic::Swap => generator.qis_swap_body(),
+        Intrinsic::H => generator.qis_h_body(),
+        Intrinsic::Reset => generator.qis_reset_body(),
+        Intrinsic::S => generator.qis_s_body(),
+        Intrinsic::SAdj => generator.qis_s_adj(),
+        Intrinsic::T => generator.qis_t_body(),
+        Intrinsic::TAdj => generator.qis_t_adj(),
+        Intrinsic::X => generator.qis_x_body(),
+        Intrinsic::Y => generator.qis_y_body(),
+        Intrinsic::Z => generator.qis_z_body(),
+    }
 }
 
 pub(crate) fn emit<'ctx>(
     generator: &CodeGenerator<'ctx>,
-    inst: &Instruction,
-    qubits: &HashMap<String, BasicValueEnum<'ctx>>,
-    results: &mut HashMap<String, Option<PointerValue<'ctx>>>,
-    entry_point: FunctionValue,
-) {
-    let get_qubit = |name| get_qubit(qubits, name);
-
+    inst: &resolved::Instruction,
+    qubits: &Scope<BasicValueEnum<'ctx>>,
+    results: &mut Scope<Option<PointerValue<'ctx>>>,
+    variables: &mut HashMap<String, BasicValueEnum<'ctx>>,
+    entry_point: FunctionValue<'ctx>,
+) -> Result<(), GenerationError> {
     match inst {
-        Instruction::Cx(inst) => {
-            let control = get_qubit(&inst.control);
-            let qubit = get_qubit(&inst.target);
-            controlled(generator, generator.qis_cnot_body(), control, qubit);
+        resolved::Instruction::Intrinsic {
+            intrinsic,
+            qubits: operands,
+        } => {
+            let args: Vec<BasicMetadataValueEnum> = get_qubits(qubits, operands)?
+                .into_iter()
+                .map(Into::into)
+                .collect();
+            generator.emit_void_call(intrinsic_function(generator, *intrinsic), &args);
         }
-        Instruction::Cz(inst) => {
-            let control = get_qubit(&inst.control);
-            let qubit = get_qubit(&inst.target);
-            controlled(generator, generator.qis_cz_body(), control, qubit);
+        resolved::Instruction::Rotation {
+            rotation,
+            theta,
+            qubit,
+        } => {
+            let theta = get_value(generator, qubits, results, variables, theta)?;
+            let qubit = get_qubit(qubits, qubit)?;
+            let function = match rotation {
+                Rotation::Rx => generator.qis_rx_body(),
+                Rotation::Ry => generator.qis_ry_body(),
+                Rotation::Rz => generator.qis_rz_body(),
+            };
+            generator.emit_void_call(function, &[theta.into(), qubit.into()]);
         }
-        Instruction::H(inst) => {
-            generator.emit_void_call(generator.qis_h_body(), &[get_qubit(&inst.qubit).into()]);
-        }
-        Instruction::M(inst) => {
-            measure(generator, &inst.qubit, &inst.target, qubits, results);
-        }
-        Instruction::Reset(inst) => {
-            generator.emit_void_call(generator.qis_reset_body(), &[get_qubit(&inst.qubit).into()]);
-        }
-        Instruction::Rx(inst) => {
-            generator.emit_void_call(
-                generator.qis_rx_body(),
-                &[
-                    generator.f64_to_f64(inst.theta),
-                    get_qubit(&inst.qubit).into(),
-                ],
-            );
-        }
-        Instruction::Ry(inst) => {
-            generator.emit_void_call(
-                generator.qis_ry_body(),
-                &[
-                    generator.f64_to_f64(inst.theta),
-                    get_qubit(&inst.qubit).into(),
-                ],
-            );
+        resolved::Instruction::ControlledGate {
+            gate,
+            controls,
+            target,
+            theta,
+        } => {
+            let controls = get_qubits(qubits, controls)?;
+            let target = get_qubit(qubits, target)?;
+            let theta = theta
+                .as_ref()
+                .map(|theta| get_value(generator, qubits, results, variables, theta))
+                .transpose()?;
+            emit_controlled_gate(generator, gate, &controls, target, theta);
         }
-        Instruction::Rz(inst) => {
-            generator.emit_void_call(
-                generator.qis_rz_body(),
-                &[
-                    generator.f64_to_f64(inst.theta),
-                    get_qubit(&inst.qubit).into(),
-                ],
-            );
+        resolved::Instruction::M { qubit, target } => {
+            measure(generator, qubit, target, qubits, results)?;
         }
-        Instruction::S(inst) => {
-            generator.emit_void_call(generator.qis_s_body(), &[get_qubit(&inst.qubit).into()]);
+        resolved::Instruction::Measure {
+            bases,
+            qubits: operands,
+            target,
+        } => emit_measure(generator, qubits, results, bases, operands, target)?,
+        resolved::Instruction::Call { call, args } => {
+            emit_call(generator, qubits, results, variables, call, args)?;
         }
-        Instruction::SAdj(inst) => {
-            generator.emit_void_call(generator.qis_s_adj(), &[get_qubit(&inst.qubit).into()]);
+        resolved::Instruction::If(if_) => {
+            emit_if(generator, qubits, results, variables, entry_point, if_)?;
         }
-        Instruction::T(inst) => {
-            generator.emit_void_call(generator.qis_t_body(), &[get_qubit(&inst.qubit).into()]);
+        resolved::Instruction::Switch(switch) => {
+            emit_switch(generator, qubits, results, variables, entry_point, switch)?;
         }
-        Instruction::TAdj(inst) => {
-            generator.emit_void_call(generator.qis_t_adj(), &[get_qubit(&inst.qubit).into()]);
+        resolved::Instruction::For(for_) => {
+            emit_for(generator, qubits, results, variables, entry_point, for_)?;
         }
-        Instruction::X(inst) => {
-            generator.emit_void_call(generator.qis_x_body(), &[get_qubit(&inst.qubit).into()]);
+        resolved::Instruction::BinaryOp { op, lhs, rhs } => {
+            let lhs = get_value(generator, qubits, results, variables, lhs)?;
+            let rhs = get_value(generator, qubits, results, variables, rhs)?;
+            let value = emit_binary_op(generator, op, lhs, rhs)?;
+            variables.insert(op.result.clone(), value);
         }
-        Instruction::Y(inst) => {
-            generator.emit_void_call(generator.qis_y_body(), &[get_qubit(&inst.qubit).into()]);
+        resolved::Instruction::Comparison {
+            comparison,
+            lhs,
+            rhs,
+        } => {
+            let lhs = get_value(generator, qubits, results, variables, lhs)?;
+            let rhs = get_value(generator, qubits, results, variables, rhs)?;
+            let value = emit_comparison(generator, comparison, lhs, rhs)?;
+            variables.insert(comparison.result.clone(), value.into());
         }
-        Instruction::Z(inst) => {
-            generator.emit_void_call(generator.qis_z_body(), &[get_qubit(&inst.qubit).into()]);
+        resolved::Instruction::Spanned { span, inst } => {
+            let previous = generator.builder.get_current_debug_location();
+            // Debug locations use column 0 when the column is unknown.
+            let column = span.column.unwrap_or(0);
+            generator.set_debug_location(entry_point, &span.file, span.line, column);
+            let result = emit(generator, inst, qubits, results, variables, entry_point);
+            match previous {
+                Some(location) => generator
+                    .builder
+                    .set_current_debug_location(generator.context, location),
+                None => generator.builder.unset_current_debug_location(),
+            }
+            result?;
         }
-        Instruction::Call(call) => emit_call(generator, qubits, results, call),
-        Instruction::If(if_) => emit_if(generator, qubits, results, entry_point, if_),
     }
+
+    Ok(())
 }
 
 fn emit_call<'ctx>(
     generator: &CodeGenerator<'ctx>,
-    qubits: &HashMap<String, BasicValueEnum<'ctx>>,
-    results: &HashMap<String, Option<PointerValue<'ctx>>>,
+    qubits: &Scope<BasicValueEnum<'ctx>>,
+    results: &Scope<Option<PointerValue<'ctx>>>,
+    variables: &mut HashMap<String, BasicValueEnum<'ctx>>,
     call: &Call,
-) {
-    let args: Vec<_> = call
-        .args
+    args: &[Value],
+) -> Result<(), GenerationError> {
+    let args = args
         .iter()
-        .map(|value| match value {
-            Value::Integer(value) => generator
-                .context
-                .custom_width_int_type(value.width())
-                .const_int(value.value(), false)
-                .into(),
-            Value::Double(value) => generator.f64_to_f64(*value),
-            Value::Qubit(name) => get_qubit(qubits, name).into(),
-            Value::Result(name) => get_result(generator, results, name).into(),
-        })
-        .collect();
+        .map(|value| Ok(get_value(generator, qubits, results, variables, value)?.into()))
+        .collect::<Result<Vec<_>, _>>()?;
 
-    // TODO: Panicking can be unfriendly to Python clients.
-    // See: https://github.com/qir-alliance/pyqir/issues/31
     let function = generator
         .module
         .get_function(&call.name)
-        .unwrap_or_else(|| panic!("Function {} not found.", &call.name));
+        .ok_or_else(|| GenerationError::UnknownFunction(call.name.clone()))?;
 
-    generator.emit_void_call(function, args.as_slice());
+    if let Some(result) = &call.result {
+        if function.get_type().get_return_type().is_none() {
+            return Err(GenerationError::VoidReturn(call.name.clone()));
+        }
+
+        let value = generator.emit_call_with_return(function, args.as_slice(), result);
+        variables.insert(result.clone(), value);
+    } else {
+        generator.builder.build_call(function, args.as_slice(), "");
+    }
+
+    Ok(())
 }
 
-fn emit_if<'ctx>(
+fn emit_controlled_gate<'ctx>(
     generator: &CodeGenerator<'ctx>,
-    qubits: &HashMap<String, BasicValueEnum<'ctx>>,
-    results: &mut HashMap<String, Option<PointerValue<'ctx>>>,
-    entry_point: FunctionValue,
-    if_: &If,
+    inst: &ControlledGate,
+    controls: &[BasicValueEnum<'ctx>],
+    target: BasicValueEnum<'ctx>,
+    theta: Option<BasicValueEnum<'ctx>>,
 ) {
-    let result = get_result(generator, results, &if_.condition);
-
-    let condition = if generator.use_static_result_alloc {
-        result::read_result(generator, result)
-    } else {
-        result::equal(generator, result, result::get_one(generator))
+    let name = match &inst.gate {
+        Gate::H => "h",
+        Gate::S => "s",
+        Gate::T => "t",
+        Gate::X => "x",
+        Gate::Y => "y",
+        Gate::Z => "z",
+        Gate::Rx(_) => "rx",
+        Gate::Ry(_) => "ry",
+        Gate::Rz(_) => "rz",
     };
+    let adjoint = inst.adjoint && !matches!(inst.gate, Gate::H | Gate::X | Gate::Y | Gate::Z);
+
+    let array = generator.emit_qubit_array(controls, "controls");
+    match theta {
+        None => {
+            let function = if adjoint {
+                generator.qis_ctladj(name)
+            } else {
+                generator.qis_ctl(name)
+            };
+            generator.emit_void_call(function, &[array.into(), target.into()]);
+        }
+        Some(theta) => {
+            let function = if adjoint {
+                generator.qis_rotated_ctladj(name)
+            } else {
+                generator.qis_rotated_ctl(name)
+            };
+            generator.emit_void_call(function, &[array.into(), theta.into(), target.into()]);
+        }
+    }
+    generator.emit_release_array(array);
+}
+
+/// # Errors
+///
+/// - Returns `StaticMeasure` if static result allocation is used, because the measurement returns
+///   a new result.
+/// - Returns an error if a qubit or the target result doesn't exist.
+fn emit_measure<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    qubits: &Scope<BasicValueEnum<'ctx>>,
+    results: &mut Scope<Option<PointerValue<'ctx>>>,
+    bases: &[Pauli],
+    operands: &[Operand],
+    target: &Operand,
+) -> Result<(), GenerationError> {
+    if generator.use_static_result_alloc {
+        return Err(GenerationError::StaticMeasure(target.reference.to_string()));
+    }
+
+    let qubits = get_qubits(qubits, operands)?;
+    let position = result_position(target)?;
+
+    let pauli_type = generator.pauli_type();
+    let bases: Vec<_> = bases
+        .iter()
+        .map(|basis| {
+            let value = match basis {
+                Pauli::I => 0,
+                Pauli::X => 1,
+                Pauli::Z => 2,
+                Pauli::Y => 3,
+            };
+            pauli_type.const_int(value, false)
+        })
+        .collect();
+
+    let bases = generator.emit_pauli_array(&bases, "bases");
+    let qubits = generator.emit_qubit_array(&qubits, "qubits");
+    let new_value = generator.emit_call_with_return(
+        generator.qis_measure_body(),
+        &[bases.into(), qubits.into()],
+        &target.reference.flat_name(),
+    );
+    generator.emit_release_array(bases);
+    generator.emit_release_array(qubits);
+
+    *results.at_mut(position) = Some(new_value.into_pointer_value());
+    Ok(())
+}
+
+fn emit_if<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    qubits: &Scope<BasicValueEnum<'ctx>>,
+    results: &mut Scope<Option<PointerValue<'ctx>>>,
+    variables: &HashMap<String, BasicValueEnum<'ctx>>,
+    entry_point: FunctionValue<'ctx>,
+    if_: &If,
+) -> Result<(), GenerationError> {
+    let condition = emit_condition(generator, results, variables, &if_.condition)?;
 
     let then_block = generator.context.append_basic_block(entry_point, "then");
     let else_block = generator.context.append_basic_block(entry_point, "else");
@@ -230,17 +439,453 @@ fn emit_if<'ctx>(
         .context
         .append_basic_block(entry_point, "continue");
 
-    let mut emit_block = |block, insts| {
-        generator.builder.position_at_end(block);
+    emit_branches(
+        generator,
+        qubits,
+        results,
+        variables,
+        entry_point,
+        &[
+            (then_block, if_.then_insts.as_slice()),
+            (else_block, if_.else_insts.as_slice()),
+        ],
+        continue_block,
+    )
+}
+
+fn emit_switch<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    qubits: &Scope<BasicValueEnum<'ctx>>,
+    results: &mut Scope<Option<PointerValue<'ctx>>>,
+    variables: &HashMap<String, BasicValueEnum<'ctx>>,
+    entry_point: FunctionValue<'ctx>,
+    switch: &Switch,
+) -> Result<(), GenerationError> {
+    if switch.results.len() > 64 {
+        return Err(GenerationError::SwitchTooWide(switch.results.len()));
+    }
+
+    let int64_type = generator.int64_type();
+    let mut value = None;
+    for (index, result) in switch.results.iter().enumerate() {
+        let bit = result::is_one(generator, get_result(generator, results, result)?);
+        let mut bit = generator.builder.build_int_z_extend(bit, int64_type, "");
+        if index > 0 {
+            let shift = int64_type.const_int(index as u64, false);
+            bit = generator.builder.build_left_shift(bit, shift, "");
+        }
+
+        value = Some(match value {
+            None => bit,
+            Some(value) => generator.builder.build_or(value, bit, ""),
+        });
+    }
+    let value = value.unwrap_or_else(|| int64_type.const_zero());
 
+    let cases: Vec<_> = switch
+        .cases
+        .iter()
+        .map(|(case, _)| {
+            let block = generator.context.append_basic_block(entry_point, "case");
+            (int64_type.const_int(*case, false), block)
+        })
+        .collect();
+    let default_block = generator.context.append_basic_block(entry_point, "default");
+    generator.builder.build_switch(value, default_block, &cases);
+
+    let continue_block = generator
+        .context
+        .append_basic_block(entry_point, "continue");
+
+    let mut branches: Vec<_> = cases
+        .iter()
+        .zip(&switch.cases)
+        .map(|((_, block), (_, insts))| (*block, insts.as_slice()))
+        .collect();
+    branches.push((default_block, switch.default_insts.as_slice()));
+
+    emit_branches(
+        generator,
+        qubits,
+        results,
+        variables,
+        entry_point,
+        &branches,
+        continue_block,
+    )
+}
+
+/// Emits the instructions of each branch into its block, followed by a branch to `continue_block`,
+/// and positions the builder at the end of `continue_block`. Variables defined in a branch are not
+/// visible after it.
+///
+/// Every branch starts from the values that the results had before the branches. Dynamically
+/// allocated results that any branch measures have a new value in each branch, so they are merged
+/// into phi nodes in `continue_block` with the value that each branch ends with.
+fn emit_branches<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    qubits: &Scope<BasicValueEnum<'ctx>>,
+    results: &mut Scope<Option<PointerValue<'ctx>>>,
+    variables: &HashMap<String, BasicValueEnum<'ctx>>,
+    entry_point: FunctionValue<'ctx>,
+    branches: &[(BasicBlock<'ctx>, &[resolved::Instruction])],
+    continue_block: BasicBlock<'ctx>,
+) -> Result<(), GenerationError> {
+    let mut measured: Vec<(usize, &Reference)> = vec![];
+    if !generator.use_static_result_alloc {
+        for (_, insts) in branches {
+            for target in resolved::measured_results(insts) {
+                if let Ok(position) = target.position {
+                    if measured.iter().all(|&(other, _)| other != position) {
+                        measured.push((position, target.reference));
+                    }
+                }
+            }
+        }
+    }
+    let initial_values: Vec<_> = measured
+        .iter()
+        .map(|&(position, _)| *results.at(position))
+        .collect();
+
+    let mut incoming = vec![];
+    for &(block, insts) in branches {
+        for (&(position, _), &initial_value) in measured.iter().zip(&initial_values) {
+            *results.at_mut(position) = initial_value;
+        }
+
+        generator.builder.position_at_end(block);
+        let mut block_variables = variables.clone();
         for inst in insts {
-            emit(generator, inst, qubits, results, entry_point);
+            emit(
+                generator,
+                inst,
+                qubits,
+                results,
+                &mut block_variables,
+                entry_point,
+            )?;
         }
 
+        // A result that this branch doesn't measure and that was never measured before gets its
+        // zero value here, at the end of the branch, so that it is defined in the predecessor.
+        let values = measured
+            .iter()
+            .map(|&(position, result)| result_value(generator, *results.at(position), result))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The branch may have ended in a different block than it started in if it contains control
+        // flow.
+        let end_block = generator
+            .builder
+            .get_insert_block()
+            .expect("Builder is not positioned in a block.");
         generator.builder.build_unconditional_branch(continue_block);
-    };
+        incoming.push((end_block, values));
+    }
 
-    emit_block(then_block, &if_.then_insts);
-    emit_block(else_block, &if_.else_insts);
     generator.builder.position_at_end(continue_block);
+    // A result is only merged if a branch measures it, so there is at least one incoming value.
+    for (index, &(position, result)) in measured.iter().enumerate() {
+        let ty = incoming[0].1[index].get_type();
+        let phi = generator.builder.build_phi(ty, &result.flat_name());
+        for (block, values) in &incoming {
+            phi.add_incoming(&[(&values[index], *block)]);
+        }
+        *results.at_mut(position) = Some(phi.as_basic_value().into_pointer_value());
+    }
+
+    Ok(())
+}
+
+fn emit_for<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    qubits: &Scope<BasicValueEnum<'ctx>>,
+    results: &mut Scope<Option<PointerValue<'ctx>>>,
+    variables: &HashMap<String, BasicValueEnum<'ctx>>,
+    entry_point: FunctionValue<'ctx>,
+    for_: &For,
+) -> Result<(), GenerationError> {
+    let int64_type = generator.int64_type();
+    let preheader_block = generator
+        .builder
+        .get_insert_block()
+        .expect("Builder is not positioned in a block.");
+
+    let header_block = generator.context.append_basic_block(entry_point, "loop");
+    let body_block = generator.context.append_basic_block(entry_point, "body");
+    let exit_block = generator.context.append_basic_block(entry_point, "exit");
+
+    // Dynamically allocated results that the body measures change on every iteration, so their
+    // values are phi nodes in the header, which dominates both the body and the exit.
+    let mut measured: Vec<(usize, &Reference)> = vec![];
+    if !generator.use_static_result_alloc {
+        for target in resolved::measured_results(&for_.body) {
+            if let Ok(position) = target.position {
+                if measured.iter().all(|&(other, _)| other != position) {
+                    measured.push((position, target.reference));
+                }
+            }
+        }
+    }
+    let initial_values = measured
+        .iter()
+        .map(|&(position, result)| result_value(generator, *results.at(position), result))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    generator.builder.build_unconditional_branch(header_block);
+    generator.builder.position_at_end(header_block);
+
+    let index = generator.builder.build_phi(int64_type, for_.index);
+    index.add_incoming(&[(&int64_type.const_zero(), preheader_block)]);
+    let index_value = index.as_basic_value().into_int_value();
+
+    let mut phis = vec![];
+    for (&(position, result), initial_value) in measured.iter().zip(initial_values) {
+        let phi = generator
+            .builder
+            .build_phi(initial_value.get_type(), &result.flat_name());
+        phi.add_incoming(&[(&initial_value, preheader_block)]);
+        *results.at_mut(position) = Some(phi.as_basic_value().into_pointer_value());
+        phis.push(phi);
+    }
+
+    let condition = generator.builder.build_int_compare(
+        IntPredicate::ULT,
+        index_value,
+        int64_type.const_int(for_.count, false),
+        "condition",
+    );
+    generator
+        .builder
+        .build_conditional_branch(condition, body_block, exit_block);
+
+    generator.builder.position_at_end(body_block);
+    let mut body_variables = variables.clone();
+    body_variables.insert(for_.index.to_owned(), index_value.into());
+    for inst in &for_.body {
+        emit(
+            generator,
+            inst,
+            qubits,
+            results,
+            &mut body_variables,
+            entry_point,
+        )?;
+    }
+
+    // The body may have ended in a different block than it started in if it contains control flow.
+    let latch_block = generator
+        .builder
+        .get_insert_block()
+        .expect("Builder is not positioned in a block.");
+    let next = generator
+        .builder
+        .build_int_add(index_value, int64_type.const_int(1, false), "next");
+    generator.builder.build_unconditional_branch(header_block);
+    index.add_incoming(&[(&next, latch_block)]);
+
+    for (&(position, _), phi) in measured.iter().zip(phis) {
+        let value = results.at_mut(position);
+        if let Some(latest) = *value {
+            phi.add_incoming(&[(&latest, latch_block)]);
+        }
+        *value = Some(phi.as_basic_value().into_pointer_value());
+    }
+
+    generator.builder.position_at_end(exit_block);
+    Ok(())
+}
+
+/// The results that the instructions measure into, including in nested blocks.
+pub(crate) fn measured_results(instructions: &[Instruction]) -> Vec<&Reference> {
+    let mut targets = vec![];
+    for inst in instructions {
+        match inst {
+            Instruction::M(measured) => targets.push(&measured.target),
+            Instruction::Measure(measure) => targets.push(&measure.target),
+            Instruction::If(if_) => {
+                targets.extend(measured_results(&if_.then_insts));
+                targets.extend(measured_results(&if_.else_insts));
+            }
+            Instruction::Switch(switch) => {
+                for case in &switch.cases {
+                    targets.extend(measured_results(&case.insts));
+                }
+                targets.extend(measured_results(&switch.default_insts));
+            }
+            Instruction::For(for_) => targets.extend(measured_results(&for_.body)),
+            Instruction::Spanned(spanned) => {
+                targets.extend(measured_results(slice::from_ref(spanned.inst.as_ref())));
+            }
+            _ => {}
+        }
+    }
+    targets
+}
+
+fn emit_condition<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    results: &Scope<Option<PointerValue<'ctx>>>,
+    variables: &HashMap<String, BasicValueEnum<'ctx>>,
+    condition: &Condition,
+) -> Result<IntValue<'ctx>, GenerationError> {
+    match condition {
+        Condition::Result(name) => Ok(result::is_one(
+            generator,
+            get_result(generator, results, name)?,
+        )),
+        Condition::Variable(name) => match get_variable(variables, name)? {
+            BasicValueEnum::IntValue(value) if value.get_type().get_bit_width() == 1 => Ok(value),
+            BasicValueEnum::PointerValue(result) => Ok(result::is_one(generator, result)),
+            _ => Err(GenerationError::InvalidCondition((*name).to_owned())),
+        },
+        Condition::Not(condition) => {
+            let value = emit_condition(generator, results, variables, condition)?;
+            Ok(generator.builder.build_not(value, ""))
+        }
+        Condition::And(conditions) => {
+            let init = generator.bool_type().const_int(1, false);
+            fold_conditions(
+                generator,
+                results,
+                variables,
+                conditions,
+                init,
+                |lhs, rhs| generator.builder.build_and(lhs, rhs, ""),
+            )
+        }
+        Condition::Or(conditions) => {
+            let init = generator.bool_type().const_zero();
+            fold_conditions(
+                generator,
+                results,
+                variables,
+                conditions,
+                init,
+                |lhs, rhs| generator.builder.build_or(lhs, rhs, ""),
+            )
+        }
+        Condition::Xor(conditions) => {
+            let init = generator.bool_type().const_zero();
+            fold_conditions(
+                generator,
+                results,
+                variables,
+                conditions,
+                init,
+                |lhs, rhs| generator.builder.build_xor(lhs, rhs, ""),
+            )
+        }
+    }
+}
+
+/// Combines the values of the conditions with `op`, starting from `init`. Every condition is
+/// evaluated, since reading a result has no side effects.
+fn fold_conditions<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    results: &Scope<Option<PointerValue<'ctx>>>,
+    variables: &HashMap<String, BasicValueEnum<'ctx>>,
+    conditions: &[Condition],
+    init: IntValue<'ctx>,
+    op: impl Fn(IntValue<'ctx>, IntValue<'ctx>) -> IntValue<'ctx>,
+) -> Result<IntValue<'ctx>, GenerationError> {
+    let mut conditions = conditions.iter();
+    let mut value = match conditions.next() {
+        None => return Ok(init),
+        Some(condition) => emit_condition(generator, results, variables, condition)?,
+    };
+
+    for condition in conditions {
+        let rhs = emit_condition(generator, results, variables, condition)?;
+        value = op(value, rhs);
+    }
+
+    Ok(value)
+}
+
+fn emit_binary_op<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    op: &BinaryOp,
+    lhs: BasicValueEnum<'ctx>,
+    rhs: BasicValueEnum<'ctx>,
+) -> Result<BasicValueEnum<'ctx>, GenerationError> {
+    let builder = &generator.builder;
+    let name = op.result.as_str();
+
+    match (lhs, rhs) {
+        (BasicValueEnum::IntValue(lhs), BasicValueEnum::IntValue(rhs))
+            if lhs.get_type() == rhs.get_type() =>
+        {
+            Ok(match op.kind {
+                BinaryKind::Add => builder.build_int_add(lhs, rhs, name),
+                BinaryKind::Sub => builder.build_int_sub(lhs, rhs, name),
+                BinaryKind::Mul => builder.build_int_mul(lhs, rhs, name),
+                BinaryKind::And => builder.build_and(lhs, rhs, name),
+                BinaryKind::Or => builder.build_or(lhs, rhs, name),
+                BinaryKind::Xor => builder.build_xor(lhs, rhs, name),
+            }
+            .into())
+        }
+        (BasicValueEnum::FloatValue(lhs), BasicValueEnum::FloatValue(rhs)) => match op.kind {
+            BinaryKind::Add => Ok(builder.build_float_add(lhs, rhs, name).into()),
+            BinaryKind::Sub => Ok(builder.build_float_sub(lhs, rhs, name).into()),
+            BinaryKind::Mul => Ok(builder.build_float_mul(lhs, rhs, name).into()),
+            BinaryKind::And | BinaryKind::Or | BinaryKind::Xor => {
+                Err(GenerationError::InvalidOperands(op.result.clone()))
+            }
+        },
+        _ => Err(GenerationError::InvalidOperands(op.result.clone())),
+    }
+}
+
+fn emit_comparison<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    comparison: &Comparison,
+    lhs: BasicValueEnum<'ctx>,
+    rhs: BasicValueEnum<'ctx>,
+) -> Result<IntValue<'ctx>, GenerationError> {
+    let name = comparison.result.as_str();
+
+    match (lhs, rhs) {
+        (BasicValueEnum::IntValue(lhs), BasicValueEnum::IntValue(rhs))
+            if lhs.get_type() == rhs.get_type() =>
+        {
+            let predicate = match comparison.kind {
+                ComparisonKind::Eq => IntPredicate::EQ,
+                ComparisonKind::Ne => IntPredicate::NE,
+                ComparisonKind::Lt => IntPredicate::SLT,
+                ComparisonKind::Le => IntPredicate::SLE,
+                ComparisonKind::Gt => IntPredicate::SGT,
+                ComparisonKind::Ge => IntPredicate::SGE,
+                ComparisonKind::ULt => IntPredicate::ULT,
+                ComparisonKind::ULe => IntPredicate::ULE,
+                ComparisonKind::UGt => IntPredicate::UGT,
+                ComparisonKind::UGe => IntPredicate::UGE,
+            };
+            Ok(generator
+                .builder
+                .build_int_compare(predicate, lhs, rhs, name))
+        }
+        (BasicValueEnum::FloatValue(lhs), BasicValueEnum::FloatValue(rhs)) => {
+            let predicate = match comparison.kind {
+                ComparisonKind::Eq => FloatPredicate::OEQ,
+                ComparisonKind::Ne => FloatPredicate::ONE,
+                ComparisonKind::Lt => FloatPredicate::OLT,
+                ComparisonKind::Le => FloatPredicate::OLE,
+                ComparisonKind::Gt => FloatPredicate::OGT,
+                ComparisonKind::Ge => FloatPredicate::OGE,
+                ComparisonKind::ULt
+                | ComparisonKind::ULe
+                | ComparisonKind::UGt
+                | ComparisonKind::UGe => {
+                    return Err(GenerationError::InvalidOperands(comparison.result.clone()))
+                }
+            };
+            Ok(generator
+                .builder
+                .build_float_compare(predicate, lhs, rhs, name))
+        }
+        _ => Err(GenerationError::InvalidOperands(comparison.result.clone())),
+    }
 }
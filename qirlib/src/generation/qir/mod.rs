@@ -1,21 +1,80 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use inkwell::{attributes::AttributeLoc, context::Context, module::Module, values::FunctionValue};
+use inkwell::{
+    attributes::AttributeLoc,
+    context::Context,
+    module::{Linkage, Module},
+    types::{BasicMetadataTypeEnum, FunctionType},
+    values::FunctionValue,
+};
 
 pub mod instructions;
+pub(crate) mod output;
+pub(crate) mod resolved;
 mod result;
 
+/// Adds an entry point function with the given parameters. Returns `None` if the module already
+/// has a function with the name that `declare_function` can't reuse.
 pub(crate) fn create_entry_point<'ctx>(
     context: &'ctx Context,
     module: &Module<'ctx>,
-) -> FunctionValue<'ctx> {
-    let fn_type = context.void_type().fn_type(&[], false);
-    let fn_value = module.add_function("main", fn_type, None);
+    name: &str,
+    param_types: &[BasicMetadataTypeEnum<'ctx>],
+) -> Option<FunctionValue<'ctx>> {
+    create_attributed_function(context, module, name, "EntryPoint", param_types)
+}
+
+/// The name of the `InteropFriendly` wrapper of an entry point.
+pub(crate) fn interop_function_name(entry_point: &str) -> String {
+    format!("{}__Interop", entry_point)
+}
+
+/// Adds an `InteropFriendly` function with the given parameters, which callers outside of QIR can
+/// use through the C ABI. Returns `None` under the same conditions as `create_entry_point`.
+pub(crate) fn create_interop_function<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    name: &str,
+    param_types: &[BasicMetadataTypeEnum<'ctx>],
+) -> Option<FunctionValue<'ctx>> {
+    create_attributed_function(context, module, name, "InteropFriendly", param_types)
+}
 
-    let entry_point_attribute = context.create_string_attribute("EntryPoint", "");
-    fn_value.add_attribute(AttributeLoc::Function, entry_point_attribute);
-    fn_value
+fn create_attributed_function<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    name: &str,
+    attribute: &str,
+    param_types: &[BasicMetadataTypeEnum<'ctx>],
+) -> Option<FunctionValue<'ctx>> {
+    let fn_type = context.void_type().fn_type(param_types, false);
+    let fn_value = declare_function(module, name, fn_type, None)?;
+
+    let attribute = context.create_string_attribute(attribute, "");
+    fn_value.add_attribute(AttributeLoc::Function, attribute);
+    Some(fn_value)
+}
+
+/// Adds a function that is about to be defined to the module. If the module already declares the
+/// function with the same type, such as when existing code calls it, the declaration is reused.
+/// Returns `None` if the module already defines the function, or declares it with another type.
+pub(crate) fn declare_function<'ctx>(
+    module: &Module<'ctx>,
+    name: &str,
+    ty: FunctionType<'ctx>,
+    linkage: Option<Linkage>,
+) -> Option<FunctionValue<'ctx>> {
+    match module.get_function(name) {
+        None => Some(module.add_function(name, ty, linkage)),
+        Some(function) if function.count_basic_blocks() == 0 && function.get_type() == ty => {
+            if let Some(linkage) = linkage {
+                function.set_linkage(linkage);
+            }
+            Some(function)
+        }
+        Some(_) => None,
+    }
 }
 
 #[cfg(test)]
@@ -30,7 +89,8 @@ mod tests {
         let module = context.create_module("test");
         let generator = CodeGenerator::new(&context, module, false, false).unwrap();
 
-        let entry_point = create_entry_point(generator.context, &generator.module);
+        let entry_point =
+            create_entry_point(generator.context, &generator.module, "main", &[]).unwrap();
         let entry = generator.context.append_basic_block(entry_point, "entry");
         generator.builder.position_at_end(entry);
         generator.builder.build_return(None);
@@ -0,0 +1,95 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::{
+    codegen::CodeGenerator,
+    generation::{
+        interop::{ClassicalRegister, Reference},
+        qir::result,
+        scope::Scope,
+    },
+};
+use inkwell::values::{BasicMetadataValueEnum, PointerValue};
+
+/// Records the final value of every result in `registers`, in declaration order. If `grouped` is
+/// set, a single register is recorded as an array, and more than one register is recorded as a
+/// tuple of arrays.
+pub(crate) fn record_output<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    registers: &[ClassicalRegister],
+    results: &Scope<Option<PointerValue<'ctx>>>,
+    grouped: bool,
+) {
+    if grouped && registers.len() > 1 {
+        let args = [
+            generator.usize_to_i64(registers.len()),
+            generator.i8_null_ptr(),
+        ];
+        generator.emit_void_call(generator.rt_tuple_record_output(), &args);
+    }
+
+    for register in registers {
+        if grouped {
+            let args = [
+                generator.u64_to_i64(register.size),
+                label(generator, register.label.as_deref()),
+            ];
+            generator.emit_void_call(generator.rt_array_record_output(), &args);
+        }
+
+        for index in 0..register.size {
+            let reference = Reference::indexed(register.name.clone(), index);
+            let result = match results.get(&reference) {
+                Ok(Some(result)) => *result,
+                _ => result::get_zero(generator),
+            };
+            let args = [result.into(), generator.i8_null_ptr()];
+            generator.emit_void_call(generator.rt_result_record_output(), &args);
+        }
+    }
+}
+
+/// Writes the final value of every result in `registers` into `buffer`, one byte per result in
+/// declaration order. A byte is 1 if the result is one and 0 otherwise, including if the result
+/// was never measured.
+pub(crate) fn write_output<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    registers: &[ClassicalRegister],
+    results: &Scope<Option<PointerValue<'ctx>>>,
+    buffer: PointerValue<'ctx>,
+) {
+    let mut offset = 0;
+    for register in registers {
+        for index in 0..register.size {
+            let reference = Reference::indexed(register.name.clone(), index);
+            let result = match results.get(&reference) {
+                Ok(Some(result)) => *result,
+                _ => result::get_zero(generator),
+            };
+            let bit = result::is_one(generator, result);
+            let byte = generator
+                .builder
+                .build_int_z_extend(bit, generator.context.i8_type(), "");
+            // The caller provides a byte for every result.
+            let element = unsafe {
+                generator.builder.build_in_bounds_gep(
+                    buffer,
+                    &[generator.usize_to_i64(offset).into_int_value()],
+                    "",
+                )
+            };
+            generator.builder.build_store(element, byte);
+            offset += 1;
+        }
+    }
+}
+
+fn label<'ctx>(
+    generator: &CodeGenerator<'ctx>,
+    label: Option<&str>,
+) -> BasicMetadataValueEnum<'ctx> {
+    match label {
+        Some(label) => generator.global_string(label).into(),
+        None => generator.i8_null_ptr(),
+    }
+}
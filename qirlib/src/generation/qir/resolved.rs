@@ -0,0 +1,351 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::generation::{
+    interop::{
+        self, BinaryOp, Call, Comparison, ControlledGate, Gate, IntegerValue, Pauli, Reference,
+        SourceSpan,
+    },
+    scope::{Scope, Unresolved},
+};
+
+/// A qubit or result that an instruction uses, resolved to the position of its value in scope.
+#[derive(Clone, Copy)]
+pub(crate) struct Operand<'a> {
+    /// The reference as it was written, which errors are reported with.
+    pub(crate) reference: &'a Reference,
+    pub(crate) position: Result<usize, Unresolved>,
+}
+
+pub(crate) enum Value<'a> {
+    Integer(&'a IntegerValue),
+    Double(f64),
+    Qubit(Operand<'a>),
+    Result(Operand<'a>),
+    String(&'a str),
+    Variable(&'a str),
+}
+
+pub(crate) enum Condition<'a> {
+    Result(Operand<'a>),
+    Variable(&'a str),
+    Not(Box<Condition<'a>>),
+    And(Vec<Condition<'a>>),
+    Or(Vec<Condition<'a>>),
+    Xor(Vec<Condition<'a>>),
+}
+
+/// A quantum instruction whose only operands are qubits, which are passed to its intrinsic in
+/// order.
+#[derive(Clone, Copy)]
+pub(crate) enum Intrinsic {
+    Cx,
+    Cz,
+    Ccx,
+    Ccz,
+    Swap,
+    H,
+    Reset,
+    S,
+    SAdj,
+    T,
+    TAdj,
+    X,
+    Y,
+    Z,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Rotation {
+    Rx,
+    Ry,
+    Rz,
+}
+
+pub(crate) struct If<'a> {
+    pub(crate) condition: Condition<'a>,
+    pub(crate) then_insts: Vec<Instruction<'a>>,
+    pub(crate) else_insts: Vec<Instruction<'a>>,
+}
+
+pub(crate) struct Switch<'a> {
+    pub(crate) results: Vec<Operand<'a>>,
+    pub(crate) cases: Vec<(u64, Vec<Instruction<'a>>)>,
+    pub(crate) default_insts: Vec<Instruction<'a>>,
+}
+
+pub(crate) struct For<'a> {
+    pub(crate) index: &'a str,
+    pub(crate) count: u64,
+    pub(crate) body: Vec<Instruction<'a>>,
+}
+
+/// An instruction with its qubits and results resolved to positions in scope, so that emitting it
+/// only indexes into the scopes. The instructions in nested blocks are resolved too.
+pub(crate) enum Instruction<'a> {
+    Intrinsic {
+        intrinsic: Intrinsic,
+        qubits: Vec<Operand<'a>>,
+    },
+    Rotation {
+        rotation: Rotation,
+        theta: Value<'a>,
+        qubit: Operand<'a>,
+    },
+    ControlledGate {
+        gate: &'a ControlledGate,
+        controls: Vec<Operand<'a>>,
+        target: Operand<'a>,
+        theta: Option<Value<'a>>,
+    },
+    M {
+        qubit: Operand<'a>,
+        target: Operand<'a>,
+    },
+    Measure {
+        bases: &'a [Pauli],
+        qubits: Vec<Operand<'a>>,
+        target: Operand<'a>,
+    },
+    Call {
+        call: &'a Call,
+        args: Vec<Value<'a>>,
+    },
+    If(If<'a>),
+    Switch(Switch<'a>),
+    For(For<'a>),
+    BinaryOp {
+        op: &'a BinaryOp,
+        lhs: Value<'a>,
+        rhs: Value<'a>,
+    },
+    Comparison {
+        comparison: &'a Comparison,
+        lhs: Value<'a>,
+        rhs: Value<'a>,
+    },
+    Spanned {
+        span: &'a SourceSpan,
+        inst: Box<Instruction<'a>>,
+    },
+}
+
+/// Resolves the qubits and results that the instructions use. Elements and variables must not be
+/// added to the scopes afterwards, since a new variable could shadow an element that a name was
+/// resolved to.
+pub(crate) fn resolve<'a, Q, R>(
+    instructions: &'a [interop::Instruction],
+    qubits: &Scope<Q>,
+    results: &Scope<R>,
+) -> Vec<Instruction<'a>> {
+    Resolver { qubits, results }.instructions(instructions)
+}
+
+/// The results that the instructions measure into, including in nested blocks.
+pub(crate) fn measured_results<'a>(instructions: &[Instruction<'a>]) -> Vec<Operand<'a>> {
+    let mut targets = vec![];
+    for inst in instructions {
+        add_measured_results(&mut targets, inst);
+    }
+    targets
+}
+
+fn add_measured_results<'a>(targets: &mut Vec<Operand<'a>>, inst: &Instruction<'a>) {
+    match inst {
+        Instruction::M { target, .. } | Instruction::Measure { target, .. } => {
+            targets.push(*target);
+        }
+        Instruction::If(if_) => {
+            targets.extend(measured_results(&if_.then_insts));
+            targets.extend(measured_results(&if_.else_insts));
+        }
+        Instruction::Switch(switch) => {
+            for (_, insts) in &switch.cases {
+                targets.extend(measured_results(insts));
+            }
+            targets.extend(measured_results(&switch.default_insts));
+        }
+        Instruction::For(for_) => targets.extend(measured_results(&for_.body)),
+        Instruction::Spanned { inst, .. } => add_measured_results(targets, inst),
+        _ => {}
+    }
+}
+
+struct Resolver<'s, Q, R> {
+    qubits: &'s Scope<Q>,
+    results: &'s Scope<R>,
+}
+
+impl<Q, R> Resolver<'_, Q, R> {
+    fn instructions<'a>(&self, instructions: &'a [interop::Instruction]) -> Vec<Instruction<'a>> {
+        instructions
+            .iter()
+            .map(|inst| self.instruction(inst))
+            .collect()
+    }
+
+    fn instruction<'a>(&self, inst: &'a interop::Instruction) -> Instruction<'a> {
+        match inst {
+            interop::Instruction::Cx(inst) => {
+                self.intrinsic(Intrinsic::Cx, &[&inst.control, &inst.target])
+            }
+            interop::Instruction::Cz(inst) => {
+                self.intrinsic(Intrinsic::Cz, &[&inst.control, &inst.target])
+            }
+            interop::Instruction::Ccx(inst) => self.intrinsic(
+                Intrinsic::Ccx,
+                &[&inst.control1, &inst.control2, &inst.target],
+            ),
+            interop::Instruction::Ccz(inst) => self.intrinsic(
+                Intrinsic::Ccz,
+                &[&inst.control1, &inst.control2, &inst.target],
+            ),
+            interop::Instruction::Swap(inst) => {
+                self.intrinsic(Intrinsic::Swap, &[&inst.qubit1, &inst.qubit2])
+            }
+            interop::Instruction::H(inst) => self.intrinsic(Intrinsic::H, &[&inst.qubit]),
+            interop::Instruction::Reset(inst) => self.intrinsic(Intrinsic::Reset, &[&inst.qubit]),
+            interop::Instruction::S(inst) => self.intrinsic(Intrinsic::S, &[&inst.qubit]),
+            interop::Instruction::SAdj(inst) => self.intrinsic(Intrinsic::SAdj, &[&inst.qubit]),
+            interop::Instruction::T(inst) => self.intrinsic(Intrinsic::T, &[&inst.qubit]),
+            interop::Instruction::TAdj(inst) => self.intrinsic(Intrinsic::TAdj, &[&inst.qubit]),
+            interop::Instruction::X(inst) => self.intrinsic(Intrinsic::X, &[&inst.qubit]),
+            interop::Instruction::Y(inst) => self.intrinsic(Intrinsic::Y, &[&inst.qubit]),
+            interop::Instruction::Z(inst) => self.intrinsic(Intrinsic::Z, &[&inst.qubit]),
+            interop::Instruction::Rx(inst) => Instruction::Rotation {
+                rotation: Rotation::Rx,
+                theta: self.value(&inst.theta),
+                qubit: self.qubit(&inst.qubit),
+            },
+            interop::Instruction::Ry(inst) => Instruction::Rotation {
+                rotation: Rotation::Ry,
+                theta: self.value(&inst.theta),
+                qubit: self.qubit(&inst.qubit),
+            },
+            interop::Instruction::Rz(inst) => Instruction::Rotation {
+                rotation: Rotation::Rz,
+                theta: self.value(&inst.theta),
+                qubit: self.qubit(&inst.qubit),
+            },
+            interop::Instruction::ControlledGate(gate) => Instruction::ControlledGate {
+                gate,
+                controls: gate
+                    .controls
+                    .iter()
+                    .map(|qubit| self.qubit(qubit))
+                    .collect(),
+                target: self.qubit(&gate.target),
+                theta: match &gate.gate {
+                    Gate::Rx(theta) | Gate::Ry(theta) | Gate::Rz(theta) => Some(self.value(theta)),
+                    Gate::H | Gate::S | Gate::T | Gate::X | Gate::Y | Gate::Z => None,
+                },
+            },
+            interop::Instruction::M(inst) => Instruction::M {
+                qubit: self.qubit(&inst.qubit),
+                target: self.result(&inst.target),
+            },
+            interop::Instruction::Measure(measure) => Instruction::Measure {
+                bases: &measure.bases,
+                qubits: measure
+                    .qubits
+                    .iter()
+                    .map(|qubit| self.qubit(qubit))
+                    .collect(),
+                target: self.result(&measure.target),
+            },
+            interop::Instruction::Call(call) => Instruction::Call {
+                call,
+                args: call.args.iter().map(|arg| self.value(arg)).collect(),
+            },
+            interop::Instruction::If(if_) => Instruction::If(If {
+                condition: self.condition(&if_.condition),
+                then_insts: self.instructions(&if_.then_insts),
+                else_insts: self.instructions(&if_.else_insts),
+            }),
+            interop::Instruction::Switch(switch) => Instruction::Switch(Switch {
+                results: switch
+                    .results
+                    .iter()
+                    .map(|result| self.result(result))
+                    .collect(),
+                cases: switch
+                    .cases
+                    .iter()
+                    .map(|case| (case.value, self.instructions(&case.insts)))
+                    .collect(),
+                default_insts: self.instructions(&switch.default_insts),
+            }),
+            interop::Instruction::For(for_) => Instruction::For(For {
+                index: &for_.index,
+                count: for_.count,
+                body: self.instructions(&for_.body),
+            }),
+            interop::Instruction::BinaryOp(op) => Instruction::BinaryOp {
+                op,
+                lhs: self.value(&op.lhs),
+                rhs: self.value(&op.rhs),
+            },
+            interop::Instruction::Comparison(comparison) => Instruction::Comparison {
+                comparison,
+                lhs: self.value(&comparison.lhs),
+                rhs: self.value(&comparison.rhs),
+            },
+            interop::Instruction::Spanned(spanned) => Instruction::Spanned {
+                span: &spanned.span,
+                inst: Box::new(self.instruction(&spanned.inst)),
+            },
+        }
+    }
+
+    fn intrinsic<'a>(&self, intrinsic: Intrinsic, qubits: &[&'a Reference]) -> Instruction<'a> {
+        Instruction::Intrinsic {
+            intrinsic,
+            qubits: qubits.iter().map(|qubit| self.qubit(qubit)).collect(),
+        }
+    }
+
+    fn qubit<'a>(&self, reference: &'a Reference) -> Operand<'a> {
+        Operand {
+            reference,
+            position: self.qubits.position(reference),
+        }
+    }
+
+    fn result<'a>(&self, reference: &'a Reference) -> Operand<'a> {
+        Operand {
+            reference,
+            position: self.results.position(reference),
+        }
+    }
+
+    fn value<'a>(&self, value: &'a interop::Value) -> Value<'a> {
+        match value {
+            interop::Value::Integer(value) => Value::Integer(value),
+            interop::Value::Double(value) => Value::Double(*value),
+            interop::Value::Qubit(qubit) => Value::Qubit(self.qubit(qubit)),
+            interop::Value::Result(result) => Value::Result(self.result(result)),
+            interop::Value::String(value) => Value::String(value),
+            interop::Value::Variable(name) => Value::Variable(name),
+        }
+    }
+
+    fn condition<'a>(&self, condition: &'a interop::Condition) -> Condition<'a> {
+        let conditions = |conditions: &'a [interop::Condition]| {
+            conditions
+                .iter()
+                .map(|condition| self.condition(condition))
+                .collect()
+        };
+
+        match condition {
+            interop::Condition::Result(result) => Condition::Result(self.result(result)),
+            interop::Condition::Variable(name) => Condition::Variable(name),
+            interop::Condition::Not(condition) => {
+                Condition::Not(Box::new(self.condition(condition)))
+            }
+            interop::Condition::And(operands) => Condition::And(conditions(operands)),
+            interop::Condition::Or(operands) => Condition::Or(conditions(operands)),
+            interop::Condition::Xor(operands) => Condition::Xor(conditions(operands)),
+        }
+    }
+}
@@ -28,6 +28,15 @@ pub(crate) fn equal<'a>(
         .into_int_value()
 }
 
+/// Whether the result is one, as an `i1`.
+pub(crate) fn is_one<'a>(generator: &CodeGenerator<'a>, result: PointerValue<'a>) -> IntValue<'a> {
+    if generator.use_static_result_alloc {
+        read_result(generator, result)
+    } else {
+        equal(generator, result, get_one(generator))
+    }
+}
+
 pub(crate) fn read_result<'a>(
     generator: &CodeGenerator<'a>,
     result: PointerValue<'a>,
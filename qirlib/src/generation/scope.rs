@@ -0,0 +1,227 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::generation::interop::Reference;
+use std::collections::{hash_map::Entry, HashMap};
+
+/// Why a reference could not be resolved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Unresolved {
+    /// Nothing in scope has the reference.
+    Unknown,
+
+    /// The reference is a name that more than one register element has.
+    Ambiguous,
+}
+
+/// The qubits or results in scope in a function, looked up by `Reference`. Register elements are
+/// stored by register and index, so elements of different registers never collide. A register
+/// element can also be referred to by its name, unless another element has the same name.
+///
+/// Values are kept in the order they were added, so that iterating over them is deterministic.
+/// References resolve to a position in that order, so looking up a value never copies its key.
+///
+/// The operands of a function body can be resolved to positions before it is emitted, so that
+/// looking up an operand during emission only indexes into the values. Positions stay valid as
+/// values are added and replaced.
+pub(crate) struct Scope<V> {
+    values: Vec<V>,
+
+    /// The key of the value at each position in `values`.
+    references: Vec<Reference>,
+
+    /// The position in `values` of the value of each key.
+    keys: HashMap<Reference, usize>,
+
+    /// The position of the element that each register element name refers to, or `None` if the
+    /// name is shared by more than one element.
+    names: HashMap<String, Option<usize>>,
+}
+
+impl<V> Scope<V> {
+    pub(crate) fn new() -> Self {
+        Scope {
+            values: Vec::new(),
+            references: Vec::new(),
+            keys: HashMap::new(),
+            names: HashMap::new(),
+        }
+    }
+
+    /// Adds the element at `index` in `register`. Returns false if the element was already in
+    /// scope.
+    pub(crate) fn insert_element(&mut self, register: &str, index: u64, value: V) -> bool {
+        let reference = Reference::Indexed {
+            register: register.to_owned(),
+            index,
+        };
+
+        let name = reference.flat_name();
+        let len = self.values.len();
+        let position = self.insert(reference, value);
+        match self.names.entry(name) {
+            Entry::Vacant(entry) => {
+                entry.insert(Some(position));
+            }
+            Entry::Occupied(mut entry) => {
+                if *entry.get() != Some(position) {
+                    entry.insert(None);
+                }
+            }
+        }
+
+        position == len
+    }
+
+    /// Adds a variable, which takes precedence over register elements with the same name.
+    pub(crate) fn insert_variable(&mut self, name: String, value: V) {
+        self.insert(Reference::Named(name), value);
+    }
+
+    /// Sets the value of the key, returning its position.
+    fn insert(&mut self, key: Reference, value: V) -> usize {
+        match self.keys.entry(key) {
+            Entry::Vacant(entry) => {
+                let position = self.values.len();
+                self.references.push(entry.key().clone());
+                entry.insert(position);
+                self.values.push(value);
+                position
+            }
+            Entry::Occupied(entry) => {
+                self.values[*entry.get()] = value;
+                *entry.get()
+            }
+        }
+    }
+
+    /// Gets the position of the value of the reference.
+    pub(crate) fn position(&self, reference: &Reference) -> Result<usize, Unresolved> {
+        if let Some(&position) = self.keys.get(reference) {
+            return Ok(position);
+        }
+
+        match reference {
+            Reference::Indexed { .. } => Err(Unresolved::Unknown),
+            Reference::Named(name) => match self.names.get(name) {
+                None => Err(Unresolved::Unknown),
+                Some(None) => Err(Unresolved::Ambiguous),
+                Some(&Some(position)) => Ok(position),
+            },
+        }
+    }
+
+    /// Gets the key that the value of the reference is stored under.
+    pub(crate) fn resolve(&self, reference: &Reference) -> Result<Reference, Unresolved> {
+        let position = self.position(reference)?;
+        Ok(self.references[position].clone())
+    }
+
+    pub(crate) fn get(&self, reference: &Reference) -> Result<&V, Unresolved> {
+        let position = self.position(reference)?;
+        Ok(&self.values[position])
+    }
+
+    /// Gets the value at a position returned by `position`.
+    pub(crate) fn at(&self, position: usize) -> &V {
+        &self.values[position]
+    }
+
+    /// Gets the value at a position returned by `position`.
+    pub(crate) fn at_mut(&mut self, position: usize) -> &mut V {
+        &mut self.values[position]
+    }
+
+    /// The values in the order that they were added.
+    pub(crate) fn values(&self) -> impl Iterator<Item = &V> {
+        self.values.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Scope, Unresolved};
+    use crate::generation::interop::Reference;
+
+    #[test]
+    fn colliding_names_are_ambiguous() {
+        let mut scope = Scope::new();
+        scope.insert_element("q", 11, 1);
+        scope.insert_element("q1", 1, 2);
+
+        assert_eq!(
+            scope.get(&Reference::Named("q11".to_string())),
+            Err(Unresolved::Ambiguous)
+        );
+        assert_eq!(scope.get(&Reference::indexed("q".to_string(), 11)), Ok(&1));
+        assert_eq!(scope.get(&Reference::indexed("q1".to_string(), 1)), Ok(&2));
+    }
+
+    #[test]
+    fn unique_names_resolve_to_elements() {
+        let mut scope = Scope::new();
+        scope.insert_element("q", 0, 1);
+        scope.insert_element("q", 1, 2);
+
+        assert_eq!(scope.get(&Reference::Named("q1".to_string())), Ok(&2));
+        assert_eq!(
+            scope.get(&Reference::Named("q2".to_string())),
+            Err(Unresolved::Unknown)
+        );
+    }
+
+    #[test]
+    fn values_are_in_insertion_order() {
+        let mut scope = Scope::new();
+        for index in (0..32).rev() {
+            scope.insert_element("q", index, index);
+        }
+        scope.insert_element("q", 7, 100);
+
+        let mut expected: Vec<_> = (0..32).rev().collect();
+        expected[24] = 100;
+        assert_eq!(scope.values().copied().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn replaced_elements_keep_their_names() {
+        let mut scope = Scope::new();
+        assert!(scope.insert_element("q", 0, 1));
+        assert!(!scope.insert_element("q", 0, 2));
+
+        assert_eq!(scope.get(&Reference::Named("q0".to_string())), Ok(&2));
+        assert_eq!(
+            scope.resolve(&Reference::Named("q0".to_string())),
+            Ok(Reference::indexed("q".to_string(), 0))
+        );
+    }
+
+    #[test]
+    fn positions_outlive_new_values() {
+        let mut scope = Scope::new();
+        scope.insert_element("q", 0, 1);
+        scope.insert_element("q", 1, 2);
+
+        let position = scope.position(&Reference::Named("q1".to_string()));
+        assert_eq!(position, Ok(1));
+        assert_eq!(
+            scope.position(&Reference::Named("q2".to_string())),
+            Err(Unresolved::Unknown)
+        );
+
+        scope.insert_element("q", 2, 3);
+        *scope.at_mut(1) = 4;
+        assert_eq!(scope.at(1), &4);
+        assert_eq!(scope.values().copied().collect::<Vec<_>>(), vec![1, 4, 3]);
+    }
+
+    #[test]
+    fn variables_take_precedence() {
+        let mut scope = Scope::new();
+        scope.insert_element("q", 0, 1);
+        scope.insert_variable("q0".to_string(), 2);
+
+        assert_eq!(scope.get(&Reference::Named("q0".to_string())), Ok(&2));
+        assert_eq!(scope.get(&Reference::indexed("q".to_string(), 0)), Ok(&1));
+    }
+}
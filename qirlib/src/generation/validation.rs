@@ -0,0 +1,1454 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::generation::{
+    error::ValidationError,
+    interop::{
+        BinaryKind, BinaryOp, Call, ClassicalRegister, Comparison, Condition, Controlled,
+        ControlledGate, DoublyControlled, EntryPoint, For, FunctionType, Gate, Instruction,
+        Measure, Parameter, QuantumRegister, Reference, ReturnType, SemanticModel, Subroutine,
+        Switch, Value, ValueType,
+    },
+    qir,
+    scope::{Scope, Unresolved},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    iter, mem, slice,
+};
+
+pub(crate) fn validate(model: &SemanticModel) -> Result<(), Vec<ValidationError>> {
+    let mut validator = Validator {
+        model,
+        functions: HashMap::new(),
+        qubits: Scope::new(),
+        results: Scope::new(),
+        variables: HashMap::new(),
+        errors: Vec::new(),
+    };
+
+    validator.check_registers(&model.registers, &model.qubits);
+    validator.check_functions();
+    validator.check_allocation();
+    validator.check_entry_point_params(&model.params);
+    for (index, inst) in model.instructions.iter().enumerate() {
+        validator.check_instruction(index, inst);
+    }
+    for subroutine in &model.subroutines {
+        validator.check_subroutine(subroutine);
+    }
+    for entry_point in &model.entry_points {
+        validator.check_entry_point(entry_point);
+    }
+
+    if validator.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(validator.errors)
+    }
+}
+
+struct Validator<'a> {
+    model: &'a SemanticModel,
+    functions: HashMap<String, FunctionType>,
+    qubits: Scope<()>,
+    results: Scope<()>,
+    variables: HashMap<String, ValueType>,
+    errors: Vec<ValidationError>,
+}
+
+impl Validator<'_> {
+    fn check_registers(&mut self, registers: &[ClassicalRegister], qubits: &[QuantumRegister]) {
+        let mut names = HashSet::new();
+        for register in registers {
+            if !names.insert(&register.name) {
+                self.errors
+                    .push(ValidationError::DuplicateRegister(register.name.clone()));
+            }
+
+            for index in 0..register.size {
+                self.results.insert_element(&register.name, index, ());
+            }
+        }
+
+        for qubit in qubits {
+            if !self.qubits.insert_element(&qubit.name, qubit.index, ()) {
+                let name = format!("{}{}", qubit.name, qubit.index);
+                self.errors.push(ValidationError::DuplicateRegister(name));
+            }
+        }
+    }
+
+    fn check_allocation(&mut self) {
+        if let Some(profile) = self.model.profile {
+            if !profile.allows_dynamic_alloc()
+                && !(self.model.use_static_qubit_alloc && self.model.use_static_result_alloc)
+            {
+                self.errors
+                    .push(ValidationError::DynamicAllocation(profile));
+            }
+        }
+    }
+
+    fn check_functions(&mut self) {
+        self.functions = self.model.external_functions.clone();
+        for subroutine in &self.model.subroutines {
+            if self
+                .functions
+                .insert(subroutine.name.clone(), subroutine.function_type())
+                .is_some()
+            {
+                self.errors
+                    .push(ValidationError::DuplicateFunction(subroutine.name.clone()));
+            }
+        }
+
+        let main = self.model.entry_point_name.as_str();
+        if self.functions.contains_key(main) {
+            self.errors
+                .push(ValidationError::DuplicateFunction(main.to_string()));
+        }
+
+        let mut entry_points = HashSet::new();
+        entry_points.insert(main);
+        for entry_point in &self.model.entry_points {
+            if self.functions.contains_key(&entry_point.name)
+                || !entry_points.insert(entry_point.name.as_str())
+            {
+                self.errors
+                    .push(ValidationError::DuplicateFunction(entry_point.name.clone()));
+            }
+        }
+
+        if self.model.interop_wrappers {
+            if !self.model.use_static_result_alloc {
+                self.errors.push(ValidationError::InteropDynamicResults);
+            }
+
+            let wrappers: Vec<_> = iter::once(main)
+                .chain(self.model.entry_points.iter().map(|e| e.name.as_str()))
+                .map(qir::interop_function_name)
+                .collect();
+            for wrapper in wrappers {
+                if self.functions.contains_key(&wrapper) || entry_points.contains(wrapper.as_str())
+                {
+                    self.errors
+                        .push(ValidationError::DuplicateFunction(wrapper));
+                }
+            }
+        }
+    }
+
+    /// Checks the body of a subroutine, where only the subroutine's parameters are in scope.
+    /// Problems are reported with the index of the instruction in the body.
+    fn check_subroutine(&mut self, subroutine: &Subroutine) {
+        let errors = mem::take(&mut self.errors);
+        let variables = mem::take(&mut self.variables);
+        let mut qubits = Scope::new();
+        let mut results = Scope::new();
+        for param in &subroutine.params {
+            match param.ty {
+                ValueType::Qubit => qubits.insert_variable(param.name.clone(), ()),
+                ValueType::Result => results.insert_variable(param.name.clone(), ()),
+                ValueType::Integer { .. } | ValueType::Double | ValueType::String => {}
+            }
+        }
+        let qubits = mem::replace(&mut self.qubits, qubits);
+        let results = mem::replace(&mut self.results, results);
+
+        self.define_params(&subroutine.params);
+        for (index, inst) in subroutine.instructions.iter().enumerate() {
+            self.check_instruction(index, inst);
+            if !self.model.use_static_result_alloc {
+                self.check_measured_params(index, inst);
+            }
+        }
+
+        self.variables = variables;
+        self.qubits = qubits;
+        self.results = results;
+        let subroutine_errors = mem::replace(&mut self.errors, errors);
+        self.push_function_errors(&subroutine.name, subroutine_errors);
+    }
+
+    /// Checks that a subroutine instruction does not measure into a result parameter. The only
+    /// results in scope in a subroutine are its parameters.
+    fn check_measured_params(&mut self, index: usize, inst: &Instruction) {
+        for target in qir::instructions::measured_results(slice::from_ref(inst)) {
+            if self.results.resolve(target).is_ok() {
+                self.errors.push(ValidationError::MeasureParameter {
+                    index,
+                    result: target.to_string(),
+                });
+            }
+        }
+    }
+
+    /// Checks an additional entry point, where only its own registers are in scope. Problems are
+    /// reported with the index of the instruction in the entry point.
+    fn check_entry_point(&mut self, entry_point: &EntryPoint) {
+        let errors = mem::take(&mut self.errors);
+        let variables = mem::take(&mut self.variables);
+        let qubits = mem::replace(&mut self.qubits, Scope::new());
+        let results = mem::replace(&mut self.results, Scope::new());
+
+        self.check_registers(&entry_point.registers, &entry_point.qubits);
+        self.check_entry_point_params(&entry_point.params);
+        for (index, inst) in entry_point.instructions.iter().enumerate() {
+            self.check_instruction(index, inst);
+        }
+
+        self.variables = variables;
+        self.qubits = qubits;
+        self.results = results;
+        let entry_point_errors = mem::replace(&mut self.errors, errors);
+        self.push_function_errors(&entry_point.name, entry_point_errors);
+    }
+
+    fn check_entry_point_params(&mut self, params: &[Parameter]) {
+        for param in params {
+            if param.ty != ValueType::Double {
+                self.errors
+                    .push(ValidationError::EntryPointParameterType(param.name.clone()));
+            }
+        }
+
+        self.define_params(params);
+    }
+
+    fn define_params(&mut self, params: &[Parameter]) {
+        for param in params {
+            if self
+                .variables
+                .insert(param.name.clone(), param.ty)
+                .is_some()
+            {
+                self.errors
+                    .push(ValidationError::DuplicateParameter(param.name.clone()));
+            }
+        }
+    }
+
+    fn push_function_errors(&mut self, name: &str, errors: Vec<ValidationError>) {
+        self.errors
+            .extend(errors.into_iter().map(|error| ValidationError::InFunction {
+                name: name.to_string(),
+                error: Box::new(error),
+            }));
+    }
+
+    fn check_instruction(&mut self, index: usize, inst: &Instruction) {
+        if let Some(profile) = self.model.profile {
+            if !profile.allows(inst) {
+                self.errors
+                    .push(ValidationError::NotInProfile { index, profile });
+            }
+        }
+
+        match inst {
+            Instruction::Cx(controlled) | Instruction::Cz(controlled) => {
+                self.check_controlled(index, controlled);
+            }
+            Instruction::Ccx(controlled) | Instruction::Ccz(controlled) => {
+                self.check_doubly_controlled(index, controlled);
+            }
+            Instruction::Swap(swapped) => {
+                if self.qubit_key(index, &swapped.qubit1) == self.qubit_key(index, &swapped.qubit2)
+                {
+                    self.errors.push(ValidationError::DuplicateQubit {
+                        index,
+                        qubit: swapped.qubit1.to_string(),
+                    });
+                }
+            }
+            Instruction::Rx(rotated) | Instruction::Ry(rotated) | Instruction::Rz(rotated) => {
+                self.check_angle(index, &rotated.theta);
+                self.qubit_key(index, &rotated.qubit);
+            }
+            Instruction::ControlledGate(gate) => self.check_controlled_gate(index, gate),
+            Instruction::M(measured) => {
+                self.qubit_key(index, &measured.qubit);
+                self.check_result(index, &measured.target);
+            }
+            Instruction::Measure(measure) => self.check_measure(index, measure),
+            Instruction::Call(call) => self.check_call(index, call),
+            Instruction::If(if_) => {
+                self.check_condition(index, &if_.condition);
+                self.check_block(index, &if_.then_insts);
+                self.check_block(index, &if_.else_insts);
+            }
+            Instruction::Switch(switch) => self.check_switch(index, switch),
+            Instruction::For(for_) => self.check_for(index, for_),
+            Instruction::BinaryOp(op) => self.check_binary_op(index, op),
+            Instruction::Comparison(comparison) => self.check_comparison(index, comparison),
+            Instruction::Spanned(spanned) => self.check_instruction(index, &spanned.inst),
+            Instruction::H(single)
+            | Instruction::S(single)
+            | Instruction::SAdj(single)
+            | Instruction::T(single)
+            | Instruction::TAdj(single)
+            | Instruction::X(single)
+            | Instruction::Y(single)
+            | Instruction::Z(single)
+            | Instruction::Reset(single) => {
+                self.qubit_key(index, &single.qubit);
+            }
+        }
+    }
+
+    /// Gets the key that a qubit reference resolves to, so that references to the same qubit
+    /// compare equal, and reports the reference if it is unknown or ambiguous.
+    fn qubit_key(&mut self, index: usize, qubit: &Reference) -> Reference {
+        match self.qubits.resolve(qubit) {
+            Ok(key) => key,
+            Err(Unresolved::Unknown) => {
+                self.errors.push(ValidationError::UndeclaredQubit {
+                    index,
+                    qubit: qubit.to_string(),
+                });
+                qubit.clone()
+            }
+            Err(Unresolved::Ambiguous) => {
+                self.errors.push(ValidationError::AmbiguousReference {
+                    index,
+                    name: qubit.to_string(),
+                });
+                qubit.clone()
+            }
+        }
+    }
+
+    fn check_controlled(&mut self, index: usize, controlled: &Controlled) {
+        if self.qubit_key(index, &controlled.control) == self.qubit_key(index, &controlled.target) {
+            self.errors.push(ValidationError::SameControlAndTarget {
+                index,
+                qubit: controlled.control.to_string(),
+            });
+        }
+    }
+
+    fn check_doubly_controlled(&mut self, index: usize, controlled: &DoublyControlled) {
+        let control1 = self.qubit_key(index, &controlled.control1);
+        let control2 = self.qubit_key(index, &controlled.control2);
+        let target = self.qubit_key(index, &controlled.target);
+        if control1 == control2 {
+            self.errors.push(ValidationError::DuplicateQubit {
+                index,
+                qubit: controlled.control1.to_string(),
+            });
+        }
+
+        for (control, key) in [
+            (&controlled.control1, control1),
+            (&controlled.control2, control2),
+        ] {
+            if key == target {
+                self.errors.push(ValidationError::SameControlAndTarget {
+                    index,
+                    qubit: control.to_string(),
+                });
+            }
+        }
+    }
+
+    fn check_controlled_gate(&mut self, index: usize, gate: &ControlledGate) {
+        let target = self.qubit_key(index, &gate.target);
+        let mut seen = HashSet::new();
+        for control in &gate.controls {
+            let key = self.qubit_key(index, control);
+            if !seen.insert(key.clone()) {
+                self.errors.push(ValidationError::DuplicateQubit {
+                    index,
+                    qubit: control.to_string(),
+                });
+            } else if key == target {
+                self.errors.push(ValidationError::SameControlAndTarget {
+                    index,
+                    qubit: control.to_string(),
+                });
+            }
+        }
+
+        match &gate.gate {
+            Gate::Rx(theta) | Gate::Ry(theta) | Gate::Rz(theta) => self.check_angle(index, theta),
+            Gate::H | Gate::S | Gate::T | Gate::X | Gate::Y | Gate::Z => {}
+        }
+    }
+
+    fn check_measure(&mut self, index: usize, measure: &Measure) {
+        if measure.bases.len() != measure.qubits.len() {
+            self.errors.push(ValidationError::BasisCount {
+                index,
+                bases: measure.bases.len(),
+                qubits: measure.qubits.len(),
+            });
+        }
+
+        let mut seen = HashSet::new();
+        for qubit in &measure.qubits {
+            if !seen.insert(self.qubit_key(index, qubit)) {
+                self.errors.push(ValidationError::DuplicateQubit {
+                    index,
+                    qubit: qubit.to_string(),
+                });
+            }
+        }
+
+        self.check_result(index, &measure.target);
+        if self.model.use_static_result_alloc {
+            self.errors.push(ValidationError::StaticMeasure {
+                index,
+                result: measure.target.to_string(),
+            });
+        }
+    }
+
+    fn check_angle(&mut self, index: usize, theta: &Value) {
+        self.check_value(index, theta);
+        match *theta {
+            Value::Double(theta) if !theta.is_finite() => {
+                self.errors
+                    .push(ValidationError::NonFiniteAngle { index, theta });
+            }
+            // Undeclared variables have already been reported.
+            _ if self
+                .value_type(theta)
+                .map_or(false, |ty| ty != ValueType::Double) =>
+            {
+                self.errors.push(ValidationError::AngleType { index });
+            }
+            _ => {}
+        }
+    }
+
+    fn check_result(&mut self, index: usize, result: &Reference) {
+        match self.results.resolve(result) {
+            Ok(_) => {}
+            Err(Unresolved::Unknown) => self.errors.push(ValidationError::UndeclaredResult {
+                index,
+                result: result.to_string(),
+            }),
+            Err(Unresolved::Ambiguous) => {
+                self.errors.push(ValidationError::AmbiguousReference {
+                    index,
+                    name: result.to_string(),
+                });
+            }
+        }
+    }
+
+    /// Checks a nested block of instructions. Variables defined inside the block are not visible
+    /// after it.
+    fn check_block(&mut self, index: usize, insts: &[Instruction]) {
+        let variables = self.variables.clone();
+        for inst in insts {
+            self.check_instruction(index, inst);
+        }
+        self.variables = variables;
+    }
+
+    fn check_switch(&mut self, index: usize, switch: &Switch) {
+        for result in &switch.results {
+            self.check_result(index, result);
+        }
+
+        let width = switch.results.len();
+        if width > 64 {
+            self.errors
+                .push(ValidationError::SwitchTooWide { index, width });
+        }
+
+        let mut values = HashSet::new();
+        for case in &switch.cases {
+            if width < 64 && case.value >> width != 0 {
+                self.errors.push(ValidationError::UnreachableCase {
+                    index,
+                    value: case.value,
+                });
+            }
+            if !values.insert(case.value) {
+                self.errors.push(ValidationError::DuplicateCase {
+                    index,
+                    value: case.value,
+                });
+            }
+            self.check_block(index, &case.insts);
+        }
+
+        self.check_block(index, &switch.default_insts);
+    }
+
+    fn check_for(&mut self, index: usize, for_: &For) {
+        let variables = self.variables.clone();
+        self.define_variable(index, &for_.index, ValueType::Integer { width: 64 });
+        for inst in &for_.body {
+            self.check_instruction(index, inst);
+        }
+        self.variables = variables;
+    }
+
+    fn check_condition(&mut self, index: usize, condition: &Condition) {
+        match condition {
+            Condition::Result(result) => self.check_result(index, result),
+            Condition::Variable(name) => match self.variables.get(name) {
+                None => self.errors.push(ValidationError::UndeclaredVariable {
+                    index,
+                    name: name.clone(),
+                }),
+                Some(ValueType::Integer { width: 1 } | ValueType::Result) => {}
+                Some(_) => self.errors.push(ValidationError::ConditionType {
+                    index,
+                    name: name.clone(),
+                }),
+            },
+            Condition::Not(condition) => self.check_condition(index, condition),
+            Condition::And(conditions) | Condition::Or(conditions) | Condition::Xor(conditions) => {
+                for condition in conditions {
+                    self.check_condition(index, condition);
+                }
+            }
+        }
+    }
+
+    fn check_binary_op(&mut self, index: usize, op: &BinaryOp) {
+        self.check_value(index, &op.lhs);
+        self.check_value(index, &op.rhs);
+        let lhs = self.value_type(&op.lhs);
+        let rhs = self.value_type(&op.rhs);
+
+        // Undeclared operands have already been reported.
+        if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
+            let valid = lhs == rhs
+                && match (op.kind, lhs) {
+                    (_, ValueType::Integer { .. })
+                    | (BinaryKind::Add | BinaryKind::Sub | BinaryKind::Mul, ValueType::Double) => {
+                        true
+                    }
+                    _ => false,
+                };
+
+            if !valid {
+                self.errors.push(ValidationError::OperandType {
+                    index,
+                    name: op.result.clone(),
+                });
+            }
+        }
+
+        if let Some(ty) = lhs.or(rhs) {
+            self.define_variable(index, &op.result, ty);
+        }
+    }
+
+    fn check_comparison(&mut self, index: usize, comparison: &Comparison) {
+        self.check_value(index, &comparison.lhs);
+        self.check_value(index, &comparison.rhs);
+        let lhs = self.value_type(&comparison.lhs);
+        let rhs = self.value_type(&comparison.rhs);
+
+        if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
+            let comparable = match lhs {
+                ValueType::Integer { .. } => true,
+                ValueType::Double => !comparison.kind.is_unsigned(),
+                ValueType::Qubit | ValueType::Result | ValueType::String => false,
+            };
+            if lhs != rhs || !comparable {
+                self.errors.push(ValidationError::OperandType {
+                    index,
+                    name: comparison.result.clone(),
+                });
+            }
+        }
+
+        self.define_variable(index, &comparison.result, ValueType::Integer { width: 1 });
+    }
+
+    fn define_variable(&mut self, index: usize, name: &str, ty: ValueType) {
+        if self.variables.insert(name.to_owned(), ty).is_some() {
+            self.errors.push(ValidationError::DuplicateVariable {
+                index,
+                name: name.to_owned(),
+            });
+        }
+    }
+
+    fn check_value(&mut self, index: usize, value: &Value) {
+        match value {
+            Value::Result(result) => self.check_result(index, result),
+            Value::Variable(name) => {
+                if !self.variables.contains_key(name) {
+                    self.errors.push(ValidationError::UndeclaredVariable {
+                        index,
+                        name: name.clone(),
+                    });
+                }
+            }
+            Value::Qubit(qubit) => {
+                self.qubit_key(index, qubit);
+            }
+            Value::String(value) => {
+                if value.contains('\0') {
+                    self.errors.push(ValidationError::NulInString { index });
+                }
+            }
+            Value::Integer(_) | Value::Double(_) => {}
+        }
+    }
+
+    fn check_call(&mut self, index: usize, call: &Call) {
+        let ty = if let Some(ty) = self.functions.get(&call.name) {
+            ty.clone()
+        } else {
+            self.errors.push(ValidationError::UndeclaredFunction {
+                index,
+                name: call.name.clone(),
+            });
+            return;
+        };
+
+        if ty.param_types.len() == call.args.len() {
+            for (position, (arg, &expected)) in call.args.iter().zip(&ty.param_types).enumerate() {
+                self.check_value(index, arg);
+
+                // Undeclared variables have already been reported.
+                if self.value_type(arg).map_or(false, |ty| ty != expected) {
+                    self.errors.push(ValidationError::ArgumentType {
+                        index,
+                        name: call.name.clone(),
+                        position,
+                        expected,
+                    });
+                }
+            }
+        } else {
+            self.errors.push(ValidationError::ArgumentCount {
+                index,
+                name: call.name.clone(),
+                expected: ty.param_types.len(),
+                actual: call.args.len(),
+            });
+        }
+
+        if let Some(result) = &call.result {
+            match &ty.return_type {
+                ReturnType::Void => self.errors.push(ValidationError::VoidReturn {
+                    index,
+                    name: call.name.clone(),
+                }),
+                ReturnType::Value(ty) => self.define_variable(index, result, *ty),
+            }
+        }
+    }
+
+    fn value_type(&self, value: &Value) -> Option<ValueType> {
+        match value {
+            Value::Integer(value) => Some(ValueType::Integer {
+                width: value.width(),
+            }),
+            Value::Double(_) => Some(ValueType::Double),
+            Value::Qubit(_) => Some(ValueType::Qubit),
+            Value::Result(_) => Some(ValueType::Result),
+            Value::String(_) => Some(ValueType::String),
+            Value::Variable(name) => self.variables.get(name).copied(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generation::{
+        error::ValidationError,
+        interop::{
+            BinaryKind, BinaryOp, Call, ClassicalRegister, Comparison, ComparisonKind, Condition,
+            Controlled, ControlledGate, DoublyControlled, EntryPoint, For, FunctionType, Gate, If,
+            Instruction, IntegerValue, Measure, Measured, Parameter, Pauli, Profile,
+            QuantumRegister, Reference, ReturnType, Rotated, SemanticModel, Single, Subroutine,
+            Swapped, Switch, SwitchCase, Value, ValueType,
+        },
+    };
+    use std::collections::HashMap;
+
+    fn get_model(instructions: Vec<Instruction>) -> SemanticModel {
+        let mut external_functions = HashMap::new();
+        external_functions.insert(
+            "f".to_string(),
+            FunctionType {
+                param_types: vec![ValueType::Integer { width: 64 }, ValueType::Qubit],
+                return_type: ReturnType::Void,
+            },
+        );
+        external_functions.insert(
+            "g".to_string(),
+            FunctionType {
+                param_types: vec![],
+                return_type: ReturnType::Value(ValueType::Integer { width: 64 }),
+            },
+        );
+
+        SemanticModel {
+            registers: vec![ClassicalRegister::new("r".to_string(), 2)],
+            qubits: vec![
+                QuantumRegister::new("q".to_string(), 0),
+                QuantumRegister::new("q".to_string(), 1),
+            ],
+            instructions,
+            use_static_qubit_alloc: true,
+            external_functions,
+            ..SemanticModel::new("test".to_string())
+        }
+    }
+
+    #[test]
+    fn valid_model_passes() {
+        let model = get_model(vec![
+            Instruction::H(Single::new("q0".to_string())),
+            Instruction::Cx(Controlled::new("q0".to_string(), "q1".to_string())),
+            Instruction::Rx(Rotated::new(Value::Double(1.5), "q1".to_string())),
+            Instruction::M(Measured::new("q0".to_string(), "r1".to_string())),
+            Instruction::Call(Call {
+                name: "f".to_string(),
+                args: vec![
+                    Value::Integer(IntegerValue::new(64, 3).unwrap()),
+                    Value::Qubit("q1".into()),
+                ],
+                result: None,
+            }),
+        ]);
+
+        assert_eq!(model.validate(), Ok(()));
+    }
+
+    #[test]
+    fn all_problems_are_reported_with_indices() {
+        let mut model = get_model(vec![
+            Instruction::Cz(Controlled::new("q1".to_string(), "q1".to_string())),
+            Instruction::Ry(Rotated::new(Value::Double(f64::INFINITY), "q0".to_string())),
+            Instruction::If(If {
+                condition: Condition::Result("r0".into()),
+                then_insts: vec![Instruction::M(Measured::new(
+                    "q0".to_string(),
+                    "r2".to_string(),
+                ))],
+                else_insts: vec![],
+            }),
+            Instruction::Call(Call {
+                name: "h".to_string(),
+                args: vec![],
+                result: None,
+            }),
+            Instruction::Call(Call {
+                name: "f".to_string(),
+                args: vec![],
+                result: None,
+            }),
+            Instruction::Call(Call {
+                name: "f".to_string(),
+                args: vec![Value::Double(1.0), Value::Qubit("q0".into())],
+                result: None,
+            }),
+        ]);
+        model
+            .registers
+            .push(ClassicalRegister::new("r".to_string(), 1));
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![
+                ValidationError::DuplicateRegister("r".to_string()),
+                ValidationError::SameControlAndTarget {
+                    index: 0,
+                    qubit: "q1".to_string()
+                },
+                ValidationError::NonFiniteAngle {
+                    index: 1,
+                    theta: f64::INFINITY
+                },
+                ValidationError::UndeclaredResult {
+                    index: 2,
+                    result: "r2".to_string()
+                },
+                ValidationError::UndeclaredFunction {
+                    index: 3,
+                    name: "h".to_string()
+                },
+                ValidationError::ArgumentCount {
+                    index: 4,
+                    name: "f".to_string(),
+                    expected: 2,
+                    actual: 0
+                },
+                ValidationError::ArgumentType {
+                    index: 5,
+                    name: "f".to_string(),
+                    position: 0,
+                    expected: ValueType::Integer { width: 64 }
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn strings_are_checked() {
+        let mut model = get_model(vec![]);
+        model.external_functions.insert(
+            "message".to_string(),
+            FunctionType {
+                param_types: vec![ValueType::String],
+                return_type: ReturnType::Void,
+            },
+        );
+        let call = |arg| {
+            Instruction::Call(Call {
+                name: "message".to_string(),
+                args: vec![arg],
+                result: None,
+            })
+        };
+        model.instructions = vec![
+            call(Value::String("hello".to_string())),
+            call(Value::String("a\0b".to_string())),
+            call(Value::Double(1.0)),
+        ];
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![
+                ValidationError::NulInString { index: 1 },
+                ValidationError::ArgumentType {
+                    index: 2,
+                    name: "message".to_string(),
+                    position: 0,
+                    expected: ValueType::String
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn nan_angle_is_reported() {
+        let model = get_model(vec![Instruction::Rz(Rotated::new(
+            Value::Double(f64::NAN),
+            "q0".to_string(),
+        ))]);
+
+        let errors = model.validate().unwrap_err();
+        assert!(matches!(
+            errors[..],
+            [ValidationError::NonFiniteAngle { index: 0, .. }]
+        ));
+    }
+
+    #[test]
+    fn loop_index_is_scoped_to_body() {
+        let call = |name: &str| {
+            Instruction::Call(Call {
+                name: "f".to_string(),
+                args: vec![Value::Variable(name.to_string()), Value::Qubit("q0".into())],
+                result: None,
+            })
+        };
+
+        let model = get_model(vec![
+            Instruction::For(For {
+                index: "i".to_string(),
+                count: 3,
+                body: vec![call("i")],
+            }),
+            call("i"),
+        ]);
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![ValidationError::UndeclaredVariable {
+                index: 1,
+                name: "i".to_string()
+            }])
+        );
+    }
+
+    #[test]
+    fn variable_problems_are_reported() {
+        let model = get_model(vec![
+            Instruction::BinaryOp(BinaryOp {
+                kind: BinaryKind::And,
+                lhs: Value::Double(1.0),
+                rhs: Value::Double(2.0),
+                result: "x".to_string(),
+            }),
+            Instruction::Comparison(Comparison {
+                kind: ComparisonKind::Eq,
+                lhs: Value::Variable("x".to_string()),
+                rhs: Value::Integer(IntegerValue::new(64, 1).unwrap()),
+                result: "x".to_string(),
+            }),
+            Instruction::If(If {
+                condition: Condition::Variable("y".to_string()),
+                then_insts: vec![],
+                else_insts: vec![],
+            }),
+        ]);
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![
+                ValidationError::OperandType {
+                    index: 0,
+                    name: "x".to_string()
+                },
+                ValidationError::OperandType {
+                    index: 1,
+                    name: "x".to_string()
+                },
+                ValidationError::DuplicateVariable {
+                    index: 1,
+                    name: "x".to_string()
+                },
+                ValidationError::UndeclaredVariable {
+                    index: 2,
+                    name: "y".to_string()
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn unsigned_comparisons_need_integers() {
+        let comparison = |kind, lhs, rhs, result: &str| {
+            Instruction::Comparison(Comparison {
+                kind,
+                lhs,
+                rhs,
+                result: result.to_string(),
+            })
+        };
+        let bit = |value| Value::Integer(IntegerValue::new(1, value).unwrap());
+        let model = get_model(vec![
+            comparison(ComparisonKind::ULt, bit(0), bit(1), "x"),
+            comparison(
+                ComparisonKind::ULt,
+                Value::Double(1.0),
+                Value::Double(2.0),
+                "y",
+            ),
+            comparison(
+                ComparisonKind::Lt,
+                Value::Double(1.0),
+                Value::Double(2.0),
+                "z",
+            ),
+        ]);
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![ValidationError::OperandType {
+                index: 1,
+                name: "y".to_string()
+            }])
+        );
+    }
+
+    #[test]
+    fn call_results_are_typed() {
+        let model = get_model(vec![
+            Instruction::Call(Call {
+                name: "g".to_string(),
+                args: vec![],
+                result: Some("x".to_string()),
+            }),
+            Instruction::Rx(Rotated::new(
+                Value::Variable("x".to_string()),
+                "q0".to_string(),
+            )),
+            Instruction::Call(Call {
+                name: "f".to_string(),
+                args: vec![Value::Variable("x".to_string()), Value::Qubit("q0".into())],
+                result: Some("y".to_string()),
+            }),
+        ]);
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![
+                ValidationError::AngleType { index: 1 },
+                ValidationError::VoidReturn {
+                    index: 2,
+                    name: "f".to_string()
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn subroutines_are_checked_in_their_own_scope() {
+        let mut model = get_model(vec![Instruction::Call(Call {
+            name: "s".to_string(),
+            args: vec![Value::Qubit("q0".into()), Value::Result("r0".into())],
+            result: None,
+        })]);
+
+        model.subroutines = vec![
+            Subroutine {
+                name: "s".to_string(),
+                params: vec![
+                    Parameter::new("a".to_string(), ValueType::Qubit),
+                    Parameter::new("p".to_string(), ValueType::Result),
+                ],
+                instructions: vec![
+                    Instruction::M(Measured::new("a".to_string(), "p".to_string())),
+                    Instruction::M(Measured::new("a".to_string(), "r0".to_string())),
+                ],
+            },
+            Subroutine {
+                name: "f".to_string(),
+                params: vec![],
+                instructions: vec![],
+            },
+        ];
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![
+                ValidationError::DuplicateFunction("f".to_string()),
+                ValidationError::InFunction {
+                    name: "s".to_string(),
+                    error: Box::new(ValidationError::UndeclaredResult {
+                        index: 1,
+                        result: "r0".to_string()
+                    })
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn dynamic_result_parameters_cannot_be_measured() {
+        let mut model = get_model(vec![]);
+        model.use_static_result_alloc = false;
+        model.subroutines = vec![Subroutine {
+            name: "s".to_string(),
+            params: vec![
+                Parameter::new("a".to_string(), ValueType::Qubit),
+                Parameter::new("p".to_string(), ValueType::Result),
+            ],
+            instructions: vec![
+                Instruction::X(Single::new("a".to_string())),
+                Instruction::If(If {
+                    condition: Condition::Result("p".into()),
+                    then_insts: vec![Instruction::M(Measured::new(
+                        "a".to_string(),
+                        "p".to_string(),
+                    ))],
+                    else_insts: vec![],
+                }),
+            ],
+        }];
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![ValidationError::InFunction {
+                name: "s".to_string(),
+                error: Box::new(ValidationError::MeasureParameter {
+                    index: 1,
+                    result: "p".to_string()
+                })
+            }])
+        );
+    }
+
+    #[test]
+    fn entry_point_name_is_unique() {
+        let mut model = get_model(vec![]);
+        model.entry_point_name = "f".to_string();
+        model.entry_points = vec![EntryPoint::new("main".to_string())];
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![ValidationError::DuplicateFunction("f".to_string())])
+        );
+    }
+
+    #[test]
+    fn interop_wrapper_names_are_unique() {
+        let mut model = get_model(vec![]);
+        model.interop_wrappers = true;
+        model.entry_points = vec![EntryPoint::new("main__Interop".to_string())];
+        assert_eq!(
+            model.validate(),
+            Err(vec![ValidationError::DuplicateFunction(
+                "main__Interop".to_string()
+            )])
+        );
+
+        model.entry_points = vec![];
+        assert_eq!(model.validate(), Ok(()));
+    }
+
+    #[test]
+    fn interop_wrappers_need_static_results() {
+        let mut model = get_model(vec![]);
+        model.interop_wrappers = true;
+        model.use_static_result_alloc = false;
+        assert_eq!(
+            model.validate(),
+            Err(vec![ValidationError::InteropDynamicResults])
+        );
+
+        model.use_static_result_alloc = true;
+        assert_eq!(model.validate(), Ok(()));
+    }
+
+    #[test]
+    fn entry_points_are_checked_with_their_own_registers() {
+        let mut model = get_model(vec![]);
+        let mut entry_point = EntryPoint::new("other".to_string());
+        entry_point.registers = vec![ClassicalRegister::new("s".to_string(), 1)];
+        entry_point.qubits = vec![QuantumRegister::new("q".to_string(), 0)];
+        entry_point.instructions = vec![
+            Instruction::M(Measured::new("q0".to_string(), "s0".to_string())),
+            Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+        ];
+        model.entry_points = vec![entry_point, EntryPoint::new("main".to_string())];
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![
+                ValidationError::DuplicateFunction("main".to_string()),
+                ValidationError::InFunction {
+                    name: "other".to_string(),
+                    error: Box::new(ValidationError::UndeclaredResult {
+                        index: 1,
+                        result: "r0".to_string()
+                    })
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn entry_point_params_must_be_doubles() {
+        let mut model = get_model(vec![Instruction::Rx(Rotated::new(
+            Value::Variable("theta".to_string()),
+            "q0".to_string(),
+        ))]);
+        model.params = vec![
+            Parameter::new("theta".to_string(), ValueType::Double),
+            Parameter::new("n".to_string(), ValueType::Integer { width: 64 }),
+        ];
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![ValidationError::EntryPointParameterType(
+                "n".to_string()
+            )])
+        );
+    }
+
+    #[test]
+    fn multi_qubit_gates_need_distinct_qubits() {
+        let model = get_model(vec![
+            Instruction::Ccx(DoublyControlled::new(
+                "q0".to_string(),
+                "q0".to_string(),
+                "q1".to_string(),
+            )),
+            Instruction::Ccz(DoublyControlled::new(
+                "q0".to_string(),
+                "q1".to_string(),
+                "q1".to_string(),
+            )),
+            Instruction::Swap(Swapped::new("q1".to_string(), "q1".to_string())),
+        ]);
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![
+                ValidationError::DuplicateQubit {
+                    index: 0,
+                    qubit: "q0".to_string()
+                },
+                ValidationError::SameControlAndTarget {
+                    index: 1,
+                    qubit: "q1".to_string()
+                },
+                ValidationError::DuplicateQubit {
+                    index: 2,
+                    qubit: "q1".to_string()
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn controlled_gate_needs_distinct_qubits_and_valid_angle() {
+        let model = get_model(vec![
+            Instruction::ControlledGate(ControlledGate {
+                gate: Gate::X,
+                adjoint: false,
+                controls: vec!["q0".into(), "q0".into()],
+                target: "q1".into(),
+            }),
+            Instruction::ControlledGate(ControlledGate {
+                gate: Gate::Rz(Value::Double(f64::NAN)),
+                adjoint: true,
+                controls: vec!["q1".into()],
+                target: "q1".into(),
+            }),
+        ]);
+
+        let errors = model.validate().unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(
+            errors[0],
+            ValidationError::DuplicateQubit {
+                index: 0,
+                qubit: "q0".to_string()
+            }
+        );
+        assert_eq!(
+            errors[1],
+            ValidationError::SameControlAndTarget {
+                index: 1,
+                qubit: "q1".to_string()
+            }
+        );
+        assert!(matches!(
+            errors[2],
+            ValidationError::NonFiniteAngle { index: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn switch_cases_are_checked() {
+        let model = get_model(vec![Instruction::Switch(Switch {
+            results: vec!["r0".into(), "r2".into()],
+            cases: vec![
+                SwitchCase {
+                    value: 3,
+                    insts: vec![Instruction::X(Single::new("q0".to_string()))],
+                },
+                SwitchCase {
+                    value: 4,
+                    insts: vec![],
+                },
+                SwitchCase {
+                    value: 3,
+                    insts: vec![],
+                },
+            ],
+            default_insts: vec![],
+        })]);
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![
+                ValidationError::UndeclaredResult {
+                    index: 0,
+                    result: "r2".to_string()
+                },
+                ValidationError::UnreachableCase { index: 0, value: 4 },
+                ValidationError::DuplicateCase { index: 0, value: 3 },
+            ])
+        );
+    }
+
+    #[test]
+    fn measurements_are_checked() {
+        let mut model = get_model(vec![Instruction::Measure(Measure {
+            bases: vec![Pauli::X, Pauli::X, Pauli::Y],
+            qubits: vec![
+                "q0".into(),
+                "q1".into(),
+                Reference::indexed("q".to_string(), 0),
+            ],
+            target: "r2".into(),
+        })]);
+        model.use_static_result_alloc = false;
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![
+                ValidationError::DuplicateQubit {
+                    index: 0,
+                    qubit: "q[0]".to_string()
+                },
+                ValidationError::UndeclaredResult {
+                    index: 0,
+                    result: "r2".to_string()
+                },
+            ])
+        );
+
+        let mut model = get_model(vec![Instruction::Measure(Measure {
+            bases: vec![Pauli::Z],
+            qubits: vec!["q0".into(), "q1".into()],
+            target: "r0".into(),
+        })]);
+        model.use_static_result_alloc = false;
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![ValidationError::BasisCount {
+                index: 0,
+                bases: 1,
+                qubits: 2
+            }])
+        );
+    }
+
+    #[test]
+    fn measure_needs_dynamic_results() {
+        let mut model = get_model(vec![Instruction::Measure(Measure {
+            bases: vec![Pauli::X],
+            qubits: vec!["q0".into()],
+            target: "r0".into(),
+        })]);
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![ValidationError::StaticMeasure {
+                index: 0,
+                result: "r0".to_string()
+            }])
+        );
+
+        model.use_static_result_alloc = false;
+        assert_eq!(model.validate(), Ok(()));
+    }
+
+    #[test]
+    fn compound_conditions_are_checked() {
+        let model = get_model(vec![Instruction::If(If {
+            condition: Condition::And(vec![
+                Condition::Result("r0".into()),
+                Condition::Not(Box::new(Condition::Or(vec![
+                    Condition::Result("r2".into()),
+                    Condition::Variable("x".to_string()),
+                ]))),
+            ]),
+            then_insts: vec![],
+            else_insts: vec![],
+        })]);
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![
+                ValidationError::UndeclaredResult {
+                    index: 0,
+                    result: "r2".to_string()
+                },
+                ValidationError::UndeclaredVariable {
+                    index: 0,
+                    name: "x".to_string()
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn base_profile_rejects_branching() {
+        let mut model = get_model(vec![
+            Instruction::H(Single::new("q0".to_string())),
+            Instruction::M(Measured::new("q0".to_string(), "r0".to_string())),
+            Instruction::If(If {
+                condition: Condition::Result("r0".into()),
+                then_insts: vec![Instruction::X(Single::new("q0".to_string()))],
+                else_insts: vec![],
+            }),
+        ]);
+        model.profile = Some(Profile::Base);
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![ValidationError::NotInProfile {
+                index: 2,
+                profile: Profile::Base
+            }])
+        );
+
+        model.profile = Some(Profile::Adaptive);
+        assert_eq!(model.validate(), Ok(()));
+    }
+
+    #[test]
+    fn base_profile_rejects_array_runtime_and_dynamic_allocation() {
+        // The __ctl function of a controlled gate takes its controls as a runtime array.
+        let mut model = get_model(vec![Instruction::ControlledGate(ControlledGate {
+            gate: Gate::X,
+            adjoint: false,
+            controls: vec!["q0".into()],
+            target: "q1".into(),
+        })]);
+        model.profile = Some(Profile::Base);
+        model.use_static_qubit_alloc = false;
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![
+                ValidationError::DynamicAllocation(Profile::Base),
+                ValidationError::NotInProfile {
+                    index: 0,
+                    profile: Profile::Base
+                },
+            ])
+        );
+
+        model.profile = Some(Profile::Adaptive);
+        assert_eq!(model.validate(), Ok(()));
+    }
+
+    #[test]
+    fn undeclared_qubits_are_reported() {
+        let model = get_model(vec![
+            Instruction::H(Single::new("q0".to_string())),
+            Instruction::X(Single::new("q7".to_string())),
+            Instruction::Cx(Controlled::new(
+                "q0".to_string(),
+                Reference::indexed("p".to_string(), 0),
+            )),
+        ]);
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![
+                ValidationError::UndeclaredQubit {
+                    index: 1,
+                    qubit: "q7".to_string()
+                },
+                ValidationError::UndeclaredQubit {
+                    index: 2,
+                    qubit: "p[0]".to_string()
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn ambiguous_names_are_reported() {
+        let mut model = get_model(vec![
+            Instruction::H(Single::new("q11".to_string())),
+            Instruction::H(Single::new(Reference::indexed("q".to_string(), 11))),
+            Instruction::Cx(Controlled::new(
+                Reference::indexed("q1".to_string(), 1),
+                "q11".to_string(),
+            )),
+        ]);
+        model.qubits = vec![
+            QuantumRegister::new("q".to_string(), 11),
+            QuantumRegister::new("q1".to_string(), 1),
+        ];
+
+        assert_eq!(
+            model.validate(),
+            Err(vec![
+                ValidationError::AmbiguousReference {
+                    index: 0,
+                    name: "q11".to_string()
+                },
+                ValidationError::AmbiguousReference {
+                    index: 2,
+                    name: "q11".to_string()
+                },
+            ])
+        );
+    }
+}
@@ -17,3 +17,6 @@ pub mod module;
 
 #[cfg(not(any(feature = "no-llvm-linking")))]
 pub mod passes;
+
+#[cfg(feature = "serde")]
+pub mod schema;
@@ -0,0 +1,212 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! A versioned schema for saving the generation and evaluation `SemanticModel`s with serde.
+//!
+//! The models serialize as plain structs and enums, so the schema follows their definitions. Wrap a
+//! model in `Versioned` to save it with the schema version, so that loading it with an
+//! incompatible version of the library fails instead of producing a different model:
+//!
+//! ```ignore
+//! let json = serde_json::to_string(&Versioned(&model))?;
+//! let Versioned(model) = serde_json::from_str::<Versioned<SemanticModel>>(&json)?;
+//! ```
+
+use serde::{de::Error, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+
+/// The version of the schema. It changes whenever a change to a model would make it serialize
+/// differently, or would make a previously saved model deserialize differently.
+pub const VERSION: u32 = 1;
+
+/// A model that is serialized together with the schema version, as `{"version": …, "model": …}`.
+/// Deserializing fails if the version is not `VERSION`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Versioned<T>(pub T);
+
+impl<T: Serialize> Serialize for Versioned<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Versioned", 2)?;
+        state.serialize_field("version", &VERSION)?;
+        state.serialize_field("model", &self.0)?;
+        state.end()
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Versioned<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "Versioned", deny_unknown_fields)]
+        struct Fields<T> {
+            version: u32,
+            model: T,
+        }
+
+        let fields = Fields::<T>::deserialize(deserializer)?;
+        if fields.version == VERSION {
+            Ok(Versioned(fields.model))
+        } else {
+            Err(D::Error::custom(format!(
+                "Unsupported schema version {}, expected {}.",
+                fields.version, VERSION
+            )))
+        }
+    }
+}
+
+/// Serializes a map with its keys in order, so that the same model always serializes the same
+/// way.
+pub(crate) fn serialize_sorted<V: Serialize, S: Serializer>(
+    map: &HashMap<String, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+#[cfg(all(test, not(any(feature = "no-llvm-linking"))))]
+mod tests {
+    use super::Versioned;
+    use crate::{
+        evaluation,
+        generation::{
+            emit,
+            interop::{
+                BinaryKind, BinaryOp, Call, ClassicalRegister, Condition, FunctionType, If,
+                Instruction, IntegerValue, Measured, Profile, QuantumRegister, ReturnType,
+                SemanticModel, Single, SourceSpan, Spanned, Value, ValueType,
+            },
+        },
+    };
+    use std::collections::HashMap;
+
+    fn get_model() -> SemanticModel {
+        let mut external_functions = HashMap::new();
+        for name in ["g", "f"] {
+            external_functions.insert(
+                name.to_string(),
+                FunctionType {
+                    param_types: vec![ValueType::Integer { width: 64 }, ValueType::Qubit],
+                    return_type: ReturnType::Void,
+                },
+            );
+        }
+
+        SemanticModel {
+            registers: vec![ClassicalRegister::new("r".to_string(), 1)],
+            qubits: vec![QuantumRegister::new("q".to_string(), 0)],
+            instructions: vec![
+                Instruction::Spanned(Spanned {
+                    span: SourceSpan {
+                        file: "test.py".to_string(),
+                        line: 3,
+                        column: None,
+                    },
+                    inst: Box::new(Instruction::H(Single::new("q0"))),
+                }),
+                Instruction::M(Measured::new("q0", "r0")),
+                Instruction::If(If {
+                    condition: Condition::Result("r0".into()),
+                    then_insts: vec![Instruction::X(Single::new("q0"))],
+                    else_insts: vec![],
+                }),
+                Instruction::BinaryOp(BinaryOp {
+                    kind: BinaryKind::Add,
+                    lhs: Value::Integer(IntegerValue::new(64, 2).unwrap()),
+                    rhs: Value::Integer(IntegerValue::new(64, 3).unwrap()),
+                    result: "x".to_string(),
+                }),
+                Instruction::Call(Call {
+                    name: "f".to_string(),
+                    args: vec![Value::Variable("x".to_string()), Value::Qubit("q0".into())],
+                    result: None,
+                }),
+            ],
+            record_output: true,
+            profile: Some(Profile::Adaptive),
+            external_functions,
+            ..SemanticModel::new("test".to_owned())
+        }
+    }
+
+    #[test]
+    fn generation_model_round_trips() -> Result<(), String> {
+        let model = get_model();
+        let json = serde_json::to_string(&Versioned(&model)).map_err(|e| e.to_string())?;
+        let Versioned(loaded): Versioned<SemanticModel> =
+            serde_json::from_str(&json).map_err(|e| e.to_string())?;
+
+        assert_eq!(loaded.instructions, model.instructions);
+        assert_eq!(loaded.profile, model.profile);
+        assert_eq!(
+            emit::bitcode(&loaded).map_err(|e| e.to_string())?,
+            emit::bitcode(&model).map_err(|e| e.to_string())?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn generation_model_serializes_deterministically() -> Result<(), String> {
+        let json = serde_json::to_string(&Versioned(get_model())).map_err(|e| e.to_string())?;
+        for _ in 0..16 {
+            assert_eq!(
+                serde_json::to_string(&Versioned(get_model())).map_err(|e| e.to_string())?,
+                json
+            );
+        }
+
+        let f = json.find(r#""f":"#).expect("f should be serialized.");
+        let g = json.find(r#""g":"#).expect("g should be serialized.");
+        assert!(f < g);
+        Ok(())
+    }
+
+    #[test]
+    fn evaluation_model_round_trips() -> Result<(), String> {
+        let mut model = evaluation::interop::SemanticModel::new("test".to_string());
+        model.add_reg(&evaluation::interop::QuantumRegister::new("q".to_string(), 0).as_register());
+        model.add_reg(
+            &evaluation::interop::ClassicalRegister::new("r".to_string(), 1).as_register(),
+        );
+        model.add_inst(evaluation::interop::Instruction::Rx(
+            evaluation::interop::Rotated::new(0.5, "q0".to_string()),
+        ));
+        model.add_inst(evaluation::interop::Instruction::M(
+            evaluation::interop::Measured::new("q0".to_string(), "r0".to_string()),
+        ));
+
+        let json = serde_json::to_string(&Versioned(&model)).map_err(|e| e.to_string())?;
+        let Versioned(loaded): Versioned<evaluation::interop::SemanticModel> =
+            serde_json::from_str(&json).map_err(|e| e.to_string())?;
+
+        assert_eq!(loaded.name, model.name);
+        assert_eq!(loaded.registers, model.registers);
+        assert_eq!(loaded.qubits, model.qubits);
+        assert_eq!(loaded.instructions, model.instructions);
+        Ok(())
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let json = serde_json::to_string(&Versioned(get_model()))
+            .unwrap()
+            .replacen(r#""version":1"#, r#""version":2"#, 1);
+
+        let error = serde_json::from_str::<Versioned<SemanticModel>>(&json)
+            .err()
+            .expect("Loading a model with another schema version should fail.");
+        assert!(error
+            .to_string()
+            .starts_with("Unsupported schema version 2, expected 1."));
+    }
+
+    #[test]
+    fn oversized_integers_are_rejected() {
+        let json = r#"{"width":1,"value":2}"#;
+        let error = serde_json::from_str::<IntegerValue>(json)
+            .err()
+            .expect("Loading an integer that doesn't fit in its width should fail.");
+        assert!(error
+            .to_string()
+            .starts_with("Value too big for 1-bit integer."));
+    }
+}